@@ -0,0 +1,355 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::NuccBinaryType;
+use crate::chunks::{load_chunks, Chunk};
+use crate::diff::{entries, keyed_entries};
+use crate::nucc_binary::{to_json, FixedString};
+
+
+/// The characode table, to show `characode_index` fields as the characode they point to (e.g. `"characode": "2nrt"`).
+/// Indices start at 1 for the first characode.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Characodes {
+    pub names: Vec<String>
+}
+
+impl Characodes {
+    /// Reads the table from a characode xfbin, the folder it was unpacked to, or its unpacked json
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let value = if path.extension().map_or(false, |ext| ext == "json") {
+            let data = fs::read(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+            serde_json::from_slice(&data).map_err(|err| format!("{}: {}", path.display(), err))?
+        } else {
+            load_chunks(path)?
+                .into_iter()
+                .find(|chunk| chunk.nucc_binary_type == NuccBinaryType::Characode)
+                .and_then(|chunk| chunk.json())
+                .ok_or_else(|| format!("{} has no Characode chunk", path.display()))?
+        };
+
+        Self::from_json(&value).ok_or_else(|| format!("{} isn't a Characode table", path.display()))
+    }
+
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let names = entries(value)?
+            .iter()
+            .map(|entry| text(&entry["characode"]))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self { names })
+    }
+
+    pub fn name(&self, index: u64) -> Option<&str> {
+        let position = (index as usize).checked_sub(1)?;
+        self.names.get(position).map(String::as_str)
+    }
+
+    pub fn index(&self, name: &str) -> Option<u64> {
+        self.names.iter().position(|existing| existing == name).map(|position| position as u64 + 1)
+    }
+}
+
+
+/// A change to the Characode table. Positions are the characode's new index, starting at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CharacodeEdit {
+    Insert { characode: String, position: u64 },
+    Remove { characode: String },
+    Move { characode: String, position: u64 }
+}
+
+/// Where every characode index went after an edit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reindex {
+    /// By old index minus 1, `None` for a removed characode
+    new_indices: Vec<Option<u64>>
+}
+
+impl Reindex {
+    /// `None` for indices that weren't in the table, `Some(None)` for the removed characode
+    pub fn get(&self, old_index: u64) -> Option<Option<u64>> {
+        let position = (old_index as usize).checked_sub(1)?;
+        self.new_indices.get(position).copied()
+    }
+}
+
+/// A characode index field that was rewritten after an edit
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Reindexed {
+    pub entry: String,
+    pub field: String,
+    pub before: u64,
+    pub after: u64
+}
+
+
+/// Edits the unpacked Characode table, keeping the entries that didn't change as they were
+pub fn edit_characodes(value: &mut Value, edit: &CharacodeEdit) -> Result<Reindex, String> {
+    let characodes = Characodes::from_json(value).ok_or("This isn't a Characode table")?;
+    let count = characodes.names.len();
+
+    let find = |characode: &str| characodes.names.iter()
+        .position(|name| name == characode)
+        .ok_or_else(|| format!("There's no characode {:?}", characode));
+
+    let check_position = |position: u64, last: usize| match position >= 1 && position as usize <= last {
+        true => Ok(position as usize - 1),
+        false => Err(format!("The position has to be between 1 and {}, not {}", last, position))
+    };
+
+    // The old position of every characode in its new order, `None` for the inserted one
+    let mut order = (0..count).map(Some).collect::<Vec<_>>();
+    let mut inserted = None;
+
+    match edit {
+        CharacodeEdit::Insert { characode, position } => {
+            if characodes.index(characode).is_some() {
+                return Err(format!("The characode {:?} already exists", characode));
+            }
+
+            FixedString::<8>::new(characode)?;
+
+            order.insert(check_position(*position, count + 1)?, None);
+            inserted = Some(characode.clone());
+        }
+
+        CharacodeEdit::Remove { characode } => {
+            order.remove(find(characode)?);
+        }
+
+        CharacodeEdit::Move { characode, position } => {
+            let old_position = order.remove(find(characode)?);
+            order.insert(check_position(*position, count)?, old_position);
+        }
+    }
+
+    let mut new_indices = vec![None; count];
+    let old_entries = entries(value).unwrap().clone();
+
+    let new_entries = order.iter()
+        .enumerate()
+        .map(|(new_position, old_position)| match old_position {
+            Some(old_position) => {
+                new_indices[*old_position] = Some(new_position as u64 + 1);
+                old_entries[*old_position].clone()
+            }
+            None => serde_json::json!({ "characode": inserted.clone().unwrap() })
+        })
+        .collect::<Vec<_>>();
+
+    if value.get("entry_count").is_some() {
+        value["entry_count"] = new_entries.len().into();
+    }
+
+    value["entries"] = Value::Array(new_entries);
+
+    Ok(Reindex { new_indices })
+}
+
+/// Rewrites the characode index fields of a chunk after the table was edited.
+/// Fails when an entry uses a removed characode, those entries have to be removed or changed first.
+pub fn reindex_characodes(nucc_binary_type: NuccBinaryType, value: &mut Value, reindex: &Reindex) -> Result<Vec<Reindexed>, String> {
+    let fields = nucc_binary_type.characode_fields();
+
+    let keys = match entries(value) {
+        Some(entries) => keyed_entries(nucc_binary_type, entries).into_iter().map(|(key, _)| key).collect::<Vec<_>>(),
+        None => return Ok(Vec::new())
+    };
+
+    let mut changes = Vec::new();
+
+    for (key, entry) in keys.into_iter().zip(value["entries"].as_array_mut().unwrap()) {
+        for field in fields {
+            let before = match entry.get(*field).and_then(Value::as_u64) {
+                Some(before) => before,
+                None => continue
+            };
+
+            match reindex.get(before) {
+                Some(Some(after)) if after != before => {
+                    entry[*field] = after.into();
+                    changes.push(Reindexed { entry: key.clone(), field: field.to_string(), before, after });
+                }
+
+                Some(None) => return Err(format!("entry {} still uses the removed characode ({} {})", key, field, before)),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+/// The field that holds the characode of an index field, e.g. `main_characode` for `main_characode_index`
+pub fn name_field(index_field: &str) -> String {
+    index_field.strip_suffix("_index").unwrap_or(index_field).to_string()
+}
+
+/// Replaces the characode index fields of every entry with the characode they point to.
+/// Indices that aren't in the table are left as they are.
+pub fn name_characodes(nucc_binary_type: NuccBinaryType, value: &mut Value, characodes: &Characodes) {
+    let fields = nucc_binary_type.characode_fields();
+
+    for entry in value.get_mut("entries").and_then(Value::as_array_mut).into_iter().flatten() {
+        for field in fields {
+            let name = match entry.get(*field).and_then(Value::as_u64).and_then(|index| characodes.name(index)) {
+                Some(name) => name.to_string(),
+                None => continue
+            };
+
+            // In the index's place, so the entry keeps its field order
+            if let Value::Object(map) = entry {
+                let position = map.keys().position(|key| key == field).unwrap();
+
+                map.shift_remove(*field);
+                map.shift_insert(position, name_field(field), name.into());
+            }
+        }
+    }
+}
+
+/// Turns characode names back into indices, failing on names that aren't in the table
+pub fn resolve_characodes(nucc_binary_type: NuccBinaryType, value: &mut Value, characodes: &Characodes) -> Result<(), String> {
+    let fields = nucc_binary_type.characode_fields();
+
+    for (index, entry) in value.get_mut("entries").and_then(Value::as_array_mut).into_iter().flatten().enumerate() {
+        if let Value::Object(map) = entry {
+            for field in fields {
+                let name = match map.remove(&name_field(field)) {
+                    Some(name) => name,
+                    None => continue
+                };
+
+                let name = text(&name).ok_or_else(|| format!("entry {}, {}: {} isn't a characode", index, name_field(field), name))?;
+                let characode_index = characodes.index(&name)
+                    .ok_or_else(|| format!("entry {}, {}: unknown characode {:?}", index, name_field(field), name))?;
+
+                if let Some(existing) = map.get(*field).filter(|existing| existing.as_u64() != Some(characode_index)) {
+                    return Err(format!("entry {}: {} is {:?} but {} is {}", index, name_field(field), name, field, existing));
+                }
+
+                map.insert(field.to_string(), characode_index.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Turns the characode names of unpacked chunks back into indices, so their entries are keyed the same as the ones of an xfbin
+pub fn resolve_chunks(chunks: &mut [Chunk], characodes: &Characodes) -> Result<(), String> {
+    for chunk in chunks {
+        let mut value = match chunk.json() {
+            Some(value) if uses_names(chunk.nucc_binary_type, &value) => value,
+            _ => continue
+        };
+
+        resolve_characodes(chunk.nucc_binary_type, &mut value, characodes).map_err(|err| format!("{}: {}", chunk.name, err))?;
+        chunk.data = to_json(&value);
+    }
+
+    Ok(())
+}
+
+/// Whether any entry uses characode names, so it needs the table to be repacked
+pub fn uses_names(nucc_binary_type: NuccBinaryType, value: &Value) -> bool {
+    let fields = nucc_binary_type.characode_fields();
+
+    entries(value).into_iter().flatten().any(|entry| fields.iter().any(|field| entry.get(name_field(field)).is_some()))
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(map) => map.get("text").and_then(Value::as_str).map(str::to_string),
+        _ => None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table() -> Value {
+        json!({ "entry_count": 4, "entries": [
+            { "characode": "1nrt" }, { "characode": "2sik" }, { "characode": "3sak" }, { "characode": "4kak" }
+        ]})
+    }
+
+    /// Presets using the first three characodes, as (characode_index, main_characode_index)
+    fn presets() -> Value {
+        json!({ "entries": [
+            { "player_setting_id": 1, "characode_index": 1, "main_characode_index": 1 },
+            { "player_setting_id": 2, "characode_index": 3, "main_characode_index": 3 },
+            { "player_setting_id": 3, "characode_index": 2, "main_characode_index": 1 }
+        ]})
+    }
+
+    #[test]
+    fn edit_characodes_test() {
+        let insert = |characode: &str, position| CharacodeEdit::Insert { characode: characode.to_string(), position };
+        let remove = |characode: &str| CharacodeEdit::Remove { characode: characode.to_string() };
+        let move_to = |characode: &str, position| CharacodeEdit::Move { characode: characode.to_string(), position };
+
+        // (edit, characodes and preset indices after it)
+        let cases = [
+            (insert("5new", 2), vec!["1nrt", "5new", "2sik", "3sak", "4kak"], [(1, 1), (4, 4), (3, 1)]),
+            (insert("5new", 5), vec!["1nrt", "2sik", "3sak", "4kak", "5new"], [(1, 1), (3, 3), (2, 1)]),
+            (remove("4kak"), vec!["1nrt", "2sik", "3sak"], [(1, 1), (3, 3), (2, 1)]),
+            (move_to("1nrt", 3), vec!["2sik", "3sak", "1nrt", "4kak"], [(3, 3), (2, 2), (1, 3)]),
+            (move_to("4kak", 1), vec!["4kak", "1nrt", "2sik", "3sak"], [(2, 2), (4, 4), (3, 2)]),
+            (move_to("2sik", 2), vec!["1nrt", "2sik", "3sak", "4kak"], [(1, 1), (3, 3), (2, 1)]),
+        ];
+
+        for (edit, expected_names, expected_indices) in cases {
+            let mut value = table();
+            let reindex = edit_characodes(&mut value, &edit).unwrap();
+
+            let mut presets = presets();
+            reindex_characodes(NuccBinaryType::PlayerSettingParam, &mut presets, &reindex).unwrap();
+
+            let indices = entries(&presets).unwrap().iter()
+                .map(|entry| (entry["characode_index"].as_u64().unwrap(), entry["main_characode_index"].as_u64().unwrap()))
+                .collect::<Vec<_>>();
+
+            assert_eq!(Characodes::from_json(&value).unwrap().names, expected_names, "{:?}", edit);
+            assert_eq!(value["entry_count"], expected_names.len(), "{:?}", edit);
+            assert_eq!(indices, expected_indices, "{:?}", edit);
+        }
+    }
+
+    #[test]
+    fn invalid_edit_test() {
+        let cases = [
+            CharacodeEdit::Insert { characode: String::from("2sik"), position: 1 },
+            CharacodeEdit::Insert { characode: String::from("5new"), position: 6 },
+            CharacodeEdit::Insert { characode: String::from("5new"), position: 0 },
+            CharacodeEdit::Remove { characode: String::from("5new") },
+            CharacodeEdit::Move { characode: String::from("1nrt"), position: 5 },
+        ];
+
+        for edit in cases {
+            let mut value = table();
+            assert!(edit_characodes(&mut value, &edit).is_err(), "{:?}", edit);
+            assert_eq!(value, table(), "{:?}", edit);
+        }
+    }
+
+    #[test]
+    fn remove_used_test() {
+        // Every characode the presets use, as their characode_index or only as their main_characode_index
+        for characode in ["1nrt", "2sik", "3sak"] {
+            let mut value = table();
+            let reindex = edit_characodes(&mut value, &CharacodeEdit::Remove { characode: characode.to_string() }).unwrap();
+
+            let mut presets = presets();
+            assert!(reindex_characodes(NuccBinaryType::PlayerSettingParam, &mut presets, &reindex).is_err(), "{}", characode);
+        }
+    }
+}
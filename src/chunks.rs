@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use encoding_rs::Encoding;
+use strum::IntoEnumIterator;
+use xfbin::read_xfbin;
+use xfbin::nucc::*;
+use xfbin::nucc_chunk::*;
+
+use crate::NuccBinaryType;
+use crate::nucc_binary::*;
+use crate::nucc_binary::text_encoding::{self, TextEncoding};
+use crate::split;
+
+
+/// Lists the encoding of every chunk that isn't UTF-8, so repacking writes the strings back the same way
+pub const ENCODING_FILE: &str = "encoding.json";
+
+/// A chunk in the same form as its unpacked file, so xfbins and unpacked folders can be compared
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub name: String,
+    pub nucc_binary_type: NuccBinaryType,
+    pub extension: String,
+    pub data: Vec<u8>,
+
+    /// The encoding of its strings in the xfbin
+    pub encoding: &'static Encoding
+}
+
+impl Chunk {
+    pub fn json(&self) -> Option<serde_json::Value> {
+        if self.extension == ".json" {
+            serde_json::from_slice(&self.data).ok()
+        } else {
+            None
+        }
+    }
+}
+
+
+pub fn find_nucc_binary_type(filepath: &str) -> Option<NuccBinaryType> {
+    NuccBinaryType::iter().find(|nucc_binary_type| nucc_binary_type.patterns().is_match(filepath))
+}
+
+/// Guesses the format of an unpacked file from its chunk name, e.g. `messageInfo.json`
+pub fn guess_nucc_binary_type(path: &Path) -> Option<NuccBinaryType> {
+    let stem = path.file_stem()?.to_string_lossy();
+
+    find_nucc_binary_type(&format!("{}.bin", stem))
+        .or_else(|| find_nucc_binary_type(&path.file_name()?.to_string_lossy()))
+}
+
+/// Parses a chunk's data with the given encoding, or the detected one when it's `None` or auto
+pub fn parse_chunk(nucc_binary_type: NuccBinaryType, data: &[u8], encoding: Option<TextEncoding>) -> (Box<dyn NuccBinaryParsed>, &'static Encoding) {
+    let read = || -> Box<dyn NuccBinaryParsed> { NuccBinaryParsedReader(nucc_binary_type, data).into() };
+
+    match encoding {
+        Some(TextEncoding::Fixed(encoding)) => (text_encoding::with_encoding(encoding, read), encoding),
+        Some(TextEncoding::Auto) | None => text_encoding::detect(read)
+    }
+}
+
+/// Edits a chunk's binary data through its unpacked json, going through the format's typed structs both ways.
+/// Strings are written back in the encoding they were read with.
+/// An edited value that doesn't fit the format is an error naming its field, e.g. `entries[3].sound_name: invalid type: integer`
+pub fn edit_chunk<R>(nucc_binary_type: NuccBinaryType, data: &[u8], edit: impl FnOnce(&mut serde_json::Value) -> Result<R, String>) -> Result<(Vec<u8>, R), String> {
+    if let Some(error) = pointers::check_pointers(nucc_binary_type, data).first() {
+        return Err(error.to_string());
+    }
+
+    let (nucc_binary_parsed, encoding) = parse_chunk(nucc_binary_type, data, None);
+
+    if nucc_binary_parsed.extension() != ".json" {
+        return Err(format!("{} chunks aren't json", nucc_binary_type));
+    }
+
+    let mut value = serde_json::from_slice(&nucc_binary_parsed.serialize()).unwrap();
+    let result = edit(&mut value)?;
+
+    let deserializer = NuccBinaryParsedDeserializer(nucc_binary_type, serde_json::to_vec(&value).unwrap());
+    let data = text_encoding::with_encoding(encoding, || -> Result<Vec<u8>, String> {
+        deserializer.check()?;
+        Ok(NuccBinaryParsedWriter(deserializer.into()).into())
+    })?;
+
+    Ok((data, result))
+}
+
+/// Every xfbin in a folder and its subfolders, sorted
+pub fn find_xfbins(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(dir) {
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if path.is_dir() {
+                files.extend(find_xfbins(&path));
+            } else if path.extension().map_or(false, |ext| ext == "xfbin") {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Loads the chunks of an xfbin, or of a folder it was unpacked to
+pub fn load_chunks(path: &Path) -> Result<Vec<Chunk>, String> {
+    if path.is_dir() {
+        load_folder(path)
+    } else {
+        load_xfbin(path)
+    }
+}
+
+fn load_xfbin(path: &Path) -> Result<Vec<Chunk>, String> {
+    let xfbin = read_xfbin(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+    let mut chunks = Vec::new();
+
+    for chunk in &xfbin.get_chunks_by_type(NuccChunkType::NuccChunkBinary) {
+        let nucc_binary = chunk.downcast_ref::<NuccBinary>().unwrap();
+        let name = nucc_binary.struct_info.chunk_name.clone();
+
+        if let Some(nucc_binary_type) = find_nucc_binary_type(&nucc_binary.struct_info.filepath) {
+            if let Some(error) = pointers::check_pointers(nucc_binary_type, &nucc_binary.data).first() {
+                return Err(format!("{} in {}: {}", name, path.display(), error));
+            }
+
+            let (nucc_binary_parsed, encoding) = parse_chunk(nucc_binary_type, &nucc_binary.data, None);
+
+            chunks.push(Chunk {
+                name,
+                nucc_binary_type,
+                extension: nucc_binary_parsed.extension(),
+                data: nucc_binary_parsed.serialize(),
+                encoding
+            });
+        }
+    }
+
+    Ok(chunks)
+}
+
+fn load_folder(path: &Path) -> Result<Vec<Chunk>, String> {
+    // The formats are known from the xfbin next to the folder, the one it gets repacked into
+    let mut formats = Vec::new();
+
+    if let (Some(parent), Some(folder_name)) = (path.parent(), path.file_name()) {
+        let xfbin_path = parent.join(format!("{}.xfbin", folder_name.to_string_lossy()));
+
+        if let Ok(xfbin) = read_xfbin(&xfbin_path) {
+            for chunk in &xfbin.get_chunks_by_type(NuccChunkType::NuccChunkBinary) {
+                let nucc_binary = chunk.downcast_ref::<NuccBinary>().unwrap();
+
+                if let Some(nucc_binary_type) = find_nucc_binary_type(&nucc_binary.struct_info.filepath) {
+                    formats.push((nucc_binary.struct_info.chunk_name.clone(), nucc_binary_type));
+                }
+            }
+        }
+    }
+
+    // Encodings detected when the folder was unpacked
+    let encodings: HashMap<String, String> = fs::read(path.join(ENCODING_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default();
+
+    let mut chunks = Vec::new();
+
+    let mut entries = fs::read_dir(path)
+        .map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() || split::is_split(path))
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for file in entries {
+        let file_name = file.file_name().unwrap().to_string_lossy().to_string();
+
+        if file_name == ENCODING_FILE || file_name.ends_with(".raw.json") {
+            continue;
+        }
+
+        // Chunks exported with --split are a folder named after the chunk
+        let (name, extension) = match split::is_split(&file) {
+            true => (file_name.clone(), String::from(".json")),
+            false => (
+                file.file_stem().unwrap().to_string_lossy().to_string(),
+                format!(".{}", file.extension().map(|ext| ext.to_string_lossy()).unwrap_or_default())
+            )
+        };
+
+        // Without the xfbin, guess the format from the chunk name
+        let nucc_binary_type = formats.iter()
+            .find(|(chunk_name, _)| *chunk_name == name)
+            .map(|(_, nucc_binary_type)| *nucc_binary_type)
+            .or_else(|| guess_nucc_binary_type(&file));
+
+        if let Some(nucc_binary_type) = nucc_binary_type {
+            let data = match split::is_split(&file) {
+                true => split::join(&file)?,
+                false => fs::read(&file).map_err(|err| format!("Couldn't read {}: {}", file.display(), err))?
+            };
+            let encoding = encodings.get(&name)
+                .and_then(|label| Encoding::for_label(label.as_bytes()))
+                .unwrap_or(encoding_rs::UTF_8);
+
+            chunks.push(Chunk { name, nucc_binary_type, extension, data, encoding });
+        }
+    }
+
+    Ok(chunks)
+}
@@ -0,0 +1,49 @@
+use std::fs;
+use std::path::Path;
+
+use nuccbin::ids::Ids;
+use nuccbin::roster::{add_accessory, load_tables, save_tables, AccessorySpec, ACCESSORY_FORMATS};
+
+use super::add_character::{load_project, print_changes};
+
+
+const USAGE: &str = "Usage: nuccbin add-accessory <folder> <accessory.json> [--dry-run] [--json]";
+
+/// Adds an accessory to the accessory params in a folder's xfbins from an accessory spec
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut positionals = Vec::new();
+    let mut dry_run = false;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => positionals.push(Path::new(arg))
+        }
+    }
+
+    let (folder, spec_path) = match positionals.as_slice() {
+        [folder, spec_path] => (*folder, *spec_path),
+        _ => return Err(USAGE.to_string())
+    };
+
+    let data = fs::read(spec_path).map_err(|err| format!("Couldn't read {}: {}", spec_path.display(), err))?;
+    let spec: AccessorySpec = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&data))
+        .map_err(|err| format!("{}: {}", spec_path.display(), err))?;
+
+    let (project, ids_path) = load_project(folder)?;
+    let mut ids = Ids::load(&ids_path)?;
+    let mut tables = load_tables(folder, ACCESSORY_FORMATS)?;
+
+    let changes = add_accessory(&spec, &mut tables, &mut ids, &project)?;
+    print_changes(&changes, json);
+
+    if dry_run {
+        return Ok(());
+    }
+
+    save_tables(&tables)?;
+    ids.save(&ids_path)
+}
\ No newline at end of file
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+
+use nuccbin::ids::{Ids, IDS_FILE};
+use nuccbin::project::Project;
+use nuccbin::roster::{add_character, load_tables, save_tables, CharacterSpec, Changes, CHARACTER_FORMATS};
+
+
+const USAGE: &str = "Usage: nuccbin add-character <folder> <character.json> [--dry-run] [--json]";
+
+/// Adds a character to every roster param in a folder's xfbins from a character spec
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut positionals = Vec::new();
+    let mut dry_run = false;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => positionals.push(Path::new(arg))
+        }
+    }
+
+    let (folder, spec_path) = match positionals.as_slice() {
+        [folder, spec_path] => (*folder, *spec_path),
+        _ => return Err(USAGE.to_string())
+    };
+
+    let data = fs::read(spec_path).map_err(|err| format!("Couldn't read {}: {}", spec_path.display(), err))?;
+    let spec: CharacterSpec = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&data))
+        .map_err(|err| format!("{}: {}", spec_path.display(), err))?;
+
+    let (project, ids_path) = load_project(folder)?;
+    let mut ids = Ids::load(&ids_path)?;
+    let mut tables = load_tables(folder, CHARACTER_FORMATS)?;
+
+    let changes = add_character(&spec, &mut tables, &mut ids, &project)?;
+    print_changes(&changes, json);
+
+    if dry_run {
+        return Ok(());
+    }
+
+    save_tables(&tables)?;
+    ids.save(&ids_path)
+}
+
+/// The folder's project, and where the ids given to new entries are kept
+pub fn load_project(folder: &Path) -> Result<(Project, std::path::PathBuf), String> {
+    match Project::find(folder) {
+        Some(path) => Ok((Project::load(&path)?, path.with_file_name(IDS_FILE))),
+        None => Ok((Project::default(), folder.join(IDS_FILE)))
+    }
+}
+
+pub fn print_changes(changes: &Changes, json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(changes).unwrap());
+        return;
+    }
+
+    for added in &changes.added {
+        println!("+ {} / {} ({}): {}", added.file, added.chunk, added.format, added.entry);
+    }
+
+    for edited in &changes.edited {
+        println!("~ {} / {} ({}): {}", edited.file, edited.chunk, edited.format, edited.entry);
+    }
+
+    for assigned in &changes.ids {
+        println!("{}", assigned);
+    }
+
+    for skipped in &changes.skipped {
+        println!("Skipped {}", skipped);
+    }
+
+    for warning in &changes.warnings {
+        println!("Warning: {}", warning);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,75 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use xfbin::{read_xfbin, write_xfbin};
+use xfbin::nucc::*;
+
+use nuccbin::chunks::find_nucc_binary_type;
+use nuccbin::patch::Patch;
+
+
+const USAGE: &str = "Usage: nuccbin apply <xfbin> <patch.json>... [--output <xfbin>]";
+
+/// Applies patches to an xfbin's chunks, in place unless an output path is given
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut paths = Vec::new();
+    let mut output = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => output = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => paths.push(PathBuf::from(arg))
+        }
+    }
+
+    if paths.len() < 2 {
+        return Err(USAGE.to_string());
+    }
+
+    let xfbin_path = paths.remove(0);
+    let mut xfbin = read_xfbin(&xfbin_path).map_err(|err| format!("Couldn't read {}: {}", xfbin_path.display(), err))?;
+
+    for patch_path in &paths {
+        let patch = read_patch(patch_path)?;
+
+        for chunk_patch in &patch.chunks {
+            let mut found = false;
+
+            for page in &mut xfbin.pages {
+                for chunk in &mut page.structs {
+                    if let Some(nucc_binary) = chunk.downcast_mut::<NuccBinary>() {
+                        if nucc_binary.struct_info.chunk_name != chunk_patch.chunk {
+                            continue;
+                        }
+
+                        if find_nucc_binary_type(&nucc_binary.struct_info.filepath) != Some(chunk_patch.format) {
+                            return Err(format!("{}: {} isn't a {} chunk", patch_path.display(), chunk_patch.chunk, chunk_patch.format));
+                        }
+
+                        nucc_binary.data = chunk_patch.apply_to_data(&nucc_binary.data)
+                            .map_err(|err| format!("{}: {}", patch_path.display(), err))?;
+                        found = true;
+                    }
+                }
+            }
+
+            if !found {
+                return Err(format!("{}: {} has no chunk named {}", patch_path.display(), xfbin_path.display(), chunk_patch.chunk));
+            }
+        }
+
+        println!("Applied {}", patch_path.display());
+    }
+
+    let output = output.unwrap_or(xfbin_path);
+    write_xfbin(xfbin, &output).map_err(|err| format!("Couldn't write {}: {}", output.display(), err))?;
+
+    Ok(())
+}
+
+fn read_patch(path: &Path) -> Result<Patch, String> {
+    let data = fs::read(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+    serde_json::from_slice(&data).map_err(|err| format!("{} isn't a valid patch: {}", path.display(), err))
+}
@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use xfbin::{read_xfbin, write_xfbin};
+use xfbin::nucc::*;
+
+use nuccbin::NuccBinaryType;
+use nuccbin::characodes::{edit_characodes, reindex_characodes, CharacodeEdit, Reindexed};
+use nuccbin::chunks::{edit_chunk, find_nucc_binary_type};
+
+
+const USAGE: &str = concat!(
+    "Usage: nuccbin characode <characode.bin.xfbin> insert <characode> <position> [<xfbin>...] [--dry-run] [--json]\n",
+    "       nuccbin characode <characode.bin.xfbin> remove <characode> [<xfbin>...] [--dry-run] [--json]\n",
+    "       nuccbin characode <characode.bin.xfbin> move <characode> <position> [<xfbin>...] [--dry-run] [--json]"
+);
+
+#[derive(Serialize)]
+struct Change {
+    file: String,
+    chunk: String,
+
+    #[serde(flatten)]
+    reindexed: Reindexed
+}
+
+/// Inserts, removes or moves a characode, and rewrites the characode indices of every other xfbin given so they still point to the same characters
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut positionals = Vec::new();
+    let mut dry_run = false;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => positionals.push(arg.clone())
+        }
+    }
+
+    let mut positionals = positionals.into_iter();
+    let mut next = || positionals.next().ok_or(USAGE);
+
+    let characode_path = PathBuf::from(next()?);
+    let operation = next()?;
+    let characode = next()?;
+
+    let parse_position = |position: String| position.parse::<u64>().map_err(|_| format!("Invalid position: {}", position));
+
+    let edit = match operation.as_str() {
+        "insert" => CharacodeEdit::Insert { characode, position: parse_position(next()?)? },
+        "remove" => CharacodeEdit::Remove { characode },
+        "move" => CharacodeEdit::Move { characode, position: parse_position(next()?)? },
+        _ => return Err(USAGE.to_string())
+    };
+
+    let dependents = positionals.map(PathBuf::from).collect::<Vec<_>>();
+
+    // Everything is edited in memory first, so nothing is written when one of the files fails
+    let mut characode_xfbin = read_xfbin(&characode_path).map_err(|err| format!("Couldn't read {}: {}", characode_path.display(), err))?;
+    let mut reindex = None;
+
+    for page in &mut characode_xfbin.pages {
+        for chunk in &mut page.structs {
+            if let Some(nucc_binary) = chunk.downcast_mut::<NuccBinary>() {
+                if find_nucc_binary_type(&nucc_binary.struct_info.filepath) == Some(NuccBinaryType::Characode) {
+                    let (data, chunk_reindex) = edit_chunk(NuccBinaryType::Characode, &nucc_binary.data, |value| edit_characodes(value, &edit))
+                        .map_err(|err| format!("{}: {}", nucc_binary.struct_info.chunk_name, err))?;
+                    nucc_binary.data = data;
+                    reindex = Some(chunk_reindex);
+                }
+            }
+        }
+    }
+
+    let reindex = reindex.ok_or_else(|| format!("{} has no Characode chunk", characode_path.display()))?;
+
+    let mut xfbins = Vec::new();
+    let mut changes = Vec::new();
+
+    for path in dependents {
+        let mut xfbin = read_xfbin(&path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+
+        for page in &mut xfbin.pages {
+            for chunk in &mut page.structs {
+                if let Some(nucc_binary) = chunk.downcast_mut::<NuccBinary>() {
+                    let nucc_binary_type = match find_nucc_binary_type(&nucc_binary.struct_info.filepath) {
+                        Some(nucc_binary_type) if !nucc_binary_type.characode_fields().is_empty() => nucc_binary_type,
+                        _ => continue
+                    };
+
+                    let chunk_name = nucc_binary.struct_info.chunk_name.clone();
+
+                    let (data, reindexed) = edit_chunk(nucc_binary_type, &nucc_binary.data, |value| reindex_characodes(nucc_binary_type, value, &reindex))
+                        .map_err(|err| format!("{} / {}: {}", path.display(), chunk_name, err))?;
+
+                    nucc_binary.data = data;
+                    changes.extend(reindexed.into_iter().map(|reindexed| Change {
+                        file: path.display().to_string(),
+                        chunk: chunk_name.clone(),
+                        reindexed
+                    }));
+                }
+            }
+        }
+
+        xfbins.push((path, xfbin));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&changes).unwrap());
+    } else {
+        for change in &changes {
+            let reindexed = &change.reindexed;
+            println!("{} / {}: entry {}, {} {} -> {}", change.file, change.chunk, reindexed.entry, reindexed.field, reindexed.before, reindexed.after);
+        }
+
+        println!("{} characode indices rewritten in {} files", changes.len(), xfbins.len());
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    write_xfbin(characode_xfbin, &characode_path).map_err(|err| format!("Couldn't write {}: {}", characode_path.display(), err))?;
+
+    for (path, xfbin) in xfbins {
+        write_xfbin(xfbin, &path).map_err(|err| format!("Couldn't write {}: {}", path.display(), err))?;
+    }
+
+    Ok(())
+}
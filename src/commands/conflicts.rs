@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use nuccbin::chunks::{find_xfbins, load_chunks};
+use nuccbin::conflicts::{find_overlaps, touches, Overlap};
+
+
+const USAGE: &str = "Usage: nuccbin conflicts <base game folder> <mod folder>... [--json]";
+
+/// Lists the entries that more than one mod changes, compared to the base game's files
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut paths = Vec::new();
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => paths.push(PathBuf::from(arg))
+        }
+    }
+
+    if paths.len() < 3 {
+        return Err(USAGE.to_string());
+    }
+
+    let base_dir = paths.remove(0);
+    let base_files = find_xfbins(&base_dir);
+
+    let mut mods = Vec::new();
+    let mut mod_names = Vec::new();
+
+    for mod_dir in &paths {
+        let mod_name = mod_dir.file_name().map_or_else(|| mod_dir.display().to_string(), |name| name.to_string_lossy().to_string());
+        mod_names.push(mod_name.clone());
+
+        for file in find_xfbins(mod_dir) {
+            let relative = file.strip_prefix(mod_dir).unwrap().to_path_buf();
+
+            // Mods usually mirror the game's folders, otherwise the file is matched by name
+            let base_file = Some(base_dir.join(&relative)).filter(|path| path.is_file())
+                .or_else(|| base_files.iter().find(|path| path.file_name() == file.file_name()).cloned());
+
+            // Grouped by the game file it changes, so mods that put it in different folders still overlap
+            let game_file = base_file.as_ref()
+                .and_then(|base_file| base_file.strip_prefix(&base_dir).ok())
+                .unwrap_or(&relative)
+                .display()
+                .to_string();
+
+            let loaded = load_chunks(&file).and_then(|chunks| match &base_file {
+                Some(base_file) => Ok((load_chunks(base_file)?, chunks)),
+                None => Ok((Vec::new(), chunks))
+            });
+
+            match loaded {
+                Ok((base, chunks)) => mods.push((mod_name.clone(), game_file, touches(&base, &chunks))),
+                Err(err) => eprintln!("Skipping {}: {}", file.display(), err)
+            }
+        }
+    }
+
+    let overlaps = find_overlaps(&mods);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&overlaps).unwrap());
+    } else if overlaps.is_empty() {
+        println!("No overlapping changes");
+    } else {
+        print_overlaps(&overlaps, &mod_names);
+        print_matrix(&overlaps, &mod_names);
+    }
+
+    Ok(())
+}
+
+/// One table per chunk, with a row per overlapping entry and a column per mod
+fn print_overlaps(overlaps: &[Overlap], mod_names: &[String]) {
+    let mut chunks: BTreeMap<(&str, &str), Vec<&Overlap>> = BTreeMap::new();
+
+    for overlap in overlaps {
+        chunks.entry((&overlap.file, &overlap.chunk)).or_default().push(overlap);
+    }
+
+    for ((file, chunk), overlaps) in chunks {
+        println!("{} / {}", file, chunk);
+
+        let mut rows = vec![std::iter::once(String::from("entry")).chain(mod_names.iter().cloned()).collect::<Vec<_>>()];
+
+        for overlap in overlaps {
+            rows.push(std::iter::once(overlap.entry.clone())
+                .chain(mod_names.iter().map(|mod_name| overlap.mods.get(mod_name).map(|fields| fields.join(", ")).unwrap_or_default()))
+                .collect());
+        }
+
+        print_table(&rows);
+        println!();
+    }
+}
+
+/// How many entries each pair of mods both change
+fn print_matrix(overlaps: &[Overlap], mod_names: &[String]) {
+    let mut rows = vec![std::iter::once(String::new()).chain(mod_names.iter().cloned()).collect::<Vec<_>>()];
+
+    for first in mod_names {
+        rows.push(std::iter::once(first.clone())
+            .chain(mod_names.iter().map(|second| match first == second {
+                true => String::from("-"),
+                false => overlaps.iter()
+                    .filter(|overlap| overlap.mods.contains_key(first) && overlap.mods.contains_key(second))
+                    .count()
+                    .to_string()
+            }))
+            .collect());
+    }
+
+    print_table(&rows);
+}
+
+fn print_table(rows: &[Vec<String>]) {
+    let widths = (0..rows[0].len())
+        .map(|column| rows.iter().map(|row| row[column].chars().count()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+
+    for row in rows {
+        let cells = row.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect::<Vec<_>>();
+        println!("  {}", cells.join(" | ").trim_end());
+    }
+}
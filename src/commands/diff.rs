@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use nuccbin::characodes::{resolve_chunks, Characodes};
+use nuccbin::chunks::load_chunks;
+use nuccbin::diff::{diff_chunks, mixed_characodes};
+use nuccbin::patch::Patch;
+
+
+const USAGE: &str = "Usage: nuccbin diff <before> <after> [--json | --patch] [--characodes <characode.bin.xfbin>]";
+
+/// Compares two xfbins or unpacked folders entry by entry
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut paths = Vec::new();
+    let mut json = false;
+    let mut patch = false;
+    let mut characodes = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--patch" => patch = true,
+            "--characodes" => characodes = Some(Characodes::load(Path::new(args.next().ok_or(USAGE)?))?),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => paths.push(Path::new(arg))
+        }
+    }
+
+    let (before, after) = match paths[..] {
+        [before, after] => (before, after),
+        _ => return Err(USAGE.to_string())
+    };
+
+    let (mut before, mut after) = (load_chunks(before)?, load_chunks(after)?);
+
+    // Names and indices only key the same once the names are indices again
+    match &characodes {
+        Some(characodes) => {
+            resolve_chunks(&mut before, characodes)?;
+            resolve_chunks(&mut after, characodes)?;
+        }
+
+        None => {
+            let mixed = mixed_characodes(&before, &after);
+
+            if !mixed.is_empty() {
+                return Err(format!("{} use characode names on one side and indices on the other, pass --characodes to compare them", mixed.join(", ")));
+            }
+        }
+    }
+
+    let diffs = diff_chunks(&before, &after);
+
+    if patch {
+        println!("{}", serde_json::to_string_pretty(&Patch::from_diffs(&diffs)?).unwrap());
+    } else if json {
+        println!("{}", serde_json::to_string_pretty(&diffs).unwrap());
+    } else if diffs.is_empty() {
+        println!("No differences");
+    } else {
+        for diff in &diffs {
+            print!("{}", diff);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::str::FromStr;
+
+use nuccbin::NuccBinaryType;
+use nuccbin::explain::{explain, find_fields, format_table, render_fields, render_format};
+
+
+const USAGE: &str = "Usage: nuccbin explain <format> [field] [--json]\n       nuccbin explain --markdown";
+
+/// Prints the layout of a format with what is known about its fields, or everything about one field
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut positionals = Vec::new();
+    let mut json = false;
+    let mut markdown = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--markdown" => markdown = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => positionals.push(arg.as_str())
+        }
+    }
+
+    if markdown {
+        print!("{}", format_table());
+        return Ok(());
+    }
+
+    let (format, field) = match positionals.as_slice() {
+        [format] => (*format, None),
+        [format, field] => (*format, Some(*field)),
+        _ => return Err(USAGE.to_string())
+    };
+
+    let nucc_binary_type = NuccBinaryType::from_str(format).map_err(|_| format!("Unknown format: {}", format))?;
+
+    match (field, json) {
+        (Some(field), true) => println!("{}", serde_json::to_string_pretty(&find_fields(nucc_binary_type, field)?).unwrap()),
+        (Some(field), false) => print!("{}", render_fields(nucc_binary_type, &find_fields(nucc_binary_type, field)?)),
+        (None, true) => println!("{}", serde_json::to_string_pretty(&explain(nucc_binary_type)).unwrap()),
+        (None, false) => print!("{}", render_format(&explain(nucc_binary_type)))
+    }
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use nuccbin::chunks::{find_xfbins, load_chunks};
+use nuccbin::lint::{check_project, limit, lint, rule_id, rules, severity};
+use nuccbin::nucc_binary::lint::Severity;
+use nuccbin::project::Project;
+
+
+const USAGE: &str = "Usage: nuccbin lint <folder or xfbin> [--project <nuccbin.json>] [--json] [--rules]";
+
+/// Runs every format's lint rules on a folder's xfbins, failing when any of them reports an error
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut project_path = None;
+    let mut json = false;
+    let mut list = false;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--project" => project_path = Some(Path::new(args.next().ok_or(USAGE)?).to_path_buf()),
+            "--json" => json = true,
+            "--rules" => list = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ if path.is_none() => path = Some(Path::new(arg)),
+            _ => return Err(USAGE.to_string())
+        }
+    }
+
+    // Without --project, use the one in the linted folder or the closest one above it
+    let project_path = project_path.or_else(|| match path {
+        Some(path) if path.is_dir() => Project::find(path),
+        Some(path) => Project::find(path.parent().unwrap_or(Path::new("."))),
+        None => Project::find(Path::new("."))
+    });
+
+    let project = match project_path {
+        Some(project_path) => Project::load(&project_path)?,
+        None => Project::default()
+    };
+    check_project(&project)?;
+
+    if list {
+        for (nucc_binary_type, rule) in rules() {
+            let limit = limit(&project, nucc_binary_type, rule).map(|limit| format!(" (limit {})", limit)).unwrap_or_default();
+            println!("{:<8} {:<50} {}{}", severity(&project, nucc_binary_type, rule), rule_id(nucc_binary_type, rule), rule.description, limit);
+        }
+
+        return Ok(());
+    }
+
+    let path = path.ok_or(USAGE)?;
+    let files = match path.is_dir() {
+        true => find_xfbins(path),
+        false => vec![path.to_path_buf()]
+    };
+
+    let mut chunks = Vec::new();
+
+    for file in files {
+        let name = match path.is_dir() {
+            true => file.strip_prefix(path).unwrap().display().to_string(),
+            false => file.display().to_string()
+        };
+
+        match load_chunks(&file) {
+            Ok(loaded) => chunks.extend(loaded.into_iter().map(|chunk| (name.clone(), chunk))),
+            Err(err) => eprintln!("Skipping {}: {}", file.display(), err)
+        }
+    }
+
+    let issues = lint(&chunks, &project);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&issues).unwrap());
+    } else {
+        for issue in &issues {
+            println!("{}", issue);
+        }
+    }
+
+    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+
+    match errors {
+        0 => Ok(()),
+        _ => Err(format!("{} errors, {} warnings", errors, issues.len() - errors))
+    }
+}
\ No newline at end of file
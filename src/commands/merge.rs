@@ -0,0 +1,173 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde_json::Value;
+use xfbin::{read_xfbin, write_xfbin};
+use xfbin::nucc::*;
+use xfbin::nucc_chunk::*;
+
+use nuccbin::NuccBinaryType;
+use nuccbin::chunks::{find_nucc_binary_type, guess_nucc_binary_type};
+use nuccbin::merge::{merge_data, merge_serialized, Conflict};
+
+
+const USAGE: &str = "Usage: nuccbin merge <base> <ours> <theirs> [--output <path>] [--format <format>] [--name <path>] [--json]";
+const DRIVER_USAGE: &str = "Usage: nuccbin merge-driver %O %A %B %P";
+
+/// Merges two edited versions of an xfbin, or of one unpacked file, with the version they both started from.
+/// The result goes to ours unless an output path is given, and conflicts keep ours' value.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut paths = Vec::new();
+    let mut output = None;
+    let mut format = None;
+    let mut name = None;
+    let mut json = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => output = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            "--format" => {
+                let value = args.next().ok_or(USAGE)?;
+                format = Some(NuccBinaryType::from_str(value).map_err(|_| format!("Unknown format: {}", value))?);
+            }
+            "--name" => name = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            "--json" => json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => paths.push(PathBuf::from(arg))
+        }
+    }
+
+    let (base, ours, theirs) = match &paths[..] {
+        [base, ours, theirs] => (base, ours, theirs),
+        _ => return Err(USAGE.to_string())
+    };
+
+    let output = output.unwrap_or_else(|| ours.clone());
+
+    // The file's real path when the sides are temporary copies, like the ones git passes to merge drivers
+    let name = name.unwrap_or_else(|| ours.clone());
+
+    let conflicts = if name.extension().map_or(false, |ext| ext == "xfbin") {
+        merge_xfbins(base, ours, theirs, &output)?
+    } else {
+        let nucc_binary_type = format.or_else(|| guess_nucc_binary_type(&name))
+            .ok_or_else(|| format!("Couldn't tell the format of {}, pass it with --format", name.display()))?;
+
+        let (merged, conflicts) = merge_serialized(nucc_binary_type, &read(base)?, &read(ours)?, &read(theirs)?);
+        fs::write(&output, merged).map_err(|err| format!("Couldn't write {}: {}", output.display(), err))?;
+
+        conflicts.into_iter().map(|conflict| (None, conflict)).collect()
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&conflicts).unwrap());
+    } else {
+        for (chunk, conflict) in &conflicts {
+            match chunk {
+                Some(chunk) => println!("{}: {}", chunk, conflict),
+                None => println!("{}", conflict)
+            }
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} conflicts, ours was kept for each of them in {}", conflicts.len(), output.display()))
+    }
+}
+
+/// `merge` with git's merge driver arguments, writing the result over ours and failing on conflicts so git marks the file as conflicted
+pub fn run_driver(args: &[String]) -> Result<(), String> {
+    match args {
+        [base, ours, theirs, name] => run(&[base.clone(), ours.clone(), theirs.clone(), String::from("--name"), name.clone()]),
+        _ => Err(DRIVER_USAGE.to_string())
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))
+}
+
+fn merge_xfbins(base: &Path, ours: &Path, theirs: &Path, output: &Path) -> Result<Vec<(Option<String>, Conflict)>, String> {
+    let (base_chunks, their_chunks) = (binary_chunks(base)?, binary_chunks(theirs)?);
+    let mut xfbin = read_xfbin(ours).map_err(|err| format!("Couldn't read {}: {}", ours.display(), err))?;
+
+    let mut conflicts = Vec::new();
+    let mut our_names = HashSet::new();
+
+    for page in &mut xfbin.pages {
+        for chunk in &mut page.structs {
+            if let Some(nucc_binary) = chunk.downcast_mut::<NuccBinary>() {
+                let chunk_name = nucc_binary.struct_info.chunk_name.clone();
+                our_names.insert(chunk_name.clone());
+
+                let nucc_binary_type = match find_nucc_binary_type(&nucc_binary.struct_info.filepath) {
+                    Some(nucc_binary_type) => nucc_binary_type,
+                    None => continue
+                };
+
+                match (base_chunks.get(&chunk_name), their_chunks.get(&chunk_name)) {
+                    (Some(base_data), Some(their_data)) => {
+                        let (merged, chunk_conflicts) = merge_data(nucc_binary_type, base_data, &nucc_binary.data, their_data)
+                            .map_err(|err| format!("{}: {}", chunk_name, err))?;
+
+                        nucc_binary.data = merged;
+                        conflicts.extend(chunk_conflicts.into_iter().map(|conflict| (Some(chunk_name.clone()), conflict)));
+                    }
+
+                    // Added on both sides, differently
+                    (None, Some(their_data)) if *their_data != nucc_binary.data => {
+                        conflicts.push((Some(chunk_name.clone()), chunk_conflict(None, Some(&nucc_binary.data), Some(their_data))));
+                    }
+
+                    // Kept as ours has them, removed or not by theirs
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Chunks only theirs has can't be added to ours, unless ours removed them and theirs left them as they were
+    let mut their_names = their_chunks.keys().filter(|name| !our_names.contains(*name)).collect::<Vec<_>>();
+    their_names.sort();
+
+    for chunk_name in their_names {
+        let (base_data, their_data) = (base_chunks.get(chunk_name), &their_chunks[chunk_name]);
+
+        if base_data != Some(their_data) {
+            conflicts.push((Some(chunk_name.clone()), chunk_conflict(base_data, None, Some(their_data))));
+        }
+    }
+
+    write_xfbin(xfbin, output).map_err(|err| format!("Couldn't write {}: {}", output.display(), err))?;
+
+    Ok(conflicts)
+}
+
+/// A whole chunk that the sides don't agree on, with the size each of them has it at
+fn chunk_conflict(base: Option<&Vec<u8>>, ours: Option<&Vec<u8>>, theirs: Option<&Vec<u8>>) -> Conflict {
+    let size = |data: Option<&Vec<u8>>| data.map_or(Value::Null, |data| Value::from(format!("{} bytes", data.len())));
+
+    Conflict {
+        entry: None,
+        field: String::from("data"),
+        base: size(base),
+        ours: size(ours),
+        theirs: size(theirs)
+    }
+}
+
+/// The data of every binary chunk by chunk name
+fn binary_chunks(path: &Path) -> Result<HashMap<String, Vec<u8>>, String> {
+    let xfbin = read_xfbin(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+
+    Ok(xfbin.get_chunks_by_type(NuccChunkType::NuccChunkBinary)
+        .iter()
+        .map(|chunk| chunk.downcast_ref::<NuccBinary>().unwrap())
+        .map(|nucc_binary| (nucc_binary.struct_info.chunk_name.clone(), nucc_binary.data.clone()))
+        .collect())
+}
@@ -0,0 +1,41 @@
+mod add_accessory;
+mod add_character;
+mod add_costume;
+mod apply;
+mod characode;
+mod conflicts;
+mod diff;
+mod explain;
+mod lint;
+mod merge;
+mod render_settings;
+mod schema;
+mod select;
+mod textconv;
+mod validate;
+
+
+/// Runs the subcommand named by the first argument, `None` when it isn't one so the path is unpacked or repacked as usual
+pub fn run(args: &[String]) -> Option<Result<(), String>> {
+    let (command, rest) = args.split_first()?;
+
+    match command.as_str() {
+        "add-accessory" => Some(add_accessory::run(rest)),
+        "add-character" => Some(add_character::run(rest)),
+        "add-costume" => Some(add_costume::run(rest)),
+        "apply" => Some(apply::run(rest)),
+        "characode" => Some(characode::run(rest)),
+        "conflicts" => Some(conflicts::run(rest)),
+        "diff" => Some(diff::run(rest)),
+        "explain" => Some(explain::run(rest)),
+        "lint" => Some(lint::run(rest)),
+        "merge" => Some(merge::run(rest)),
+        "merge-driver" => Some(merge::run_driver(rest)),
+        "render-settings" => Some(render_settings::run(rest)),
+        "schema" => Some(schema::run(rest)),
+        "select" => Some(select::run(rest)),
+        "textconv" => Some(textconv::run(rest)),
+        "validate" => Some(validate::run(rest)),
+        _ => None
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use xfbin::{read_xfbin, write_xfbin};
+use xfbin::nucc::*;
+
+use nuccbin::NuccBinaryType;
+use nuccbin::chunks::{edit_chunk, find_nucc_binary_type};
+use nuccbin::render_settings::{mirror_1p_to_2p, Mirrored};
+
+
+const USAGE: &str = "Usage: nuccbin render-settings <characterSelectParam.bin.xfbin> mirror [<searchcode>...] [--dry-run] [--json]";
+
+#[derive(Serialize)]
+struct Change {
+    chunk: String,
+
+    #[serde(flatten)]
+    mirrored: Mirrored
+}
+
+/// Copies the 1P render settings of the given slots, or every slot, mirrored to 2P
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut positionals = Vec::new();
+    let mut dry_run = false;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => positionals.push(arg.clone())
+        }
+    }
+
+    let mut positionals = positionals.into_iter();
+    let path = PathBuf::from(positionals.next().ok_or(USAGE)?);
+
+    if positionals.next().as_deref() != Some("mirror") {
+        return Err(USAGE.to_string());
+    }
+
+    let searchcodes = positionals.collect::<Vec<_>>();
+
+    let mut xfbin = read_xfbin(&path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+    let mut mirrored = Vec::new();
+    let mut found = false;
+
+    for page in &mut xfbin.pages {
+        for chunk in &mut page.structs {
+            if let Some(nucc_binary) = chunk.downcast_mut::<NuccBinary>() {
+                if find_nucc_binary_type(&nucc_binary.struct_info.filepath) == Some(NuccBinaryType::CharacterSelectParam) {
+                    let (data, chunk_mirrored) = edit_chunk(NuccBinaryType::CharacterSelectParam, &nucc_binary.data, |value| Ok(mirror_1p_to_2p(value, &searchcodes)))
+                        .map_err(|err| format!("{}: {}", nucc_binary.struct_info.chunk_name, err))?;
+                    nucc_binary.data = data;
+                    found = true;
+
+                    let chunk = &nucc_binary.struct_info.chunk_name;
+                    mirrored.extend(chunk_mirrored.into_iter().map(|mirrored| Change { chunk: chunk.clone(), mirrored }));
+                }
+            }
+        }
+    }
+
+    if !found {
+        return Err(format!("{} has no CharacterSelectParam chunk", path.display()));
+    }
+
+    if let Some(missing) = searchcodes.iter().find(|searchcode| !mirrored.iter().any(|change| &change.mirrored.searchcode == *searchcode)) {
+        return Err(format!("No slot has the searchcode {}", missing));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&mirrored).unwrap());
+    } else {
+        for change in &mirrored {
+            println!("{}: entry {}, {}: 2P mirrored from 1P", change.chunk, change.mirrored.entry, change.mirrored.searchcode);
+        }
+
+        println!("{} entries mirrored", mirrored.len());
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    write_xfbin(xfbin, &path).map_err(|err| format!("Couldn't write {}: {}", path.display(), err))
+}
\ No newline at end of file
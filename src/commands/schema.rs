@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use strum::IntoEnumIterator;
+
+use nuccbin::NuccBinaryType;
+use nuccbin::nucc_binary::to_json;
+use nuccbin::schema::schema;
+
+
+const USAGE: &str = "Usage: nuccbin schema <format> [--out <file>]\n       nuccbin schema --all <folder>";
+
+/// Prints or writes the JSON Schema of a format's unpacked json, or writes one for every format to a folder
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut format = None;
+    let mut out = None;
+    let mut all = None;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            "--all" => all = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ if format.is_none() => format = Some(arg.as_str()),
+            _ => return Err(USAGE.to_string())
+        }
+    }
+
+    if let Some(folder) = all {
+        fs::create_dir_all(&folder).map_err(|err| format!("Couldn't create {}: {}", folder.display(), err))?;
+
+        for nucc_binary_type in NuccBinaryType::iter() {
+            if let Some(schema) = schema(nucc_binary_type) {
+                let path = folder.join(format!("{}.schema.json", nucc_binary_type));
+                fs::write(&path, to_json(&schema)).map_err(|err| format!("Couldn't write {}: {}", path.display(), err))?;
+                println!("{}", path.display());
+            }
+        }
+
+        return Ok(());
+    }
+
+    let format = format.ok_or(USAGE)?;
+    let nucc_binary_type = NuccBinaryType::from_str(format).map_err(|_| format!("Unknown format: {}", format))?;
+    let schema = schema(nucc_binary_type).ok_or_else(|| format!("{} isn't unpacked to json", nucc_binary_type))?;
+
+    match out {
+        Some(path) => fs::write(&path, to_json(&schema)).map_err(|err| format!("Couldn't write {}: {}", path.display(), err)),
+        None => {
+            println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+            Ok(())
+        }
+    }
+}
\ No newline at end of file
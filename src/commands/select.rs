@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::PathBuf;
+
+use xfbin::{read_xfbin, write_xfbin};
+use xfbin::nucc::*;
+
+use nuccbin::NuccBinaryType;
+use nuccbin::chunks::{edit_chunk, find_nucc_binary_type};
+use nuccbin::select::{edit_slots, layout, render_svg, render_text, SlotEdit, SlotRef};
+
+
+const USAGE: &str = concat!(
+    "Usage: nuccbin select <characterSelectParam.bin.xfbin> [--columns <n>] [--svg <file>] [--json]\n",
+    "       nuccbin select <characterSelectParam.bin.xfbin> move <page>:<slot> <page>:<slot> [--dry-run] [--json]\n",
+    "       nuccbin select <characterSelectParam.bin.xfbin> swap <page>:<slot> <page>:<slot> [--dry-run] [--json]\n",
+    "       nuccbin select <characterSelectParam.bin.xfbin> insert <page>:<slot> [--dry-run] [--json]"
+);
+
+const DEFAULT_COLUMNS: usize = 8;
+
+/// Shows the character select screen's pages as grids, or moves, swaps and inserts slots
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut positionals = Vec::new();
+    let mut columns = DEFAULT_COLUMNS;
+    let mut svg = None;
+    let mut dry_run = false;
+    let mut json = false;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--columns" => {
+                let value = args.next().ok_or(USAGE)?;
+                columns = value.parse().map_err(|_| format!("Invalid column count: {}", value))?;
+            }
+            "--svg" => svg = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ => positionals.push(arg.clone())
+        }
+    }
+
+    let mut positionals = positionals.into_iter();
+    let mut next = || positionals.next().ok_or(USAGE);
+
+    let path = PathBuf::from(next()?);
+    let slot = |slot: String| slot.parse::<SlotRef>();
+
+    let edit = match next().ok() {
+        None => None,
+        Some(operation) => Some(match operation.as_str() {
+            "move" => SlotEdit::Move { from: slot(next()?)?, to: slot(next()?)? },
+            "swap" => SlotEdit::Swap { a: slot(next()?)?, b: slot(next()?)? },
+            "insert" => SlotEdit::Insert { at: slot(next()?)? },
+            _ => return Err(USAGE.to_string())
+        })
+    };
+
+    let mut xfbin = read_xfbin(&path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+    let mut found = false;
+
+    for page in &mut xfbin.pages {
+        for chunk in &mut page.structs {
+            if let Some(nucc_binary) = chunk.downcast_mut::<NuccBinary>() {
+                if find_nucc_binary_type(&nucc_binary.struct_info.filepath) != Some(NuccBinaryType::CharacterSelectParam) {
+                    continue;
+                }
+
+                found = true;
+
+                let (data, pages) = edit_chunk(NuccBinaryType::CharacterSelectParam, &nucc_binary.data, |value| {
+                    if let Some(edit) = &edit {
+                        let moved = edit_slots(value, edit)?;
+
+                        if json {
+                            println!("{}", serde_json::to_string_pretty(&moved).unwrap());
+                        } else {
+                            for moved in &moved {
+                                println!("{}: {} -> {}", moved.searchcode, moved.before, moved.after);
+                            }
+
+                            println!("{} entries moved", moved.len());
+                        }
+                    }
+
+                    Ok(layout(value))
+                }).map_err(|err| format!("{}: {}", nucc_binary.struct_info.chunk_name, err))?;
+
+                nucc_binary.data = data;
+
+                if let Some(svg) = &svg {
+                    fs::write(svg, render_svg(&pages, columns)).map_err(|err| format!("Couldn't write {}: {}", svg.display(), err))?;
+                }
+
+                if edit.is_none() {
+                    match json {
+                        true => println!("{}", serde_json::to_string_pretty(&pages).unwrap()),
+                        false => print!("{}", render_text(&pages, columns))
+                    }
+                }
+            }
+        }
+    }
+
+    if !found {
+        return Err(format!("{} has no CharacterSelectParam chunk", path.display()));
+    }
+
+    if edit.is_none() || dry_run {
+        return Ok(());
+    }
+
+    write_xfbin(xfbin, &path).map_err(|err| format!("Couldn't write {}: {}", path.display(), err))
+}
\ No newline at end of file
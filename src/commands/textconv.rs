@@ -0,0 +1,19 @@
+use std::path::Path;
+
+use nuccbin::chunks::load_chunks;
+use nuccbin::textconv::render;
+
+
+const USAGE: &str = "Usage: nuccbin textconv <xfbin>";
+
+/// Prints every supported chunk of an xfbin as sorted text, for `git diff`
+pub fn run(args: &[String]) -> Result<(), String> {
+    let path = match args {
+        [path] => Path::new(path),
+        _ => return Err(USAGE.to_string())
+    };
+
+    print!("{}", render(&load_chunks(path)?));
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use nuccbin::chunks::{find_xfbins, load_chunks};
+use nuccbin::skeleton::{check_bones, Skeleton};
+use nuccbin::validate::{validate, IssueKind};
+
+
+const USAGE: &str = "Usage: nuccbin validate <folder> [--skeleton <bones.txt>]... [--json]";
+
+/// Checks the references between every param in a folder's xfbins, failing on dangling and duplicated ids,
+/// and the bones accessories attach to when skeleton listings are given
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut folder = None;
+    let mut skeletons = Vec::new();
+    let mut json = false;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--skeleton" => skeletons.push(Skeleton::load(&PathBuf::from(args.next().ok_or(USAGE)?))?),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            _ if folder.is_none() => folder = Some(Path::new(arg)),
+            _ => return Err(USAGE.to_string())
+        }
+    }
+
+    let folder = folder.ok_or(USAGE)?;
+    let mut chunks = Vec::new();
+
+    for file in find_xfbins(folder) {
+        let name = file.strip_prefix(folder).unwrap().display().to_string();
+
+        match load_chunks(&file) {
+            Ok(loaded) => chunks.extend(loaded.into_iter().map(|chunk| (name.clone(), chunk))),
+            Err(err) => eprintln!("Skipping {}: {}", file.display(), err)
+        }
+    }
+
+    let mut issues = validate(&chunks);
+    issues.extend(check_bones(&chunks, &skeletons));
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&issues).unwrap());
+    } else {
+        for issue in &issues {
+            println!("{}", issue);
+        }
+    }
+
+    let errors = issues.iter().filter(|issue| issue.kind != IssueKind::Orphan).count();
+
+    match errors {
+        0 => Ok(()),
+        _ => Err(format!("{} errors, {} warnings", errors, issues.len() - errors))
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::NuccBinaryType;
+use crate::chunks::Chunk;
+use crate::diff::{diff_chunks, entries, keyed_entries, Status};
+
+
+/// Something a mod changes in one chunk: an entry by its key, a slot it claims, or the header
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Touch {
+    pub chunk: String,
+    pub entry: String,
+
+    /// The changed fields, or what happened to the whole entry, e.g. `(added)`
+    pub fields: Vec<String>
+}
+
+/// An entry that more than one mod changes
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Overlap {
+    pub file: String,
+    pub chunk: String,
+    pub entry: String,
+
+    /// What each of those mods changes in it
+    pub mods: BTreeMap<String, Vec<String>>
+}
+
+
+/// Fields that place an entry somewhere two mods can't both use, even when the entries themselves differ
+pub fn slot_fields(nucc_binary_type: NuccBinaryType) -> &'static [&'static str] {
+    match nucc_binary_type {
+        NuccBinaryType::CharacterSelectParam => &["page_index", "slot_index", "costume_slot_index"],
+        _ => &[]
+    }
+}
+
+/// Everything a mod's chunks change compared to the base game's.
+/// Every entry of a chunk the base doesn't have, or of a file without a base, counts as added.
+pub fn touches(base: &[Chunk], chunks: &[Chunk]) -> Vec<Touch> {
+    let mut touches = Vec::new();
+
+    for diff in diff_chunks(base, chunks) {
+        let touch = |entry: &str, fields: Vec<String>| Touch { chunk: diff.chunk.clone(), entry: entry.to_string(), fields };
+
+        let value = chunks.iter().find(|chunk| chunk.name == diff.chunk).and_then(Chunk::json);
+        let keyed = value.as_ref().and_then(entries).map(|entries| keyed_entries(diff.format, entries)).unwrap_or_default();
+
+        match diff.status {
+            Status::Added => touches.push(touch("(whole chunk)", vec![String::from("(added)")])),
+            Status::Removed => touches.push(touch("(whole chunk)", vec![String::from("(removed)")])),
+            Status::Modified => {}
+        }
+
+        if !diff.header.is_empty() {
+            touches.push(touch("(header)", diff.header.iter().map(|change| change.field.clone()).collect()));
+        }
+
+        for entry in &diff.removed {
+            touches.push(touch(&entry.key, vec![String::from("(removed)")]));
+        }
+
+        let added = match diff.status {
+            Status::Added => keyed.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            _ => diff.added.iter().map(|entry| &entry.key).collect()
+        };
+
+        for key in &added {
+            touches.push(touch(key, vec![String::from("(added)")]));
+        }
+
+        for entry in &diff.modified {
+            touches.push(touch(&entry.key, entry.changes.iter().map(|change| change.field.clone()).collect()));
+        }
+
+        // Entries that were added or moved claim the slot they end up in
+        let slot_fields = slot_fields(diff.format);
+
+        if slot_fields.is_empty() {
+            continue;
+        }
+
+        let moved = added.into_iter()
+            .chain(diff.modified.iter()
+                .filter(|entry| entry.changes.iter().any(|change| slot_fields.contains(&change.field.as_str())))
+                .map(|entry| &entry.key))
+            .collect::<HashSet<_>>();
+
+        for (key, entry) in &keyed {
+            if moved.contains(key) {
+                let slot = slot_fields.iter().map(|field| entry[*field].to_string()).collect::<Vec<_>>().join("/");
+                touches.push(touch(&format!("slot {}", slot), vec![key.clone()]));
+            }
+        }
+    }
+
+    touches
+}
+
+/// Groups the touches of every mod, `(mod, file, touches)`, into the entries touched by more than one mod
+pub fn find_overlaps(mods: &[(String, String, Vec<Touch>)]) -> Vec<Overlap> {
+    let mut touched: BTreeMap<(String, String, String), BTreeMap<String, Vec<String>>> = BTreeMap::new();
+
+    for (mod_name, file, touches) in mods {
+        for touch in touches {
+            touched.entry((file.clone(), touch.chunk.clone(), touch.entry.clone()))
+                .or_default()
+                .entry(mod_name.clone())
+                .or_default()
+                .extend(touch.fields.iter().cloned());
+        }
+    }
+
+    touched.into_iter()
+        .filter(|(_, mods)| mods.len() > 1)
+        .map(|((file, chunk, entry), mods)| Overlap { file, chunk, entry, mods })
+        .collect()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chunk(nucc_binary_type: NuccBinaryType, value: serde_json::Value) -> Chunk {
+        Chunk { name: nucc_binary_type.to_string(), nucc_binary_type, extension: String::from(".json"), data: value.to_string().into_bytes(), encoding: encoding_rs::UTF_8 }
+    }
+
+    #[test]
+    fn added_chunk_test() {
+        let slot = |searchcode: &str| json!({ "searchcode": searchcode, "page_index": 1, "slot_index": 2, "costume_slot_index": 0 });
+        let message = |text1: &str| json!({ "crc32": "01020304", "text1": text1 });
+
+        let mod_a = [chunk(NuccBinaryType::CharacterSelectParam, json!({ "entries": [slot("1aaa01")] })), chunk(NuccBinaryType::MessageInfo, json!({ "entries": [message("a")] }))];
+        let mod_b = [chunk(NuccBinaryType::CharacterSelectParam, json!({ "entries": [slot("1bbb01")] })), chunk(NuccBinaryType::MessageInfo, json!({ "entries": [message("b")] }))];
+
+        // Neither chunk is in the base, or there's no base file at all
+        for base in [vec![], vec![chunk(NuccBinaryType::Characode, json!({ "entries": [] }))]] {
+            let mods = [
+                (String::from("a"), String::from("data.xfbin"), touches(&base, &mod_a)),
+                (String::from("b"), String::from("data.xfbin"), touches(&base, &mod_b))
+            ];
+
+            let overlaps = find_overlaps(&mods).into_iter().map(|overlap| (overlap.chunk, overlap.entry)).collect::<Vec<_>>();
+
+            assert!(overlaps.contains(&(String::from("CharacterSelectParam"), String::from("slot 1/2/0"))), "{:?}", overlaps);
+            assert!(overlaps.contains(&(String::from("MessageInfo"), String::from("01020304"))), "{:?}", overlaps);
+        }
+    }
+}
\ No newline at end of file
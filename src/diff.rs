@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::NuccBinaryType;
+use crate::chunks::Chunk;
+
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Added,
+    Removed,
+    Modified
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ChunkDiff {
+    pub chunk: String,
+    pub format: NuccBinaryType,
+    pub status: Status,
+
+    /// Changes outside of the entries, or to the whole file for formats without entries
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub header: Vec<FieldChange>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<EntryChange>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<EntryChange>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub modified: Vec<EntryDiff>
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EntryChange {
+    pub key: String,
+    pub entry: Value
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EntryDiff {
+    pub key: String,
+    pub changes: Vec<FieldChange>
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value
+}
+
+/// The key an entry is matched by across files, built from the format's key fields.
+/// Formats without key fields are matched by position.
+pub fn entry_key(nucc_binary_type: NuccBinaryType, entry: &Value, index: usize) -> String {
+    let fields = nucc_binary_type.entry_key();
+
+    if fields.is_empty() {
+        return index.to_string();
+    }
+
+    fields.iter()
+        // Exports with characode names have `characode` in place of `characode_index`
+        .map(|field| match entry.get(*field).or_else(|| entry.get(crate::characodes::name_field(field))).unwrap_or(&Value::Null) {
+            Value::String(text) => text.clone(),
+            Value::Object(map) if map.contains_key("text") => map["text"].as_str().unwrap_or_default().to_string(),
+            value => value.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Keys every entry of a chunk, numbering repeated keys (`key#2`, `key#3`, ...) so each one is unique
+pub fn keyed_entries(nucc_binary_type: NuccBinaryType, entries: &[Value]) -> Vec<(String, &Value)> {
+    let mut seen = HashMap::new();
+
+    entries.iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let key = entry_key(nucc_binary_type, entry, index);
+            let count = seen.entry(key.clone()).or_insert(0);
+            *count += 1;
+
+            match *count {
+                1 => (key, entry),
+                n => (format!("{}#{}", key, n), entry)
+            }
+        })
+        .collect()
+}
+
+/// Chunks that use characode names on one side and indices on the other. Their entries can't be matched by key
+/// until the names are turned back into indices with `characodes::resolve_chunks`
+pub fn mixed_characodes(before: &[Chunk], after: &[Chunk]) -> Vec<String> {
+    let uses_names = |chunk: &Chunk| chunk.json().map_or(false, |value| crate::characodes::uses_names(chunk.nucc_binary_type, &value));
+
+    before.iter()
+        .filter_map(|old| after.iter().find(|new| new.name == old.name).map(|new| (old, new)))
+        .filter(|(old, new)| uses_names(old) != uses_names(new))
+        .map(|(old, _)| old.name.clone())
+        .collect()
+}
+
+pub fn diff_chunks(before: &[Chunk], after: &[Chunk]) -> Vec<ChunkDiff> {
+    let mut diffs = Vec::new();
+
+    for old in before {
+        match after.iter().find(|new| new.name == old.name) {
+            Some(new) => diffs.extend(diff_chunk(old, new)),
+            None => diffs.push(whole_chunk(old, Status::Removed))
+        }
+    }
+
+    for new in after {
+        if !before.iter().any(|old| old.name == new.name) {
+            diffs.push(whole_chunk(new, Status::Added));
+        }
+    }
+
+    diffs
+}
+
+/// `None` when both chunks are the same
+pub fn diff_chunk(before: &Chunk, after: &Chunk) -> Option<ChunkDiff> {
+    if before.data == after.data {
+        return None;
+    }
+
+    let mut diff = ChunkDiff {
+        chunk: after.name.clone(),
+        format: after.nucc_binary_type,
+        status: Status::Modified,
+        header: Vec::new(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        modified: Vec::new()
+    };
+
+    let (old, new) = match (before.json(), after.json()) {
+        (Some(old), Some(new)) => (old, new),
+        _ => {
+            diff.header.push(FieldChange {
+                field: String::from("data"),
+                before: format!("{} bytes", before.data.len()).into(),
+                after: format!("{} bytes", after.data.len()).into()
+            });
+
+            return Some(diff);
+        }
+    };
+
+    match (entries(&old), entries(&new)) {
+        (Some(old_entries), Some(new_entries)) => {
+            diff.header = diff_header(&old, &new);
+
+            let old_keyed = keyed_entries(after.nucc_binary_type, old_entries);
+            let new_keyed = keyed_entries(after.nucc_binary_type, new_entries);
+
+            let old_by_key = old_keyed.iter().map(|(key, entry)| (key.as_str(), *entry)).collect::<HashMap<_, _>>();
+            let new_by_key = new_keyed.iter().map(|(key, entry)| (key.as_str(), *entry)).collect::<HashMap<_, _>>();
+
+            for (key, old_entry) in &old_keyed {
+                match new_by_key.get(key.as_str()) {
+                    Some(new_entry) => {
+                        let mut changes = Vec::new();
+                        diff_values("", old_entry, new_entry, &mut changes);
+
+                        if !changes.is_empty() {
+                            diff.modified.push(EntryDiff { key: key.clone(), changes });
+                        }
+                    }
+                    None => diff.removed.push(EntryChange { key: key.clone(), entry: (*old_entry).clone() })
+                }
+            }
+
+            for (key, new_entry) in &new_keyed {
+                if !old_by_key.contains_key(key.as_str()) {
+                    diff.added.push(EntryChange { key: key.clone(), entry: (*new_entry).clone() });
+                }
+            }
+        }
+
+        _ => diff_values("", &old, &new, &mut diff.header)
+    }
+
+    if diff.header.is_empty() && diff.added.is_empty() && diff.removed.is_empty() && diff.modified.is_empty() {
+        return None;
+    }
+
+    Some(diff)
+}
+
+pub fn entries(value: &Value) -> Option<&Vec<Value>> {
+    value.get("entries").and_then(Value::as_array)
+}
+
+fn whole_chunk(chunk: &Chunk, status: Status) -> ChunkDiff {
+    ChunkDiff {
+        chunk: chunk.name.clone(),
+        format: chunk.nucc_binary_type,
+        status,
+        header: Vec::new(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        modified: Vec::new()
+    }
+}
+
+fn diff_header(old: &Value, new: &Value) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+
+    if let (Value::Object(old), Value::Object(new)) = (old, new) {
+        for (field, old_value) in old {
+            // The entry count follows from the added and removed entries
+            if field == "entries" || field == "entry_count" {
+                continue;
+            }
+
+            diff_values(field, old_value, new.get(field).unwrap_or(&Value::Null), &mut changes);
+        }
+    }
+
+    changes
+}
+
+/// Collects the changed leaves under `path`, arrays that changed length are reported whole
+pub fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (field, old_value) in old_map {
+                diff_values(&join(path, field), old_value, new_map.get(field).unwrap_or(&Value::Null), changes);
+            }
+
+            for (field, new_value) in new_map {
+                if !old_map.contains_key(field) {
+                    diff_values(&join(path, field), &Value::Null, new_value, changes);
+                }
+            }
+        }
+
+        (Value::Array(old_items), Value::Array(new_items)) if old_items.len() == new_items.len() => {
+            for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                diff_values(&format!("{}[{}]", path, index), old_item, new_item, changes);
+            }
+        }
+
+        _ => changes.push(FieldChange {
+            field: path.to_string(),
+            before: old.clone(),
+            after: new.clone()
+        })
+    }
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+
+impl fmt::Display for ChunkDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marker = match self.status {
+            Status::Added => '+',
+            Status::Removed => '-',
+            Status::Modified => '~'
+        };
+
+        writeln!(f, "{} {} ({})", marker, self.chunk, self.format)?;
+
+        for change in &self.header {
+            writeln!(f, "    {}", change)?;
+        }
+
+        for entry in &self.removed {
+            writeln!(f, "    - {}", entry.key)?;
+        }
+
+        for entry in &self.added {
+            writeln!(f, "    + {}", entry.key)?;
+        }
+
+        for entry in &self.modified {
+            writeln!(f, "    ~ {}", entry.key)?;
+
+            for change in &entry.changes {
+                writeln!(f, "        {}", change)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.before, self.after)
+    }
+}
@@ -0,0 +1,201 @@
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::NuccBinaryType;
+use crate::nucc_binary::fields::{describe, Credit, FieldMeta};
+use crate::nucc_binary::layout::FieldType;
+use crate::schema::description;
+use crate::validate::{Target, REFERENCES};
+
+
+/// Where the README format table links the formats' source files
+pub const SOURCE_URL: &str = "https://github.com/maxcabd/nuccbin/blob/main/src/nucc_binary/";
+
+/// Everything known about a format, as `nuccbin explain` shows it
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Explained {
+    pub format: NuccBinaryType,
+    pub source: String,
+    pub credit: Option<Credit>,
+
+    /// `None` for formats that aren't a table of fixed size entries, those are unpacked as is
+    pub entry_size: Option<usize>,
+    pub fields: Vec<FieldMeta>
+}
+
+
+pub fn explain(nucc_binary_type: NuccBinaryType) -> Explained {
+    let info = nucc_binary_type.info();
+
+    Explained {
+        format: nucc_binary_type,
+        source: format!("src/nucc_binary/{}", info.source),
+        credit: info.credit,
+        entry_size: nucc_binary_type.layout().map(|layout| layout.entry_size),
+        fields: describe(nucc_binary_type)
+    }
+}
+
+/// The fields called `name`, by their layout or json name. Arrays match every element, e.g. `location` matches `location[0]` to `location[2]`
+pub fn find_fields(nucc_binary_type: NuccBinaryType, name: &str) -> Result<Vec<FieldMeta>, String> {
+    let found = describe(nucc_binary_type).into_iter()
+        .flat_map(|field| std::iter::once(field.clone()).chain(field.fields))
+        .filter(|field| field.is(name))
+        .collect::<Vec<_>>();
+
+    match found.is_empty() {
+        true => Err(format!("{} has no field named {}", nucc_binary_type, name)),
+        false => Ok(found)
+    }
+}
+
+/// The references `nuccbin validate` checks from and to a field, e.g. `refers to PlayerSettingParam.player_setting_id`
+pub fn references(nucc_binary_type: NuccBinaryType, field: &str) -> Vec<String> {
+    let mut references = Vec::new();
+
+    for reference in REFERENCES.iter().filter(|reference| reference.from == nucc_binary_type && reference.field == field) {
+        match reference.to {
+            Target::Field(target, target_field) => references.push(format!("refers to {}.{}", target, target_field)),
+            Target::CharacodeIndex => references.push("refers to an index of Characode".to_string())
+        }
+    }
+
+    for reference in REFERENCES.iter().filter(|reference| matches!(reference.to, Target::Field(target, target_field) if target == nucc_binary_type && target_field == field)) {
+        references.push(format!("referred to by {}.{}", reference.from, reference.field));
+    }
+
+    references
+}
+
+
+pub fn render_format(explained: &Explained) -> String {
+    let mut text = format!("{}, {}\n", explained.format, explained.source);
+
+    if let Some(credit) = &explained.credit {
+        text.push_str(&format!("Reversed by {}\n", credit_text(credit)));
+    }
+
+    let entry_size = match explained.entry_size {
+        Some(entry_size) => entry_size,
+        None => {
+            text.push_str("Unpacked as is, without fields\n");
+            return text;
+        }
+    };
+
+    let header = explained.fields.iter().filter(|field| field.header).collect::<Vec<_>>();
+
+    if !header.is_empty() {
+        text.push_str("\nHeader\n");
+        header.iter().for_each(|field| field_lines(&mut text, field, 1));
+    }
+
+    text.push_str(&format!("\nEntry, {:#x} bytes\n", entry_size));
+    explained.fields.iter().filter(|field| !field.header).for_each(|field| field_lines(&mut text, field, 1));
+
+    text
+}
+
+pub fn render_fields(nucc_binary_type: NuccBinaryType, fields: &[FieldMeta]) -> String {
+    let mut text = String::new();
+
+    for field in fields {
+        let place = if field.header { "in the header" } else { "in each entry" };
+
+        text.push_str(&format!("{}.{}\n", nucc_binary_type, field.name));
+        text.push_str(&format!("  json:    {}\n", field.json.as_deref().unwrap_or("not unpacked")));
+        text.push_str(&format!("  type:    {}\n", field.field_type.name()));
+        text.push_str(&format!("  offset:  {:#x} {}\n", field.offset, place));
+        text.push_str(&format!("  meaning: {}\n", field.meaning.unwrap_or("unknown")));
+
+        for (value, name) in field.values {
+            text.push_str(&format!("  value:   {} = {}\n", value, name));
+        }
+
+        for reference in field.json.as_deref().map(|json| references(nucc_binary_type, json)).unwrap_or_default() {
+            text.push_str(&format!("  {}\n", reference));
+        }
+
+        text.push('\n');
+    }
+
+    text
+}
+
+/// The README's format table, one row per format
+pub fn format_table() -> String {
+    let mut table = String::from("| Format | Serialize | Deserialize | Extension | Known fields | Reversed by |\n| --- | --- | --- | --- | --- | --- |\n");
+
+    for nucc_binary_type in NuccBinaryType::iter() {
+        let info = nucc_binary_type.info();
+
+        let extension = match nucc_binary_type.layout() {
+            Some(_) => String::from("json"),
+            None => nucc_binary_type.to_string().to_lowercase()
+        };
+
+        let fields = describe(nucc_binary_type).into_iter()
+            .flat_map(|field| std::iter::once(field.clone()).chain(field.fields))
+            .filter(|field| field.json.is_some() && !matches!(field.field_type, FieldType::Array { .. }))
+            .collect::<Vec<_>>();
+
+        let known = match fields.is_empty() {
+            true => String::from("-"),
+            false => format!("{} of {}", fields.iter().filter(|field| field.meaning.is_some()).count(), fields.len())
+        };
+
+        let credit = match info.credit {
+            Some(Credit { name, url: Some(url) }) => format!("[{}]({})", name, url),
+            Some(Credit { name, url: None }) => name.to_string(),
+            None => String::new()
+        };
+
+        let deserialize = if info.deserialize { "✔️" } else { "❌" };
+
+        table.push_str(&format!("| [{}]({}{}) | ✔️ | {} | `{}` | {} | {} |\n", nucc_binary_type, SOURCE_URL, info.source, deserialize, extension, known, credit));
+    }
+
+    table
+}
+
+
+fn credit_text(credit: &Credit) -> String {
+    match credit.url {
+        Some(url) => format!("{} ({})", credit.name, url),
+        None => credit.name.to_string()
+    }
+}
+
+fn field_lines(text: &mut String, field: &FieldMeta, depth: usize) {
+    let name = match &field.json {
+        Some(json) if json != field.name => format!("{} ({})", json, field.name),
+        Some(json) => json.clone(),
+        None => format!("{} (not unpacked)", field.name)
+    };
+
+    let indent = "  ".repeat(depth);
+    let line = format!("{}{:<7} {:<12} {:<32} {}", indent, format!("{:#x}", field.offset), field.field_type.name(), name, description(field).unwrap_or_default());
+
+    text.push_str(line.trim_end());
+    text.push('\n');
+
+    for sub_field in &field.fields {
+        field_lines(text, sub_field, depth + 1);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readme_table_test() {
+        let readme = include_str!("../README.md").replace("\r\n", "\n");
+
+        let start = readme.find("| Format |").unwrap();
+        let end = readme[start..].find("\n\n").map_or(readme.len(), |end| start + end + 1);
+
+        assert_eq!(&readme[start..end], format_table(), "The README's format table is out of date, replace it with the output of `nuccbin explain --markdown`");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,224 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::NuccBinaryType;
+use crate::nucc_binary::to_json;
+use crate::project::IdRange;
+use crate::validate::{Target, REFERENCES};
+
+
+/// Which id every `"auto"` placeholder got, kept next to the project file so later repacks give them the same ones
+pub const IDS_FILE: &str = "nuccbin.ids.json";
+
+pub const AUTO: &str = "auto";
+
+/// Placeholder ids by format, then by placeholder name.
+/// Placeholders without a name are stored as `<chunk>[<entry index>]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ids(pub BTreeMap<String, BTreeMap<String, i64>>);
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Assigned {
+    pub format: NuccBinaryType,
+    pub placeholder: String,
+    pub entry: usize,
+    pub field: String,
+    pub id: i64,
+
+    /// `false` when the placeholder kept the id it had from an earlier repack
+    pub new: bool
+}
+
+impl fmt::Display for Assigned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.new { "assigned" } else { "kept" };
+        write!(f, "{} {}: entry {}, {} {} {}", self.format, self.placeholder, self.entry, self.field, verb, self.id)
+    }
+}
+
+impl Ids {
+    /// Empty when the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read(path) {
+            Ok(data) => serde_json::from_slice(&data).map(Ids).map_err(|err| format!("{}: {}", path.display(), err)),
+            Err(_) => Ok(Ids::default())
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, to_json(&self.0)).map_err(|err| format!("Couldn't write {}: {}", path.display(), err))
+    }
+
+    pub fn get(&self, nucc_binary_type: NuccBinaryType, name: &str) -> Option<i64> {
+        self.0.get(&nucc_binary_type.to_string()).and_then(|names| names.get(name)).copied()
+    }
+}
+
+
+/// The name of an `"auto"` or `"auto:<name>"` placeholder, empty for a bare `"auto"`
+pub fn placeholder(value: &Value) -> Option<&str> {
+    match value.as_str()? {
+        AUTO => Some(""),
+        text => text.strip_prefix("auto:")
+    }
+}
+
+/// Whether a format has ids or refers to another format's ids, so it can have placeholders
+pub fn uses_ids(nucc_binary_type: NuccBinaryType) -> bool {
+    nucc_binary_type.id_field().is_some() || !id_references(nucc_binary_type).is_empty()
+}
+
+/// Fields referring to a format's id field, with the format they refer to
+pub fn id_references(nucc_binary_type: NuccBinaryType) -> Vec<(&'static str, NuccBinaryType)> {
+    REFERENCES.iter()
+        .filter(|reference| reference.from == nucc_binary_type)
+        .filter_map(|reference| match reference.to {
+            Target::Field(target, field) if target.id_field() == Some(field) => Some((reference.field, target)),
+            _ => None
+        })
+        .collect()
+}
+
+/// Replaces the placeholders in a chunk's unpacked json with ids.
+/// Id fields get the id their placeholder had before if it's still free, or the next free one, from `range` when the project reserves one.
+/// Fields referring to ids, e.g. `CostumeParam.player_setting_id`, get the id of the named placeholder, so its format has to be repacked first.
+pub fn assign_ids(nucc_binary_type: NuccBinaryType, chunk: &str, value: &mut Value, ids: &mut Ids, range: Option<IdRange>) -> Result<Vec<Assigned>, String> {
+    let mut assigned = Vec::new();
+
+    let entries = match value.get_mut("entries").and_then(Value::as_array_mut) {
+        Some(entries) => entries,
+        None => return Ok(assigned)
+    };
+
+    if let Some(field) = nucc_binary_type.id_field() {
+        let (min, max) = nucc_binary_type.layout()
+            .and_then(|layout| layout.field(field))
+            .and_then(|field| field.field_type.int_range())
+            .unwrap_or((i64::MIN, i64::MAX));
+
+        if let Some(range) = range.filter(|range| range.start < min.max(0) || range.end > max) {
+            return Err(format!("The {} id range {} to {} doesn't fit in {}, which holds {} to {}", nucc_binary_type, range.start, range.end, field, min.max(0), max));
+        }
+
+        let names = ids.0.entry(nucc_binary_type.to_string()).or_default();
+        let mut named = HashSet::new();
+
+        let mut used = entries.iter()
+            .filter_map(|entry| entry.get(field).and_then(Value::as_i64))
+            .collect::<HashSet<_>>();
+
+        for (index, entry) in entries.iter_mut().enumerate() {
+            let name = match entry.get(field).and_then(placeholder) {
+                Some("") => format!("{}[{}]", chunk, index),
+                Some(name) => name.to_string(),
+                None => continue
+            };
+
+            if !named.insert(name.clone()) {
+                return Err(format!("entry {}, {}: auto:{} is already the id of another entry", index, field, name));
+            }
+
+            // Ids that went to other placeholders stay theirs, even when their entries aren't in this chunk
+            let taken = names.iter()
+                .filter(|(other, _)| **other != name)
+                .map(|(_, id)| *id)
+                .collect::<HashSet<_>>();
+
+            let (id, new) = match names.get(&name) {
+                Some(&id) if !used.contains(&id) => (id, false),
+                _ => (next_free(&used, &taken, range, max).ok_or_else(|| match range {
+                    Some(range) => format!("No free {} left between {} and {} for {}", field, range.start, range.end, name),
+                    None => format!("No free {} left for {}", field, name)
+                })?, true)
+            };
+
+            used.insert(id);
+            names.insert(name.clone(), id);
+            entry[field] = id.into();
+
+            assigned.push(Assigned { format: nucc_binary_type, placeholder: name, entry: index, field: field.to_string(), id, new });
+        }
+    }
+
+    for (field, target) in id_references(nucc_binary_type) {
+        for (index, entry) in entries.iter_mut().enumerate() {
+            let name = match entry.get(field).and_then(placeholder) {
+                Some("") => return Err(format!("entry {}, {}: a reference needs a named placeholder, e.g. \"auto:<name>\"", index, field)),
+                Some(name) => name.to_string(),
+                None => continue
+            };
+
+            let id = ids.get(target, &name)
+                .ok_or_else(|| format!("entry {}, {}: auto:{} isn't a {} id yet, repack the {} that has it first", index, field, name, target, target))?;
+
+            entry[field] = id.into();
+
+            assigned.push(Assigned { format: nucc_binary_type, placeholder: name, entry: index, field: field.to_string(), id, new: false });
+        }
+    }
+
+    Ok(assigned)
+}
+
+/// Without a range, the id after the largest one, as long as it's at most `max`
+fn next_free(used: &HashSet<i64>, taken: &HashSet<i64>, range: Option<IdRange>, max: i64) -> Option<i64> {
+    match range {
+        Some(range) => (range.start..=range.end).find(|id| !used.contains(id) && !taken.contains(id)),
+        None => used.iter().chain(taken).max().map_or(Some(0), |largest| largest.checked_add(1)).filter(|id| *id <= max)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn repack_again_test() {
+        // (entries, placeholders ids as the first repack gives them)
+        let cases = [
+            (json!([{"player_setting_id": 5}, {"player_setting_id": "auto"}, {"player_setting_id": "auto:a"}]), vec![("psp[1]", 6), ("a", 7)]),
+            (json!([{"player_setting_id": "auto:a"}, {"player_setting_id": "auto:b"}, {"player_setting_id": 0}]), vec![("a", 1), ("b", 2)]),
+            (json!([{"player_setting_id": "auto:a"}]), vec![("a", 0)]),
+        ];
+
+        for (entries, expected) in cases {
+            let mut ids = Ids::default();
+
+            for repack in 0..2 {
+                let mut value = json!({ "entries": entries.clone() });
+                let assigned = assign_ids(NuccBinaryType::PlayerSettingParam, "psp", &mut value, &mut ids, None).unwrap();
+
+                let got = assigned.iter().map(|assigned| (assigned.placeholder.as_str(), assigned.id)).collect::<Vec<_>>();
+                assert_eq!(got, expected, "{}", entries);
+                assert!(assigned.iter().all(|assigned| assigned.new == (repack == 0)), "{}", entries);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_ids_test() {
+        let range = |start, end| Some(IdRange { start, end });
+
+        // (format, entries, reserved range)
+        let cases = [
+            (NuccBinaryType::PlayerSettingParam, json!([{"player_setting_id": "auto:a"}, {"player_setting_id": "auto:a"}]), None),
+            (NuccBinaryType::PlayerSettingParam, json!([{"player_setting_id": u32::MAX}, {"player_setting_id": "auto"}]), None),
+            (NuccBinaryType::CostumeParam, json!([{"index": u32::MAX}, {"index": "auto"}]), None),
+            (NuccBinaryType::PlayerSettingParam, json!([{"player_setting_id": "auto"}]), range(-1, 10)),
+            (NuccBinaryType::PlayerSettingParam, json!([{"player_setting_id": "auto"}]), range(0, u32::MAX as i64 + 1)),
+            (NuccBinaryType::PlayerSettingParam, json!([{"player_setting_id": 0}, {"player_setting_id": "auto"}]), range(0, 0)),
+        ];
+
+        for (nucc_binary_type, entries, range) in cases {
+            let mut value = json!({ "entries": entries.clone() });
+            assert!(assign_ids(nucc_binary_type, "chunk", &mut value, &mut Ids::default(), range).is_err(), "{}", entries);
+        }
+    }
+}
\ No newline at end of file
@@ -2,7 +2,26 @@ use strum_macros::{EnumString, EnumIter, Display};
 use regex::Regex;
 
 pub mod args;
+pub mod characodes;
+pub mod chunks;
+pub mod conflicts;
+pub mod diff;
+pub mod explain;
+pub mod ids;
+pub mod lint;
+pub mod merge;
 pub mod nucc_binary;
+pub mod patch;
+pub mod project;
+pub mod render_settings;
+pub mod roster;
+pub mod rotation;
+pub mod schema;
+pub mod select;
+pub mod skeleton;
+pub mod split;
+pub mod textconv;
+pub mod validate;
 
 
 #[derive(Debug, Copy, Clone, EnumString, EnumIter, Display, PartialEq, Hash, Eq)]
@@ -90,6 +109,73 @@ impl NuccBinaryType {
             NuccBinaryType::Xml => { Regex::new(r"(\.xml)$").unwrap() }
         }
     }
+
+    /// Fields that identify an entry across files, e.g. when diffing or merging. Empty for formats matched by position
+    pub fn entry_key(&self) -> &'static [&'static str] {
+        match self {
+            NuccBinaryType::AccessoriesParam => &["accessory"],
+            NuccBinaryType::AccessoryExceptionParam => &["modelcode"],
+            NuccBinaryType::AccessoryParam => &["accessory"],
+            NuccBinaryType::AnimeSongBgmParam => &["bgm_name"],
+            NuccBinaryType::Characode => &["characode"],
+            NuccBinaryType::CharaPoseParam => &["player_setting_id", "pose_index"],
+            NuccBinaryType::CharacterSelectParam => &["searchcode"],
+            NuccBinaryType::CommandListParam => &["characode", "costume_index", "command_order"],
+            NuccBinaryType::CostumeBreakParam => &["characode_index", "costume_index"],
+            NuccBinaryType::CostumeParam => &["player_setting_id", "color_index"],
+            NuccBinaryType::DictionaryCharacterParam => &["dictionary_link"],
+            NuccBinaryType::EffectPrm => &["effect_id"],
+            NuccBinaryType::MessageInfo => &["crc32"],
+            NuccBinaryType::OugiFinishParam => &["search_code"],
+            NuccBinaryType::PlayerDoubleEffectParam => &["characode_index"],
+            NuccBinaryType::PlayerIcon => &["characode_index", "duel_player_param_costume_index"],
+            NuccBinaryType::PlayerSettingParam => &["player_setting_id"],
+            NuccBinaryType::ProhibitedSubstringParam => &["substring"],
+            NuccBinaryType::SkillIndexSettingParam => &["characode_index"],
+            NuccBinaryType::SupportActionParam => &["characode_index"],
+            NuccBinaryType::SupportSkillRecoverySpeedParam => &["characode_index"],
+            _ => &[]
+        }
+    }
+
+    /// Entry fields holding an index into the Characode table
+    pub fn characode_fields(&self) -> &'static [&'static str] {
+        match self {
+            NuccBinaryType::CostumeBreakParam => &["characode_index"],
+            NuccBinaryType::PlayerDoubleEffectParam => &["characode_index"],
+            NuccBinaryType::PlayerIcon => &["characode_index"],
+            NuccBinaryType::PlayerSettingParam => &["characode_index", "main_characode_index"],
+            NuccBinaryType::SkillIndexSettingParam => &["characode_index"],
+            NuccBinaryType::SupportActionParam => &["characode_index"],
+            NuccBinaryType::SupportSkillRecoverySpeedParam => &["characode_index"],
+            _ => &[]
+        }
+    }
+
+    /// The entry field numbering the entries, which repack fills in for `"auto"` placeholders
+    pub fn id_field(&self) -> Option<&'static str> {
+        match self {
+            NuccBinaryType::AccessoriesParam => Some("index"),
+            NuccBinaryType::CharaPoseParam => Some("index"),
+            NuccBinaryType::CostumeParam => Some("index"),
+            NuccBinaryType::OugiFinishParam => Some("index"),
+            NuccBinaryType::PlayerSettingParam => Some("player_setting_id"),
+            _ => None
+        }
+    }
+}
+
+impl serde::Serialize for NuccBinaryType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for NuccBinaryType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+        name.parse().map_err(|_| serde::de::Error::custom(format!("Unknown format: {}", name)))
+    }
 }
 
 
@@ -117,4 +203,64 @@ mod tests {
             characode.serialize(); // Serialize to JSON
         }
     }*/
+
+    #[test]
+    fn canonical_output_test() {
+        use super::nucc_binary::{canonical, NuccBinaryParsed, NuccBinaryParsedDeserializer, NuccBinaryParsedReader, NuccBinaryParsedWriter};
+
+        canonical::set_canonical(true);
+
+        // Hand written, with the floats spelled differently than they get exported
+        let json = br#"{"entry_count": 1, "entries": [{"characode_index": 7,
+            "skll1": 1.50, "skll2": -0.0, "skll3": 1E-7, "skll4": "0x7fc00000",
+            "skll5": 100000000, "skll6": 0.1, "awa_skll1": 3, "awa_skll2": "0xFF800000"}]}"#;
+
+        let nucc_binary_type = NuccBinaryType::SupportSkillRecoverySpeedParam;
+        let deserializer = NuccBinaryParsedDeserializer(nucc_binary_type, json.to_vec());
+        let data: Vec<u8> = NuccBinaryParsedWriter(deserializer.into()).into();
+
+        let export = |data: &[u8]| {
+            let nucc_binary_parsed: Box<dyn NuccBinaryParsed> = NuccBinaryParsedReader(nucc_binary_type, data).into();
+            String::from_utf8(nucc_binary_parsed.serialize()).unwrap()
+        };
+
+        let exported = export(&data);
+        assert_eq!(exported, EXPECTED);
+
+        // Exporting again, or exporting what the canonical file repacks to, gives the same bytes
+        assert_eq!(export(&data), exported);
+
+        let deserializer = NuccBinaryParsedDeserializer(nucc_binary_type, exported.clone().into_bytes());
+        let repacked: Vec<u8> = NuccBinaryParsedWriter(deserializer.into()).into();
+        assert_eq!(repacked, data);
+        assert_eq!(export(&repacked), exported);
+
+        // Exports that are edited as json values keep the struct's field order, e.g. with characode names
+        let characodes = crate::characodes::Characodes { names: (1..=7).map(|index| format!("{}chr", index)).collect() };
+
+        let mut value = serde_json::from_str(&exported).unwrap();
+        crate::characodes::name_characodes(nucc_binary_type, &mut value, &characodes);
+
+        let named = String::from_utf8(super::nucc_binary::to_json(&value)).unwrap();
+        assert_eq!(named, EXPECTED.replace("\"characode_index\": 7", "\"characode\": \"7chr\""));
+    }
+
+    const EXPECTED: &str = concat!(
+        "{\n",
+        "  \"entry_count\": 1,\n",
+        "  \"entries\": [\n",
+        "    {\n",
+        "      \"characode_index\": 7,\n",
+        "      \"skll1\": 1.5,\n",
+        "      \"skll2\": -0.0,\n",
+        "      \"skll3\": 1e-7,\n",
+        "      \"skll4\": \"0x7FC00000\",\n",
+        "      \"skll5\": 100000000.0,\n",
+        "      \"skll6\": 0.1,\n",
+        "      \"awa_skll1\": 3.0,\n",
+        "      \"awa_skll2\": \"0xFF800000\"\n",
+        "    }\n",
+        "  ]\n",
+        "}\n",
+    );
 }
\ No newline at end of file
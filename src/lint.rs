@@ -0,0 +1,204 @@
+use std::fmt;
+
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::NuccBinaryType;
+use crate::chunks::Chunk;
+use crate::diff::{entries, keyed_entries};
+use crate::nucc_binary::{text_encoding, NuccBinaryParsed, NuccBinaryParsedDeserializer};
+use crate::nucc_binary::lint::{Rule, Severity};
+use crate::project::Project;
+
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    /// `<format>/<rule>`, what the project file uses to change its severity
+    pub rule: String,
+    pub severity: Severity,
+    pub file: String,
+    pub chunk: String,
+    pub entry: String,
+    pub field: String,
+    pub message: String
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}] {} / {}: entry {}, {} {}", self.severity, self.rule, self.file, self.chunk, self.entry, self.field, self.message)
+    }
+}
+
+
+/// Reported as `<format>/invalid`, always an error, for chunks whose json doesn't fit their format. Their rules aren't run.
+pub const INVALID: &str = "invalid";
+
+pub fn rule_id(nucc_binary_type: NuccBinaryType, rule: &Rule) -> String {
+    format!("{}/{}", nucc_binary_type, rule.name)
+}
+
+/// Every rule of every format
+pub fn rules() -> Vec<(NuccBinaryType, &'static Rule)> {
+    NuccBinaryType::iter()
+        .flat_map(|nucc_binary_type| nucc_binary_type.lint_rules().iter().map(move |rule| (nucc_binary_type, rule)))
+        .collect()
+}
+
+/// The rule's severity after the project's overrides, a full id wins over a rule name
+pub fn severity(project: &Project, nucc_binary_type: NuccBinaryType, rule: &Rule) -> Severity {
+    project.lint.get(&rule_id(nucc_binary_type, rule))
+        .or_else(|| project.lint.get(rule.name))
+        .copied()
+        .unwrap_or(rule.severity)
+}
+
+/// The rule's limit after the project's overrides, `None` for rules without one
+pub fn limit(project: &Project, nucc_binary_type: NuccBinaryType, rule: &Rule) -> Option<u64> {
+    rule.limit?;
+
+    project.lint_limits.get(&rule_id(nucc_binary_type, rule))
+        .or_else(|| project.lint_limits.get(rule.name))
+        .copied()
+        .or(rule.limit)
+}
+
+/// Overrides that don't match any rule, or limits for rules without one, most likely typos
+pub fn check_project(project: &Project) -> Result<(), String> {
+    let rules = rules();
+    let matches = |name: &String, (nucc_binary_type, rule): &(NuccBinaryType, &Rule)| *name == rule_id(*nucc_binary_type, rule) || name == rule.name;
+
+    for name in project.lint.keys() {
+        if !rules.iter().any(|rule| matches(name, rule)) {
+            return Err(format!("Unknown lint rule: {}", name));
+        }
+    }
+
+    for name in project.lint_limits.keys() {
+        if !rules.iter().any(|rule| matches(name, rule) && rule.1.limit.is_some()) {
+            return Err(format!("{} isn't a lint rule with a limit", name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the rules of every chunk's format, `chunks` are paired with the file they come from
+pub fn lint(chunks: &[(String, Chunk)], project: &Project) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    for (file, chunk) in chunks {
+        let rules = chunk.nucc_binary_type.lint_rules().iter()
+            .map(|rule| (rule, severity(project, chunk.nucc_binary_type, rule)))
+            .filter(|(_, severity)| *severity != Severity::Off)
+            .collect::<Vec<_>>();
+
+        let value = match chunk.json() {
+            Some(value) => value,
+            None => continue
+        };
+
+        let keys = entries(&value).map(|entries| keyed_entries(chunk.nucc_binary_type, entries)).unwrap_or_default();
+        let key = |entry: usize| keys.get(entry).map_or_else(|| entry.to_string(), |(key, _)| key.clone());
+
+        let deserializer = NuccBinaryParsedDeserializer(chunk.nucc_binary_type, chunk.data.clone());
+
+        let parsed: Box<dyn NuccBinaryParsed> = match text_encoding::with_encoding(chunk.encoding, || deserializer.check()) {
+            Ok(()) if rules.is_empty() => continue,
+            Ok(()) => text_encoding::with_encoding(chunk.encoding, || deserializer.into()),
+            Err(err) => {
+                // `entries[3].price: invalid type: ...`, named by the entry's key like the other issues
+                let (path, message) = err.split_once(": ").unwrap_or(("", &err));
+
+                let (entry, field) = match path.strip_prefix("entries[").and_then(|path| path.split_once(']')) {
+                    Some((index, field)) => (index.parse().map_or_else(|_| index.to_string(), key), field.trim_start_matches('.')),
+                    None => (String::new(), path)
+                };
+
+                issues.push(LintIssue {
+                    rule: format!("{}/{}", chunk.nucc_binary_type, INVALID),
+                    severity: Severity::Error,
+                    file: file.clone(),
+                    chunk: chunk.name.clone(),
+                    entry,
+                    field: field.to_string(),
+                    message: message.to_string()
+                });
+
+                continue;
+            }
+        };
+
+        for (rule, severity) in rules {
+            let limit = limit(project, chunk.nucc_binary_type, rule).unwrap_or_default();
+
+            for finding in (rule.check)(parsed.as_ref(), limit) {
+                issues.push(LintIssue {
+                    rule: rule_id(chunk.nucc_binary_type, rule),
+                    severity,
+                    file: file.clone(),
+                    chunk: chunk.name.clone(),
+                    entry: key(finding.entry),
+                    field: finding.field,
+                    message: finding.message
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(nucc_binary_type: NuccBinaryType, json: &str) -> (String, Chunk) {
+        let chunk = Chunk { name: nucc_binary_type.to_string(), nucc_binary_type, extension: String::from(".json"), data: json.as_bytes().to_vec(), encoding: encoding_rs::UTF_8 };
+        (String::from("mod.xfbin"), chunk)
+    }
+
+    #[test]
+    fn invalid_json_test() {
+        let chunks = [
+            chunk(NuccBinaryType::Anmofs, r#"{"anm_name": "x", "characode": "2nrt", "entry_count": 1, "entries": [{"frame_min": "one"}]}"#),
+            chunk(NuccBinaryType::Characode, r#"{"entry_count": 1, "entries": [{"characode": 5}]}"#),
+        ];
+
+        let issues = lint(&chunks, &Project::default());
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|issue| issue.severity == Severity::Error && issue.rule.ends_with("/invalid")));
+        assert_eq!((issues[0].entry.as_str(), issues[0].field.as_str()), ("0", "frame_min"));
+        assert_eq!((issues[1].entry.as_str(), issues[1].field.as_str()), ("5", "characode"));
+    }
+
+    #[test]
+    fn limit_test() {
+        let bgm = |bgm_name: &str, preview_start: u32, preview_length: u32| serde_json::json!({ "bgm_cue_id": 0, "index": 0, "unk3": 0, "unk4": 0,
+            "preview_start": preview_start, "preview_length": preview_length, "bgm_name": bgm_name, "bgm_artist": "" });
+
+        let json = serde_json::json!({ "version": 1001, "entry_count": 3, "entry_ptr": 8, "entries": [bgm("a", 0, 1000), bgm("b", 500_000, 200_000), bgm("c", u32::MAX, 1)] });
+        let chunks = [chunk(NuccBinaryType::AnimeSongBgmParam, &json.to_string())];
+
+        let rules = |project: &Project| lint(&chunks, project).into_iter().map(|issue| (issue.rule, issue.entry)).collect::<Vec<_>>();
+
+        // (limits, rules that fire)
+        let cases = [
+            (serde_json::json!({}), vec![("AnimeSongBgmParam/preview-out-of-range", "b"), ("AnimeSongBgmParam/preview-out-of-range", "c"), ("AnimeSongBgmParam/preview-overflow", "c")]),
+            (serde_json::json!({ "preview-out-of-range": 999 }), vec![("AnimeSongBgmParam/preview-out-of-range", "a"), ("AnimeSongBgmParam/preview-out-of-range", "b"), ("AnimeSongBgmParam/preview-out-of-range", "c"), ("AnimeSongBgmParam/preview-overflow", "c")]),
+            (serde_json::json!({ "AnimeSongBgmParam/preview-out-of-range": u64::MAX }), vec![("AnimeSongBgmParam/preview-overflow", "c")]),
+        ];
+
+        for (limits, expected) in cases {
+            let project = serde_json::from_value(serde_json::json!({ "lint_limits": limits })).unwrap();
+            check_project(&project).unwrap();
+
+            let expected = expected.into_iter().map(|(rule, entry)| (rule.to_string(), entry.to_string())).collect::<Vec<_>>();
+            assert_eq!(rules(&project), expected, "{}", limits);
+        }
+
+        let project = serde_json::from_value(serde_json::json!({ "lint_limits": { "frame-range": 1 } })).unwrap();
+        assert!(check_project(&project).is_err());
+    }
+}
\ No newline at end of file
@@ -1,75 +1,175 @@
-mod args;
-mod nucc_binary;
+mod commands;
+mod options;
 
 use std::{path::Path , fs::{self, create_dir_all}};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 
-use strum::IntoEnumIterator;
 use xfbin::{read_xfbin, write_xfbin};
 
 use xfbin::nucc::*;
 use xfbin::nucc_chunk::*;
 
-use nucc_binary::*;
 use nuccbin::NuccBinaryType;
-use args::*;
+use nuccbin::args::*;
+use nuccbin::characodes::{self, Characodes};
+use nuccbin::chunks::{find_nucc_binary_type, parse_chunk, ENCODING_FILE};
+use nuccbin::ids::{self, Ids, IDS_FILE};
+use nuccbin::nucc_binary::*;
+use nuccbin::project::Project;
+use nuccbin::render_settings;
+use nuccbin::rotation;
+use nuccbin::split;
+use nuccbin::nucc_binary::text_encoding::{self, TextEncoding};
+use options::Options;
 
 
 pub fn main() {
 
+    // Subcommands like `nuccbin diff a.xfbin b.xfbin` come before the usual unpack and repack
+    let cli_args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    if let Some(result) = commands::run(&cli_args) {
+        if let Err(err) = result {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
     let args: Args = Args::new().unwrap();
+    let options = Options::new().unwrap_or_else(|err| panic!("{}", err));
 
     if let Ok(metadata) = fs::metadata(args.filepath.clone()) {
 
         if metadata.is_file() && args.extension == "xfbin" {
             // When the object is an xfbin file, unpack it
-            unpack(args.clone());
+            unpack(args.clone(), &options);
         }
 
         if metadata.is_dir() {
             // When the object is a directory, repack it
-            repack(args.clone());
+            repack(args.clone(), &options);
         }
     }
 
 }
 
-fn find_nucc_binary_type(filepath: &String) -> Option<NuccBinaryType> {
-    for nucc_binary_type in NuccBinaryType::iter() {
-        let regex = nucc_binary_type.patterns();
-        if regex.is_match(filepath) {
-            return Some(nucc_binary_type);
-        }
-    }
-    None
-}
-
-fn unpack(args: Args) {
+fn unpack(args: Args, options: &Options) {
     let xfbin = read_xfbin(&args.filepath).unwrap();
 
     // Create a directory with the name of the xfbin to store the serialized binary chunks
     let directory = Path::new(&args.filename);
     create_dir_all(directory).unwrap();
-    
+
+    float::set_precision(options.float_precision);
+    canonical::set_canonical(options.canonical);
+
+    let characodes = load_characodes(options);
+
+    // Sorted so the file is the same every time
+    let mut encodings = BTreeMap::new();
    
     for chunk in &xfbin.get_chunks_by_type(NuccChunkType::NuccChunkBinary) {
         let nucc_binary = chunk.downcast_ref::<NuccBinary>().unwrap();
-        let data = nucc_binary.data.clone();
+        let mut data = nucc_binary.data.clone();
+        let chunk_name = &nucc_binary.struct_info.chunk_name;
 
         if let Some(nucc_binary_type) = find_nucc_binary_type(&nucc_binary.struct_info.filepath) { 
-            let reader = NuccBinaryParsedReader(nucc_binary_type, &data);
-            let nucc_binary_parsed: Box<dyn NuccBinaryParsed> = reader.into();
+            if options.raw {
+                match nucc_binary_type.layout().map(|layout| raw::raw_export(nucc_binary_type, layout, &data)) {
+                    Some(Ok(raw_chunk)) => {
+                        fs::write(directory.join(format!("{}.raw.json", chunk_name)), to_json(&raw_chunk)).unwrap();
+                    }
+                    Some(Err(err)) => println!("Couldn't export {} raw: {}", chunk_name, err),
+                    None => println!("Raw export isn't supported for {}", nucc_binary_type)
+                }
+            }
+
+            let pointer_errors = pointers::check_pointers(nucc_binary_type, &data);
+
+            if !pointer_errors.is_empty() {
+                for error in &pointer_errors {
+                    println!("{}: {}", chunk_name, error);
+                }
+
+                if !options.lenient {
+                    println!("Skipping {}, pass --lenient to unpack it with those strings left empty", chunk_name);
+                    continue;
+                }
+
+                pointers::clear_pointers(&mut data, &pointer_errors);
+            }
+
+            let (nucc_binary_parsed, encoding) = parse_chunk(nucc_binary_type, &data, options.encoding_for(nucc_binary_type));
+
+            if encoding != encoding_rs::UTF_8 {
+                encodings.insert(chunk_name.clone(), encoding.name());
+            }
+
             let ext = nucc_binary_parsed.extension();
+            let mut serialized = nucc_binary_parsed.serialize();
+
+            if let Some(characodes) = characodes.as_ref().filter(|_| !nucc_binary_type.characode_fields().is_empty()) {
+                let mut value = serde_json::from_slice(&serialized).unwrap();
+                characodes::name_characodes(nucc_binary_type, &mut value, characodes);
+                serialized = to_json(&value);
+            }
+
+            if options.group_render_settings && nucc_binary_type == NuccBinaryType::CharacterSelectParam {
+                let mut value = serde_json::from_slice(&serialized).unwrap();
+                render_settings::group(&mut value);
+                serialized = to_json(&value);
+            }
+
+            if let Some(view) = options.bodacc_rotation.filter(|_| nucc_binary_type == NuccBinaryType::BodAcc) {
+                let mut value = serde_json::from_slice(&serialized).unwrap();
+
+                for index in rotation::to_view(&mut value, view) {
+                    println!("{}: entry {} is kept as radians, its rotation wouldn't convert back from {} exactly", chunk_name, index, view);
+                }
 
-            fs::write(directory.join(format!("{}{}", &nucc_binary.struct_info.chunk_name, ext)), nucc_binary_parsed.serialize()).unwrap();
+                serialized = to_json(&value);
+            }
+
+            // Only one of the two forms is kept so repacking can't pick up a stale one
+            let file = directory.join(format!("{}{}", chunk_name, ext));
+            let split_dir = directory.join(chunk_name);
+
+            match split::split(nucc_binary_type, &serialized).filter(|_| options.split) {
+                Some(files) => {
+                    if split_dir.is_dir() {
+                        fs::remove_dir_all(&split_dir).unwrap();
+                    }
+                    create_dir_all(&split_dir).unwrap();
 
+                    for (name, data) in files {
+                        fs::write(split_dir.join(name), data).unwrap();
+                    }
+
+                    if file.is_file() {
+                        fs::remove_file(&file).unwrap();
+                    }
+                }
+                None => {
+                    if split::is_split(&split_dir) {
+                        fs::remove_dir_all(&split_dir).unwrap();
+                    }
+
+                    fs::write(file, serialized).unwrap();
+                }
+            }
         }
     }
+
+    if !encodings.is_empty() {
+        fs::write(directory.join(ENCODING_FILE), to_json(&encodings)).unwrap();
+    }
 }
 
 
-fn repack(args: Args) {
+fn repack(args: Args, options: &Options) {
     // Find the xfbin in the target folder with the same name as the folder
     let dir = Path::new(&args.directory);
     let xfbin_filepath = dir.join(format!("{}.xfbin", args.filepath.file_name().unwrap().to_str().unwrap()));
@@ -78,13 +178,41 @@ fn repack(args: Args) {
 
     let mut filepath_map = HashMap::new();
 
+    let characodes = load_characodes(options);
+
+    // "auto" ids come from the project's reserved ranges, and which ones they got is kept next to the project file
+    let project_path = Project::find(&args.filepath);
+    let project = project_path.as_ref()
+        .map(|path| Project::load(path).unwrap_or_else(|err| panic!("{}", err)))
+        .unwrap_or_default();
+
+    let ids_path = match &project_path {
+        Some(path) => path.with_file_name(IDS_FILE),
+        None => dir.join(IDS_FILE)
+    };
+    let mut placeholder_ids = Ids::load(&ids_path).unwrap_or_else(|err| panic!("{}", err));
+    let mut assigned = Vec::new();
+
+    // Encodings detected when the folder was unpacked
+    let encodings: HashMap<String, String> = fs::read(args.filepath.join(ENCODING_FILE))
+        .map(|data| serde_json::from_slice(&data).unwrap())
+        .unwrap_or_default();
+
     for entry in fs::read_dir(&args.filepath).unwrap() {
         let entry = entry.unwrap();
         let path = entry.path();
+
+        if path.file_name().unwrap() == ENCODING_FILE {
+            continue;
+        }
+
         let filepath = path.to_str().unwrap().to_string();
         
-        // Extract the chunk name from the filename
-        let chunk_name = path.file_stem().unwrap().to_str().unwrap().to_string();
+        // Extract the chunk name from the filename, split chunks are a folder named after the chunk
+        let chunk_name = match split::is_split(&path) {
+            true => path.file_name().unwrap().to_str().unwrap().to_string(),
+            false => path.file_stem().unwrap().to_str().unwrap().to_string()
+        };
         filepath_map.insert(chunk_name, filepath);
     }
 
@@ -99,13 +227,71 @@ fn repack(args: Args) {
 
         if let Some(filepath) = filepath_map.get(chunk_name) {
             if let Some(nucc_binary_type) = find_nucc_binary_type(&chunk_info.filepath) {
-                let serialized = fs::read(filepath).unwrap(); // Read each serialized binary chunk
+                // Read each serialized binary chunk, joining the entry files back together if it was split
+                let mut serialized = match split::is_split(Path::new(filepath)) {
+                    true => split::join(Path::new(filepath)).unwrap_or_else(|err| panic!("{}", err)),
+                    false => fs::read(filepath).unwrap()
+                };
+
+                // Characode names go back to their index
+                if !nucc_binary_type.characode_fields().is_empty() {
+                    let mut value = serde_json::from_slice(&serialized).unwrap();
+
+                    if characodes::uses_names(nucc_binary_type, &value) {
+                        let characodes = characodes.as_ref()
+                            .unwrap_or_else(|| panic!("{} uses characode names, pass --characodes <characode.bin.xfbin> to repack it", chunk_name));
+
+                        characodes::resolve_characodes(nucc_binary_type, &mut value, characodes)
+                            .unwrap_or_else(|err| panic!("{}: {}", chunk_name, err));
+                        serialized = to_json(&value);
+                    }
+                }
+
+                // Grouped render settings go back to their flat fields
+                if nucc_binary_type == NuccBinaryType::CharacterSelectParam {
+                    let mut value = serde_json::from_slice(&serialized).unwrap();
+
+                    if render_settings::is_grouped(&value) {
+                        render_settings::ungroup(&mut value).unwrap_or_else(|err| panic!("{}: {}", chunk_name, err));
+                        serialized = to_json(&value);
+                    }
+                }
+
+                // Rotations in degrees, quaternions or matrices go back to radians
+                if nucc_binary_type == NuccBinaryType::BodAcc {
+                    let mut value = serde_json::from_slice(&serialized).unwrap();
+
+                    if rotation::uses_views(&value) {
+                        rotation::from_views(&mut value).unwrap_or_else(|err| panic!("{}: {}", chunk_name, err));
+                        serialized = to_json(&value);
+                    }
+                }
+
+                if ids::uses_ids(nucc_binary_type) {
+                    let mut value = serde_json::from_slice(&serialized).unwrap();
+                    let range = project.id_range(nucc_binary_type).unwrap_or_else(|err| panic!("{}", err));
+
+                    let chunk_assigned = ids::assign_ids(nucc_binary_type, chunk_name, &mut value, &mut placeholder_ids, range)
+                        .unwrap_or_else(|err| panic!("{}: {}", chunk_name, err));
+
+                    if !chunk_assigned.is_empty() {
+                        serialized = to_json(&value);
+                        assigned.extend(chunk_assigned);
+                    }
+                }
+
+                let encoding = match options.encoding_for(nucc_binary_type) {
+                    Some(TextEncoding::Fixed(encoding)) => encoding,
+                    Some(TextEncoding::Auto) | None => encodings.get(chunk_name)
+                        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+                        .unwrap_or(encoding_rs::UTF_8)
+                };
 
                 let deserializer = NuccBinaryParsedDeserializer(nucc_binary_type, serialized);
                 let mut nucc_binary = nucc_binary.clone();
                 
                 nucc_binary.struct_info = chunk_info;
-                nucc_binary.data = NuccBinaryParsedWriter(deserializer.into()).into();
+                nucc_binary.data = text_encoding::with_encoding(encoding, || NuccBinaryParsedWriter(deserializer.into()).into());
 
                 nucc_binaries.push(nucc_binary);
             }
@@ -129,5 +315,16 @@ fn repack(args: Args) {
     
     
     write_xfbin(xfbin, &xfbin_filepath.as_path()).unwrap();
+
+    if !assigned.is_empty() {
+        for assigned in &assigned {
+            println!("{}", assigned);
+        }
+
+        placeholder_ids.save(&ids_path).unwrap_or_else(|err| panic!("{}", err));
+    }
 }
 
+fn load_characodes(options: &Options) -> Option<Characodes> {
+    options.characodes.as_ref().map(|path| Characodes::load(path).unwrap_or_else(|err| panic!("{}", err)))
+}
\ No newline at end of file
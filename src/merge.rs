@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::NuccBinaryType;
+use crate::chunks::parse_chunk;
+use crate::diff::keyed_entries;
+use crate::nucc_binary::*;
+
+
+/// A field, or a whole entry, that ours and theirs both changed in different ways.
+/// The merged result keeps ours.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Conflict {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<String>,
+
+    /// Empty when the whole entry conflicts, e.g. one side removed it and the other edited it
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub field: String,
+
+    pub base: Value,
+    pub ours: Value,
+    pub theirs: Value
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let (Some(entry), true) = (&self.entry, self.field.is_empty()) {
+            match (self.ours.is_null(), self.theirs.is_null()) {
+                (false, true) => return write!(f, "entry {}: edited by ours, removed by theirs", entry),
+                (true, false) => return write!(f, "entry {}: removed by ours, edited by theirs", entry),
+                _ => {}
+            }
+        }
+
+        match (&self.entry, self.field.is_empty()) {
+            (Some(entry), true) => write!(f, "entry {}", entry)?,
+            (Some(entry), false) => write!(f, "entry {}, {}", entry, self.field)?,
+            (None, _) => write!(f, "{}", self.field)?
+        }
+
+        write!(f, ": base {}, ours {}, theirs {}", describe(&self.base), describe(&self.ours), describe(&self.theirs))
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => String::from("(none)"),
+        Value::Object(_) => String::from("(entry)"),
+        value => value.to_string()
+    }
+}
+
+
+/// Merges the unpacked json of a chunk entry by entry, matching entries by the format's key fields.
+/// Edits to different entries or fields are combined, fields edited differently on both sides are reported as conflicts.
+pub fn merge(nucc_binary_type: NuccBinaryType, base: &Value, ours: &Value, theirs: &Value) -> (Value, Vec<Conflict>) {
+    let mut conflicts = Vec::new();
+
+    let entries = |value: &Value| value.get("entries").and_then(Value::as_array).cloned();
+
+    let merged = match (entries(base), entries(ours), entries(theirs)) {
+        (Some(base_entries), Some(our_entries), Some(their_entries)) => {
+            let mut merged = merge_value("", None, &without_entries(base), &without_entries(ours), &without_entries(theirs), &mut conflicts);
+            let merged_entries = merge_entries(nucc_binary_type, &base_entries, &our_entries, &their_entries, &mut conflicts);
+
+            // Back where ours has it, as it's left out of the header merge
+            if let Some(position) = ours.as_object().and_then(|map| map.keys().position(|key| key == "entry_count")) {
+                let map = merged.as_object_mut().unwrap();
+                map.shift_insert(position.min(map.len()), String::from("entry_count"), merged_entries.len().into());
+            }
+
+            merged["entries"] = Value::Array(merged_entries);
+            merged
+        }
+
+        _ => merge_value("", None, base, ours, theirs, &mut conflicts)
+    };
+
+    (merged, conflicts)
+}
+
+/// Merges unpacked files, json ones entry by entry and anything else as a whole
+pub fn merge_serialized(nucc_binary_type: NuccBinaryType, base: &[u8], ours: &[u8], theirs: &[u8]) -> (Vec<u8>, Vec<Conflict>) {
+    let parse = |data: &[u8]| serde_json::from_slice::<Value>(data).ok();
+
+    if let (Some(base), Some(ours), Some(theirs)) = (parse(base), parse(ours), parse(theirs)) {
+        let (merged, conflicts) = merge(nucc_binary_type, &base, &ours, &theirs);
+        return (to_json(&merged), conflicts);
+    }
+
+    if ours == theirs || base == theirs {
+        (ours.to_vec(), Vec::new())
+    } else if base == ours {
+        (theirs.to_vec(), Vec::new())
+    } else {
+        let size = |data: &[u8]| Value::from(format!("{} bytes", data.len()));
+
+        (ours.to_vec(), vec![Conflict {
+            entry: None,
+            field: String::from("data"),
+            base: size(base),
+            ours: size(ours),
+            theirs: size(theirs)
+        }])
+    }
+}
+
+/// Merges a chunk's binary data, going through the format's typed structs both ways
+pub fn merge_data(nucc_binary_type: NuccBinaryType, base: &[u8], ours: &[u8], theirs: &[u8]) -> Result<(Vec<u8>, Vec<Conflict>), String> {
+    let mut serialized = Vec::new();
+    let mut encoding = encoding_rs::UTF_8;
+
+    for (side, data) in [("base", base), ("ours", ours), ("theirs", theirs)] {
+        if let Some(error) = pointers::check_pointers(nucc_binary_type, data).first() {
+            return Err(format!("{}: {}", side, error));
+        }
+
+        let (nucc_binary_parsed, detected) = parse_chunk(nucc_binary_type, data, None);
+
+        // Strings are written back the way ours had them
+        if side == "ours" {
+            encoding = detected;
+        }
+
+        serialized.push(nucc_binary_parsed.serialize());
+    }
+
+    let (merged, conflicts) = merge_serialized(nucc_binary_type, &serialized[0], &serialized[1], &serialized[2]);
+
+    let deserializer = NuccBinaryParsedDeserializer(nucc_binary_type, merged);
+    let data = text_encoding::with_encoding(encoding, || NuccBinaryParsedWriter(deserializer.into()).into());
+
+    Ok((data, conflicts))
+}
+
+
+fn without_entries(value: &Value) -> Value {
+    let mut value = value.clone();
+
+    if let Value::Object(map) = &mut value {
+        map.shift_remove("entries");
+        map.shift_remove("entry_count");
+    }
+
+    value
+}
+
+/// Keeps ours' order, with entries only theirs added appended in their order
+fn merge_entries(nucc_binary_type: NuccBinaryType, base: &[Value], ours: &[Value], theirs: &[Value], conflicts: &mut Vec<Conflict>) -> Vec<Value> {
+    let base = keyed_entries(nucc_binary_type, base);
+    let ours = keyed_entries(nucc_binary_type, ours);
+    let theirs = keyed_entries(nucc_binary_type, theirs);
+
+    let by_key = |entries: &[(String, &Value)]| entries.iter()
+        .enumerate()
+        .map(|(index, (key, _))| (key.clone(), index))
+        .collect::<HashMap<_, _>>();
+
+    let (base_keys, our_keys, their_keys) = (by_key(&base), by_key(&ours), by_key(&theirs));
+
+    let find = |entries: &[(String, &Value)], keys: &HashMap<String, usize>, key: &str| keys.get(key)
+        .map(|&index| entries[index].1.clone());
+
+    let mut merged = Vec::new();
+
+    for (key, our_entry) in &ours {
+        let entry = Some(key.clone());
+
+        match (find(&base, &base_keys, key), find(&theirs, &their_keys, key)) {
+            (Some(base_entry), Some(their_entry)) => {
+                merged.push(merge_value("", entry.as_ref(), &base_entry, our_entry, &their_entry, conflicts));
+            }
+
+            // Removed by theirs, unless ours edited it
+            (Some(base_entry), None) => {
+                if base_entry != **our_entry {
+                    conflicts.push(Conflict { entry, field: String::new(), base: base_entry, ours: (*our_entry).clone(), theirs: Value::Null });
+                    merged.push((*our_entry).clone());
+                }
+            }
+
+            // Added on both sides
+            (None, Some(their_entry)) => {
+                merged.push(merge_value("", entry.as_ref(), &Value::Null, our_entry, &their_entry, conflicts));
+            }
+
+            (None, None) => merged.push((*our_entry).clone())
+        }
+    }
+
+    for (key, their_entry) in &theirs {
+        if our_keys.contains_key(key) {
+            continue;
+        }
+
+        match find(&base, &base_keys, key) {
+            // Removed by ours, unless theirs edited it
+            Some(base_entry) => {
+                if base_entry != **their_entry {
+                    conflicts.push(Conflict { entry: Some(key.clone()), field: String::new(), base: base_entry, ours: Value::Null, theirs: (*their_entry).clone() });
+                }
+            }
+
+            None => merged.push((*their_entry).clone())
+        }
+    }
+
+    merged
+}
+
+fn merge_value(path: &str, entry: Option<&String>, base: &Value, ours: &Value, theirs: &Value, conflicts: &mut Vec<Conflict>) -> Value {
+    if ours == theirs || base == theirs {
+        return ours.clone();
+    }
+
+    if base == ours {
+        return theirs.clone();
+    }
+
+    match (ours, theirs) {
+        (Value::Object(our_map), Value::Object(their_map)) => {
+            let mut merged = our_map.clone();
+
+            let their_fields = their_map.keys().filter(|field| !our_map.contains_key(*field));
+
+            for field in our_map.keys().chain(their_fields) {
+                let field_path = if path.is_empty() { field.clone() } else { format!("{}.{}", path, field) };
+                let get = |value: &Value| value.get(field).cloned().unwrap_or(Value::Null);
+
+                let value = merge_value(&field_path, entry, &get(base), &get(ours), &get(theirs), conflicts);
+
+                if !value.is_null() || our_map.contains_key(field) {
+                    merged.insert(field.clone(), value);
+                }
+            }
+
+            Value::Object(merged)
+        }
+
+        (Value::Array(our_items), Value::Array(their_items))
+            if our_items.len() == their_items.len() && base.as_array().map_or(false, |items| items.len() == our_items.len()) =>
+        {
+            let base_items = base.as_array().unwrap();
+
+            Value::Array((0..our_items.len())
+                .map(|index| merge_value(&format!("{}[{}]", path, index), entry, &base_items[index], &our_items[index], &their_items[index], conflicts))
+                .collect())
+        }
+
+        _ => {
+            conflicts.push(Conflict {
+                entry: entry.cloned(),
+                field: path.to_string(),
+                base: base.clone(),
+                ours: ours.clone(),
+                theirs: theirs.clone()
+            });
+
+            ours.clone()
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A SkillIndexSettingParam chunk of (characode_index, first_jutsu_skl_index, second_jutsu_skl_index) entries
+    fn chunk(entries: &[(u32, i32, i32)]) -> Vec<u8> {
+        let entries = entries.iter()
+            .map(|(characode_index, first, second)| json!({ "characode_index": characode_index, "first_jutsu_skl_index": first, "second_jutsu_skl_index": second }))
+            .collect::<Vec<_>>();
+
+        let json = json!({ "entry_count": entries.len(), "entries": entries });
+        let deserializer = NuccBinaryParsedDeserializer(NuccBinaryType::SkillIndexSettingParam, serde_json::to_vec(&json).unwrap());
+        NuccBinaryParsedWriter(deserializer.into()).into()
+    }
+
+    fn read(data: &[u8]) -> Vec<(u32, i32, i32)> {
+        let nucc_binary_parsed: Box<dyn NuccBinaryParsed> = NuccBinaryParsedReader(NuccBinaryType::SkillIndexSettingParam, data).into();
+        let json = serde_json::from_slice::<Value>(&nucc_binary_parsed.serialize()).unwrap();
+
+        json["entries"].as_array().unwrap().iter()
+            .map(|entry| (
+                entry["characode_index"].as_u64().unwrap() as u32,
+                entry["first_jutsu_skl_index"].as_i64().unwrap() as i32,
+                entry["second_jutsu_skl_index"].as_i64().unwrap() as i32
+            ))
+            .collect()
+    }
+
+    #[test]
+    fn merge_data_test() {
+        let base = [(1, 10, 11), (2, 20, 21)];
+
+        // (ours, theirs, merged entries, conflicts as entry and field)
+        let cases: [(&[(u32, i32, i32)], &[(u32, i32, i32)], &[(u32, i32, i32)], &[(&str, &str)]); 10] = [
+            // One side changed
+            (&[(1, 15, 11), (2, 20, 21)], &base, &[(1, 15, 11), (2, 20, 21)], &[]),
+            (&base, &[(1, 10, 11), (2, 20, 25)], &[(1, 10, 11), (2, 20, 25)], &[]),
+
+            // Different fields of the same entry, and the same change on both sides
+            (&[(1, 15, 11), (2, 20, 21)], &[(1, 10, 16), (2, 20, 21)], &[(1, 15, 16), (2, 20, 21)], &[]),
+            (&[(1, 15, 11), (2, 20, 21)], &[(1, 15, 11), (2, 20, 21)], &[(1, 15, 11), (2, 20, 21)], &[]),
+
+            // A real conflict keeps ours
+            (&[(1, 15, 11), (2, 20, 21)], &[(1, 17, 11), (2, 20, 21)], &[(1, 15, 11), (2, 20, 21)], &[("1", "first_jutsu_skl_index")]),
+
+            // Added or removed on one side
+            (&[(1, 10, 11), (2, 20, 21), (3, 30, 31)], &base, &[(1, 10, 11), (2, 20, 21), (3, 30, 31)], &[]),
+            (&base, &[(4, 40, 41), (1, 10, 11), (2, 20, 21)], &[(1, 10, 11), (2, 20, 21), (4, 40, 41)], &[]),
+            (&[(2, 20, 21)], &base, &[(2, 20, 21)], &[]),
+
+            // Removed by theirs but edited by ours, which keeps it
+            (&[(1, 10, 11), (2, 25, 21)], &[(1, 10, 11)], &[(1, 10, 11), (2, 25, 21)], &[("2", "")]),
+
+            // Removed by ours and added again by theirs
+            (&[(1, 10, 11)], &[(1, 10, 11), (2, 20, 21), (3, 30, 31)], &[(1, 10, 11), (3, 30, 31)], &[]),
+        ];
+
+        for (ours, theirs, expected, expected_conflicts) in cases {
+            let (merged, conflicts) = merge_data(NuccBinaryType::SkillIndexSettingParam, &chunk(&base), &chunk(ours), &chunk(theirs)).unwrap();
+
+            let conflicts = conflicts.iter()
+                .map(|conflict| (conflict.entry.clone().unwrap_or_default(), conflict.field.clone()))
+                .collect::<Vec<_>>();
+
+            assert_eq!(read(&merged), expected, "ours {:?}, theirs {:?}", ours, theirs);
+            assert_eq!(conflicts, expected_conflicts.iter().map(|(entry, field)| (entry.to_string(), field.to_string())).collect::<Vec<_>>(), "ours {:?}, theirs {:?}", ours, theirs);
+        }
+    }
+
+    #[test]
+    fn duplicate_key_test() {
+        // The second entry of a key is matched to the other sides' second one
+        let base = [(1, 10, 11), (1, 20, 21)];
+        let ours = [(1, 10, 11), (1, 25, 21)];
+        let theirs = [(1, 10, 16), (1, 20, 26)];
+
+        let (merged, conflicts) = merge_data(NuccBinaryType::SkillIndexSettingParam, &chunk(&base), &chunk(&ours), &chunk(&theirs)).unwrap();
+        assert_eq!(read(&merged), [(1, 10, 16), (1, 25, 26)]);
+        assert!(conflicts.is_empty());
+
+        let theirs = [(1, 10, 11), (1, 27, 21)];
+
+        let (merged, conflicts) = merge_data(NuccBinaryType::SkillIndexSettingParam, &chunk(&base), &chunk(&ours), &chunk(&theirs)).unwrap();
+        assert_eq!(read(&merged), [(1, 10, 11), (1, 25, 21)]);
+        assert_eq!(conflicts.iter().map(|conflict| conflict.entry.as_deref()).collect::<Vec<_>>(), [Some("1#2")]);
+    }
+
+    #[test]
+    fn merge_serialized_test() {
+        // Files that aren't json are merged as a whole: (ours, theirs, merged, conflicts)
+        let cases: [(&[u8], &[u8], &[u8], usize); 4] = [
+            (b"ours", b"base", b"ours", 0),
+            (b"base", b"theirs", b"theirs", 0),
+            (b"same", b"same", b"same", 0),
+            (b"ours", b"theirs", b"ours", 1),
+        ];
+
+        for (ours, theirs, expected, expected_conflicts) in cases {
+            let (merged, conflicts) = merge_serialized(NuccBinaryType::Lua, b"base", ours, theirs);
+
+            assert_eq!(merged, expected);
+            assert_eq!(conflicts.len(), expected_conflicts);
+        }
+    }
+}
@@ -1,12 +1,14 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, FieldInfo, Credit};
+use super::lint::{self, Rule, Severity, Finding};
 use super::HEADER_SIZE;
 
-// Format reversed by Kuroha Saenoki (https://www.youtube.com/@KurohaSaenoki)
 #[allow(non_snake_case)]
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
@@ -25,13 +27,13 @@ pub struct Entry {
     #[serde(skip)]
     pub accessory_ptr: u64,
 
-    pub ofsX: i32, // Left/Right
-    pub ofsZ: i32, // Towards/Away from camera
-    pub ofsY: i32, // Up/Down
+    pub ofsX: i32,
+    pub ofsZ: i32,
+    pub ofsY: i32,
 
-    pub rotY: i32, // Up/Down
-    pub rotZ: i32, // Left/Right
-    pub rotX: i32, // Left/Right
+    pub rotY: i32,
+    pub rotZ: i32,
+    pub rotX: i32,
 
     #[brw(pad_after = 4)]
     pub unlock_condition: u32,
@@ -69,6 +71,59 @@ pub struct AccessoriesParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x48,
+    entry: &[
+        Field::new("accessory_name_id_ptr", 0x0, Ptr),
+        Field::new("accessory_link_ptr", 0x8, Ptr),
+        Field::new("index", 0x10, U32),
+        Field::new("price", 0x14, U32),
+        Field::new("icon_ptr", 0x18, Ptr),
+        Field::new("accessory_ptr", 0x20, Ptr),
+        Field::new("ofsX", 0x28, I32),
+        Field::new("ofsZ", 0x2c, I32),
+        Field::new("ofsY", 0x30, I32),
+        Field::new("rotY", 0x34, I32),
+        Field::new("rotZ", 0x38, I32),
+        Field::new("rotX", 0x3c, I32),
+        Field::new("unlock_condition", 0x40, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "accessories_param.rs",
+    credit: Some(Credit::new("Kuroha Saenoki", Some("https://www.youtube.com/@KurohaSaenoki"))),
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("index", "Id of the accessory"),
+        fields::PRICE,
+        fields::UNLOCK_CONDITION,
+        FieldInfo::new("ofsX", "Offset left/right"),
+        FieldInfo::new("ofsZ", "Offset towards/away from the camera"),
+        FieldInfo::new("ofsY", "Offset up/down"),
+        FieldInfo::new("rotY", "Rotation up/down"),
+        FieldInfo::new("rotZ", "Rotation left/right"),
+        FieldInfo::new("rotX", "Rotation left/right"),
+    ]
+};
+
+pub const LINT_RULES: &[Rule] = &[
+    Rule { name: "zero-price-locked", severity: Severity::Warning, description: lint::ZERO_PRICE_LOCKED, limit: None, check: zero_price_locked },
+];
+
+fn zero_price_locked(parsed: &dyn NuccBinaryParsed, _: u64) -> Vec<Finding> {
+    let param = parsed.downcast_ref::<AccessoriesParam>().unwrap();
+    lint::zero_price_locked(param.entries.iter().map(|entry| (entry.price, entry.unlock_condition)))
+}
+
 impl NuccBinaryParsed for AccessoriesParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::AccessoriesParam
@@ -79,7 +134,7 @@ impl NuccBinaryParsed for AccessoriesParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -108,16 +163,6 @@ impl From<&[u8]> for AccessoriesParam {
         }
 
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -155,28 +200,6 @@ impl From<AccessoriesParam> for Vec<u8> {
 
         writer.write_le(&accessories_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in accessories_param.entries
             .iter_mut()
             .enumerate()
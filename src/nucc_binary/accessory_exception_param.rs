@@ -1,8 +1,10 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, FieldInfo};
 use super::HEADER_SIZE;
 
 #[binrw]
@@ -11,14 +13,21 @@ pub struct Entry {
     #[serde(skip)]
     pub modelcode_ptr: u64,
 
-    pub head: u32, // A value of 0x1 means there are exceptions
+    #[serde(with = "super::named::flag")]
+    pub head: u32,
+    #[serde(with = "super::named::flag")]
     pub face: u32,
+    #[serde(with = "super::named::flag")]
     pub shoulders: u32,
+    #[serde(with = "super::named::flag")]
     pub back_a: u32,
+    #[serde(with = "super::named::flag")]
     pub arms: u32,
     pub unk6: u32,
+    #[serde(with = "super::named::flag")]
     pub waist: u32,
     pub unk8: u32,
+    #[serde(with = "super::named::flag")]
     pub head_a: u32,
     pub unk10: u32,
     pub unk11: u32,
@@ -45,6 +54,49 @@ pub struct AccessoryExceptionParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x38,
+    entry: &[
+        Field::new("modelcode_ptr", 0x0, Ptr),
+        Field::new("head", 0x8, U32),
+        Field::new("face", 0xc, U32),
+        Field::new("shoulders", 0x10, U32),
+        Field::new("back_a", 0x14, U32),
+        Field::new("arms", 0x18, U32),
+        Field::new("unk6", 0x1c, U32),
+        Field::new("waist", 0x20, U32),
+        Field::new("unk8", 0x24, U32),
+        Field::new("head_a", 0x28, U32),
+        Field::new("unk10", 0x2c, U32),
+        Field::new("unk11", 0x30, U32),
+        Field::new("unk12", 0x34, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "accessory_exception_param.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("modelcode", "Model the exceptions are for"),
+        FieldInfo::new("head", "1 when the model has exceptions for head accessories").values(fields::FLAG),
+        FieldInfo::new("face", "1 when the model has exceptions for face accessories").values(fields::FLAG),
+        FieldInfo::new("shoulders", "1 when the model has exceptions for shoulder accessories").values(fields::FLAG),
+        FieldInfo::new("back_a", "1 when the model has exceptions for back accessories").values(fields::FLAG),
+        FieldInfo::new("arms", "1 when the model has exceptions for arm accessories").values(fields::FLAG),
+        FieldInfo::new("waist", "1 when the model has exceptions for waist accessories").values(fields::FLAG),
+        FieldInfo::new("head_a", "1 when the model has exceptions for head accessories").values(fields::FLAG),
+    ]
+};
+
 impl NuccBinaryParsed for AccessoryExceptionParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::AccessoryExceptionParam
@@ -55,7 +107,7 @@ impl NuccBinaryParsed for AccessoryExceptionParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -84,16 +136,6 @@ impl From<&[u8]> for AccessoryExceptionParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -123,28 +165,6 @@ impl From<AccessoryExceptionParam> for Vec<u8> {
         writer.write_le(&8u64).unwrap(); // Write the ptr to the entries
         writer.write_le(&accessory_exception_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in accessory_exception_param.entries
             .iter_mut()
             .enumerate()
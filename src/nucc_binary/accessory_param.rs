@@ -1,8 +1,10 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, FieldInfo};
 
 use super::HEADER_SIZE;
 
@@ -14,14 +16,19 @@ pub struct Entry {
     pub unk1: u32,
     pub unk2: u32,
 
-    pub head: u32, // A value of 0x1 means it's a head accessory
+    #[serde(with = "super::named::flag")]
+    pub head: u32,
+    #[serde(with = "super::named::flag")]
     pub face: u32,
     pub unk5: u32,
     pub unk6: u32,
 
+    #[serde(with = "super::named::flag")]
     pub back: u32,
+    #[serde(with = "super::named::flag")]
     pub waist: u32,
     pub unk9: u32,
+    #[serde(with = "super::named::flag")]
     pub arms: u32,
 
     #[brw(ignore)]
@@ -44,6 +51,44 @@ pub struct AccessoryParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x30,
+    entry: &[
+        Field::new("accessory_ptr", 0x0, Ptr),
+        Field::new("unk1", 0x8, U32),
+        Field::new("unk2", 0xc, U32),
+        Field::new("head", 0x10, U32),
+        Field::new("face", 0x14, U32),
+        Field::new("unk5", 0x18, U32),
+        Field::new("unk6", 0x1c, U32),
+        Field::new("back", 0x20, U32),
+        Field::new("waist", 0x24, U32),
+        Field::new("unk9", 0x28, U32),
+        Field::new("arms", 0x2c, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "accessory_param.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("head", "1 when it's a head accessory").values(fields::FLAG),
+        FieldInfo::new("face", "1 when it's a face accessory").values(fields::FLAG),
+        FieldInfo::new("back", "1 when it's a back accessory").values(fields::FLAG),
+        FieldInfo::new("waist", "1 when it's a waist accessory").values(fields::FLAG),
+        FieldInfo::new("arms", "1 when it's an arm accessory").values(fields::FLAG),
+    ]
+};
+
 impl NuccBinaryParsed for AccessoryParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::AccessoryParam
@@ -54,7 +99,7 @@ impl NuccBinaryParsed for AccessoryParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -83,16 +128,6 @@ impl From<&[u8]> for AccessoryParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -124,29 +159,6 @@ impl From<AccessoryParam> for Vec<u8> {
         writer.write_le(&8u64).unwrap(); // Write the ptr to the entries
         writer.write_le(&accessory_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
-
         for (current_offset, entry) in accessory_param.entries
             .iter_mut()
             .enumerate()
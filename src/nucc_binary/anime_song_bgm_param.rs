@@ -1,9 +1,12 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo};
+use super::lint::{Rule, Severity, Finding};
 
 use super::HEADER_SIZE;
 
@@ -50,6 +53,66 @@ pub struct AnimeSongBgmParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x28,
+    entry: &[
+        Field::new("bgm_name_ptr", 0x0, Ptr),
+        Field::new("bgm_artist_ptr", 0x8, Ptr),
+        Field::new("bgm_cue_id", 0x10, U32),
+        Field::new("index", 0x14, U32),
+        Field::new("unk3", 0x18, U32),
+        Field::new("unk4", 0x1c, U32),
+        Field::new("preview_start", 0x20, U32),
+        Field::new("preview_length", 0x24, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "anime_song_bgm_param.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("preview_start", "Where the preview starts"),
+        FieldInfo::new("preview_length", "How long the preview plays"),
+    ]
+};
+
+pub const LINT_RULES: &[Rule] = &[
+    Rule { name: "preview-out-of-range", severity: Severity::Warning, description: "preview_start + preview_length is past the limit", limit: Some(600_000), check: preview_out_of_range },
+    Rule { name: "preview-overflow", severity: Severity::Error, description: "preview_start + preview_length doesn't fit in a u32, so the preview would end before it starts", limit: None, check: preview_overflow },
+];
+
+fn preview_out_of_range(parsed: &dyn NuccBinaryParsed, limit: u64) -> Vec<Finding> {
+    let param = parsed.downcast_ref::<AnimeSongBgmParam>().unwrap();
+
+    param.entries.iter().enumerate()
+        .map(|(index, entry)| (index, entry, entry.preview_start as u64 + entry.preview_length as u64))
+        .filter(|(_, _, end)| *end > limit)
+        .map(|(index, entry, end)| Finding::new(index, "preview_length", format!(
+            "{} ends the preview at {}, past the limit of {}", entry.preview_length, end, limit
+        )))
+        .collect()
+}
+
+fn preview_overflow(parsed: &dyn NuccBinaryParsed, _: u64) -> Vec<Finding> {
+    let param = parsed.downcast_ref::<AnimeSongBgmParam>().unwrap();
+
+    param.entries.iter().enumerate()
+        .filter(|(_, entry)| entry.preview_start.checked_add(entry.preview_length).is_none())
+        .map(|(index, entry)| Finding::new(index, "preview_length", format!(
+            "{} starting at {} ends the preview past the largest u32", entry.preview_length, entry.preview_start
+        )))
+        .collect()
+}
+
 impl NuccBinaryParsed for AnimeSongBgmParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::AnimeSongBgmParam
@@ -60,7 +123,7 @@ impl NuccBinaryParsed for AnimeSongBgmParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -88,16 +151,6 @@ impl From<&[u8]> for AnimeSongBgmParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -129,28 +182,6 @@ impl From<AnimeSongBgmParam> for Vec<u8> {
 
         writer.write_le(&anime_song_bgm_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in anime_song_bgm_param.entries
             .iter_mut()
             .enumerate()
@@ -2,7 +2,10 @@ use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, FixedString};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo};
+use super::lint::{Rule, Severity, Finding};
 
 const ANM_STR_LEN: usize = 0x40;
 const CHARACODE_LEN: usize = 0x10;
@@ -11,16 +14,22 @@ const CHARACODE_LEN: usize = 0x10;
 #[binrw]
 #[brw(little)]
 #[derive(Serialize, Deserialize, Debug)]
-pub struct Entry { // param entry, looks like each entry is 0x24 bytes? sometimes 0x20?
+pub struct Entry {
     pub frame_min: u32,
     pub frame_max: u32,
 
+    #[serde(with = "super::float")]
     pub ofsMinX: f32,
+    #[serde(with = "super::float")]
     pub ofsMinY: f32,
+    #[serde(with = "super::float")]
     pub ofsMinZ: f32,
 
+    #[serde(with = "super::float")]
     pub ofsMaxX: f32,
+    #[serde(with = "super::float")]
     pub ofsMaxY: f32,
+    #[serde(with = "super::float")]
     pub ofsMaxZ: f32,
 
     pub ofs_type: u32
@@ -29,13 +38,9 @@ pub struct Entry { // param entry, looks like each entry is 0x24 bytes? sometime
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Anmofs {
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = ANM_STR_LEN)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(ANM_STR_LEN - x.len()).as_str()).into_bytes())]
-    pub anm_name: String,
+    pub anm_name: FixedString<ANM_STR_LEN>,
 
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = CHARACODE_LEN)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(CHARACODE_LEN - x.len()).as_str()).into_bytes())]
-    pub characode: String, 
+    pub characode: FixedString<CHARACODE_LEN>, 
 
     pub entry_count: u32,
 
@@ -44,6 +49,52 @@ pub struct Anmofs {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("anm_name", 0x0, Str(ANM_STR_LEN)),
+        Field::new("characode", 0x40, Str(CHARACODE_LEN)),
+        Field::new("entry_count", 0x50, U32),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x58,
+    entry_size: 0x24,
+    entry: &[
+        Field::new("frame_min", 0x0, U32),
+        Field::new("frame_max", 0x4, U32),
+        Field::new("ofsMinX", 0x8, F32),
+        Field::new("ofsMinY", 0xc, F32),
+        Field::new("ofsMinZ", 0x10, F32),
+        Field::new("ofsMaxX", 0x14, F32),
+        Field::new("ofsMaxY", 0x18, F32),
+        Field::new("ofsMaxZ", 0x1c, F32),
+        Field::new("ofs_type", 0x20, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "anm_offset.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("frame_min", "First frame of the entry's offsets"),
+        FieldInfo::new("frame_max", "Last frame of the entry's offsets"),
+    ]
+};
+
+pub const LINT_RULES: &[Rule] = &[
+    Rule { name: "frame-range", severity: Severity::Error, description: "frame_min is after frame_max", limit: None, check: frame_range },
+];
+
+fn frame_range(parsed: &dyn NuccBinaryParsed, _: u64) -> Vec<Finding> {
+    let anmofs = parsed.downcast_ref::<Anmofs>().unwrap();
+
+    anmofs.entries.iter().enumerate()
+        .filter(|(_, entry)| entry.frame_min > entry.frame_max)
+        .map(|(index, entry)| Finding::new(index, "frame_min", format!("{} is after frame_max ({})", entry.frame_min, entry.frame_max)))
+        .collect()
+}
+
 impl NuccBinaryParsed for Anmofs {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::Anmofs
@@ -54,14 +105,14 @@ impl NuccBinaryParsed for Anmofs {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
         where
             Self: Sized,
         {   
-            serde_json::from_slice(data).unwrap()
+            super::from_json(data)
         }
 }
 
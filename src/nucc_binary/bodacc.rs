@@ -1,14 +1,15 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo, Credit};
 
 const HEADER_SIZE: usize = 0x14; // Size of NUCC Binary headers
 
 
-// Format reversed by Zinogre344
 #[allow(non_snake_case)]
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
@@ -24,12 +25,15 @@ pub struct Entry {
     #[brw(pad_after = 12)]
     pub accessory_location_ptr: u64,
 
+    #[serde(with = "super::float")]
     pub location: [f32; 3],
 
     #[brw(pad_after = 12)]
+    #[serde(with = "super::float")]
     pub rotation: [f32; 3],
 
     #[brw(pad_after = 4)]
+    #[serde(with = "super::float")]
     pub scale: [f32; 3],
 
 
@@ -61,6 +65,41 @@ pub struct BodAcc {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x60,
+    entry: &[
+        Field::new("accessory_ptr", 0x0, Ptr),
+        Field::new("bone_name_ptr", 0x8, Ptr),
+        Field::new("accessory_location_ptr", 0x18, Ptr),
+        Field::new("location[0]", 0x2c, F32),
+        Field::new("location[1]", 0x30, F32),
+        Field::new("location[2]", 0x34, F32),
+        Field::new("rotation[0]", 0x38, F32),
+        Field::new("rotation[1]", 0x3c, F32),
+        Field::new("rotation[2]", 0x40, F32),
+        Field::new("scale[0]", 0x50, F32),
+        Field::new("scale[1]", 0x54, F32),
+        Field::new("scale[2]", 0x58, F32),
+    ],
+    ptr_bias: 4
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "bodacc.rs",
+    credit: Some(Credit::new("Zinogre344", None)),
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("bone_name", "Bone of the model the accessory attaches to"),
+        FieldInfo::new("rotation", "Euler angles, taken as radians applied in X, Y, Z order"),
+    ]
+};
 
 impl NuccBinaryParsed for BodAcc {
     fn binary_type(&self) -> NuccBinaryType {
@@ -72,7 +111,7 @@ impl NuccBinaryParsed for BodAcc {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -101,16 +140,6 @@ impl From<&[u8]> for BodAcc {
         }
 
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -148,28 +177,6 @@ impl From<BodAcc> for Vec<u8> {
 
         writer.write_le(&bodacc.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in bodacc.entries
             .iter_mut()
             .enumerate()
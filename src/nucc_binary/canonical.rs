@@ -0,0 +1,19 @@
+//! Canonical output, so the same chunk always unpacks to byte identical files, whoever exports it.
+//!
+//! Fields keep the order of their struct, floats use the shortest decimal that reads back as the same `f32`
+//! (or their hex bits when they aren't finite), line endings are `\n` and every file ends with a single newline.
+
+use std::cell::Cell;
+
+
+thread_local! {
+    static CANONICAL: Cell<bool> = Cell::new(false);
+}
+
+pub fn set_canonical(canonical: bool) {
+    CANONICAL.with(|c| c.set(canonical));
+}
+
+pub fn is_canonical() -> bool {
+    CANONICAL.with(|c| c.get())
+}
@@ -1,8 +1,11 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, FieldInfo};
+use super::lint::{self, Rule, Severity, Finding};
 
 
 use super::HEADER_SIZE;
@@ -43,6 +46,47 @@ pub struct CharaPoseParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x20,
+    entry: &[
+        Field::new("chpo_name_id_ptr", 0x0, Ptr),
+        Field::new("index", 0x8, U32),
+        Field::new("player_setting_id", 0xc, U32),
+        Field::new("pose_index", 0x10, U32),
+        Field::new("price", 0x14, U32),
+        Field::new("unlock_condition", 0x18, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "chara_pose_param.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("index", "Id of the pose"),
+        fields::PLAYER_SETTING_ID,
+        fields::PRICE,
+        fields::UNLOCK_CONDITION,
+    ]
+};
+
+pub const LINT_RULES: &[Rule] = &[
+    Rule { name: "zero-price-locked", severity: Severity::Warning, description: lint::ZERO_PRICE_LOCKED, limit: None, check: zero_price_locked },
+];
+
+fn zero_price_locked(parsed: &dyn NuccBinaryParsed, _: u64) -> Vec<Finding> {
+    let param = parsed.downcast_ref::<CharaPoseParam>().unwrap();
+    lint::zero_price_locked(param.entries.iter().map(|entry| (entry.price, entry.unlock_condition)))
+}
+
 impl NuccBinaryParsed for CharaPoseParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::CharaPoseParam
@@ -53,7 +97,7 @@ impl NuccBinaryParsed for CharaPoseParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -82,16 +126,6 @@ impl From<&[u8]> for CharaPoseParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -123,28 +157,6 @@ impl From<CharaPoseParam> for Vec<u8> {
 
         writer.write_le(&chara_pose_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in chara_pose_param.entries
             .iter_mut()
             .enumerate()
@@ -1,14 +1,14 @@
 use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, FixedString};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo};
 
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = 8)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(8 - x.len()).as_str()).into_bytes())]
-    pub characode: String
+    pub characode: FixedString<8>
 }
 
 
@@ -22,6 +22,28 @@ pub struct Characode {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("entry_count", 0x0, U32),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x4,
+    entry_size: 0x8,
+    entry: &[
+        Field::new("characode", 0x0, Str(8)),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "characode.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("characode", "Code of the character, e.g. 2nrt. Its position in the table, starting at 1, is its characode index"),
+    ]
+};
+
 impl NuccBinaryParsed for Characode {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::Characode
@@ -32,7 +54,7 @@ impl NuccBinaryParsed for Characode {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -40,6 +62,6 @@ impl NuccBinaryParsed for Characode {
             Self: Sized,
 
         {   
-            serde_json::from_slice(data).unwrap()
+            super::from_json(data)
         }
 }
\ No newline at end of file
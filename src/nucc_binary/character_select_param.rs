@@ -1,9 +1,12 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo};
+use super::lint::{Rule, Severity, Finding};
 
 use super::HEADER_SIZE;
 
@@ -41,7 +44,7 @@ pub struct Entry {
     pub dictionary_link_ptr: u64,
 
     #[brw(pad_after = 4)]
-    pub index: i32, // not sure maybe an index?
+    pub index: i32,
 
 
     #[brw(ignore)]
@@ -73,85 +76,145 @@ pub struct Entry {
 #[binrw]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RenderSettings {
+    #[serde(with = "super::float")]
     pub ofsX1P: f32,
+    #[serde(with = "super::float")]
     pub ofsY1P: f32,
+    #[serde(with = "super::float")]
     pub ofsZ1P: f32,
 
+    #[serde(with = "super::float")]
     pub ofsX2P: f32,
+    #[serde(with = "super::float")]
     pub ofsY2P: f32,
+    #[serde(with = "super::float")]
     pub ofsZ2P: f32,
 
+    #[serde(with = "super::float")]
     pub selOfsX1P: f32,
+    #[serde(with = "super::float")]
     pub selOfsY1P: f32,
+    #[serde(with = "super::float")]
     pub selOfsZ1P: f32,
 
+    #[serde(with = "super::float")]
     pub selOfsX2P: f32,
+    #[serde(with = "super::float")]
     pub selOfsY2P: f32,
+    #[serde(with = "super::float")]
     pub selOfsZ2P: f32,
 
+    #[serde(with = "super::float")]
     pub vsOfsX1P: f32,
+    #[serde(with = "super::float")]
     pub vsOfsY1P: f32,
+    #[serde(with = "super::float")]
     pub vsOfsZ1P: f32,
 
+    #[serde(with = "super::float")]
     pub vsOfsX2P: f32,
+    #[serde(with = "super::float")]
     pub vsOfsY2P: f32,
+    #[serde(with = "super::float")]
     pub vsOfsZ2P: f32,
 
+    #[serde(with = "super::float")]
     pub rot1P: f32,
+    #[serde(with = "super::float")]
     pub rot2P: f32,
 
+    #[serde(with = "super::float")]
     pub selRot1P: f32,
+    #[serde(with = "super::float")]
     pub selRot2P: f32,
 
+    #[serde(with = "super::float")]
     pub vsRot1P: f32,
+    #[serde(with = "super::float")]
     pub vsRot2P: f32,
 
+    #[serde(with = "super::float")]
     pub lightX1P: f32,
+    #[serde(with = "super::float")]
     pub lightY1P: f32,
+    #[serde(with = "super::float")]
     pub lightZ1P: f32,
 
+    #[serde(with = "super::float")]
     pub lightX2P: f32,
+    #[serde(with = "super::float")]
     pub lightY2P: f32,
+    #[serde(with = "super::float")]
     pub lightZ2P: f32,
 
+    #[serde(with = "super::float")]
     pub selLightX1P: f32,
+    #[serde(with = "super::float")]
     pub selLightY1P: f32,
+    #[serde(with = "super::float")]
     pub selLightZ1P: f32,
 
+    #[serde(with = "super::float")]
     pub selLightX2P: f32,
+    #[serde(with = "super::float")]
     pub selLightY2P: f32,
+    #[serde(with = "super::float")]
     pub selLightZ2P: f32,
 
+    #[serde(with = "super::float")]
     pub vsLightX1P: f32,
+    #[serde(with = "super::float")]
     pub vsLightY1P: f32,
+    #[serde(with = "super::float")]
     pub vsLightZ1P: f32,
 
+    #[serde(with = "super::float")]
     pub vsLightX2P: f32,
+    #[serde(with = "super::float")]
     pub vsLightY2P: f32,
+    #[serde(with = "super::float")]
     pub vsLightZ2P: f32,
 
+    #[serde(with = "super::float")]
     pub unk_x3: f32,
+    #[serde(with = "super::float")]
     pub unk_y3: f32,
+    #[serde(with = "super::float")]
     pub unk_z3: f32,
 
+    #[serde(with = "super::float")]
     pub unk_x4: f32,
+    #[serde(with = "super::float")]
     pub unk_y4: f32,
+    #[serde(with = "super::float")]
     pub unk_z4: f32,
 
+    #[serde(with = "super::float")]
     pub unk_x5: f32,
+    #[serde(with = "super::float")]
     pub unk_y5: f32,
+    #[serde(with = "super::float")]
     pub unk_z5: f32,
 
+    #[serde(with = "super::float")]
     pub unk_x6: f32,
+    #[serde(with = "super::float")]
     pub unk_y6: f32,
+    #[serde(with = "super::float")]
     pub unk_z6: f32,
 
+    #[serde(with = "super::float")]
     pub unk_x7: f32,
+    #[serde(with = "super::float")]
     pub unk_y7: f32,
+    #[serde(with = "super::float")]
     pub unk_z7: f32,
 
+    #[serde(with = "super::float")]
     pub unk_x8: f32,
+    #[serde(with = "super::float")]
     pub unk_y8: f32,
+    #[serde(with = "super::float")]
     pub unk_z8: f32,
 
 }
@@ -171,6 +234,137 @@ pub struct CharacterSelectParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x140,
+    entry: &[
+        Field::new("searchcode_ptr", 0x0, Ptr),
+        Field::new("page_index", 0x8, U32),
+        Field::new("slot_index", 0xc, U32),
+        Field::new("costume_slot_index", 0x10, U32),
+        Field::new("char_name_ptr", 0x18, Ptr),
+        Field::new("duel_player_param_model_index", 0x20, U32),
+        Field::new("costume_name_ptr", 0x28, Ptr),
+        Field::new("accessory_ptr", 0x30, Ptr),
+        Field::new("crsel_ptr", 0x38, Ptr),
+        Field::new("render_settings.ofsX1P", 0x40, F32),
+        Field::new("render_settings.ofsY1P", 0x44, F32),
+        Field::new("render_settings.ofsZ1P", 0x48, F32),
+        Field::new("render_settings.ofsX2P", 0x4c, F32),
+        Field::new("render_settings.ofsY2P", 0x50, F32),
+        Field::new("render_settings.ofsZ2P", 0x54, F32),
+        Field::new("render_settings.selOfsX1P", 0x58, F32),
+        Field::new("render_settings.selOfsY1P", 0x5c, F32),
+        Field::new("render_settings.selOfsZ1P", 0x60, F32),
+        Field::new("render_settings.selOfsX2P", 0x64, F32),
+        Field::new("render_settings.selOfsY2P", 0x68, F32),
+        Field::new("render_settings.selOfsZ2P", 0x6c, F32),
+        Field::new("render_settings.vsOfsX1P", 0x70, F32),
+        Field::new("render_settings.vsOfsY1P", 0x74, F32),
+        Field::new("render_settings.vsOfsZ1P", 0x78, F32),
+        Field::new("render_settings.vsOfsX2P", 0x7c, F32),
+        Field::new("render_settings.vsOfsY2P", 0x80, F32),
+        Field::new("render_settings.vsOfsZ2P", 0x84, F32),
+        Field::new("render_settings.rot1P", 0x88, F32),
+        Field::new("render_settings.rot2P", 0x8c, F32),
+        Field::new("render_settings.selRot1P", 0x90, F32),
+        Field::new("render_settings.selRot2P", 0x94, F32),
+        Field::new("render_settings.vsRot1P", 0x98, F32),
+        Field::new("render_settings.vsRot2P", 0x9c, F32),
+        Field::new("render_settings.lightX1P", 0xa0, F32),
+        Field::new("render_settings.lightY1P", 0xa4, F32),
+        Field::new("render_settings.lightZ1P", 0xa8, F32),
+        Field::new("render_settings.lightX2P", 0xac, F32),
+        Field::new("render_settings.lightY2P", 0xb0, F32),
+        Field::new("render_settings.lightZ2P", 0xb4, F32),
+        Field::new("render_settings.selLightX1P", 0xb8, F32),
+        Field::new("render_settings.selLightY1P", 0xbc, F32),
+        Field::new("render_settings.selLightZ1P", 0xc0, F32),
+        Field::new("render_settings.selLightX2P", 0xc4, F32),
+        Field::new("render_settings.selLightY2P", 0xc8, F32),
+        Field::new("render_settings.selLightZ2P", 0xcc, F32),
+        Field::new("render_settings.vsLightX1P", 0xd0, F32),
+        Field::new("render_settings.vsLightY1P", 0xd4, F32),
+        Field::new("render_settings.vsLightZ1P", 0xd8, F32),
+        Field::new("render_settings.vsLightX2P", 0xdc, F32),
+        Field::new("render_settings.vsLightY2P", 0xe0, F32),
+        Field::new("render_settings.vsLightZ2P", 0xe4, F32),
+        Field::new("render_settings.unk_x3", 0xe8, F32),
+        Field::new("render_settings.unk_y3", 0xec, F32),
+        Field::new("render_settings.unk_z3", 0xf0, F32),
+        Field::new("render_settings.unk_x4", 0xf4, F32),
+        Field::new("render_settings.unk_y4", 0xf8, F32),
+        Field::new("render_settings.unk_z4", 0xfc, F32),
+        Field::new("render_settings.unk_x5", 0x100, F32),
+        Field::new("render_settings.unk_y5", 0x104, F32),
+        Field::new("render_settings.unk_z5", 0x108, F32),
+        Field::new("render_settings.unk_x6", 0x10c, F32),
+        Field::new("render_settings.unk_y6", 0x110, F32),
+        Field::new("render_settings.unk_z6", 0x114, F32),
+        Field::new("render_settings.unk_x7", 0x118, F32),
+        Field::new("render_settings.unk_y7", 0x11c, F32),
+        Field::new("render_settings.unk_z7", 0x120, F32),
+        Field::new("render_settings.unk_x8", 0x124, F32),
+        Field::new("render_settings.unk_y8", 0x128, F32),
+        Field::new("render_settings.unk_z8", 0x12c, F32),
+        Field::new("dictionary_link_ptr", 0x130, Ptr),
+        Field::new("index", 0x138, I32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "character_select_param.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("searchcode", "searchcode of the PlayerSettingParam preset in the slot"),
+        FieldInfo::new("page_index", "Page of the character select screen"),
+        FieldInfo::new("slot_index", "Slot on the page"),
+        FieldInfo::new("costume_slot_index", "Costume of the slot"),
+        FieldInfo::new("render_settings", "Where the model stands on the select, selection and versus screens, for 1P and 2P"),
+        FieldInfo::new("dictionary_link", "The DictionaryCharacterParam entry of the character"),
+        FieldInfo::new("index", "Not sure, maybe an index"),
+    ]
+};
+
+pub const LINT_RULES: &[Rule] = &[
+    Rule { name: "duplicate-slot", severity: Severity::Error, description: "Two entries on the same page, slot and costume slot", limit: None, check: duplicate_slot },
+    Rule { name: "empty-dictionary-link", severity: Severity::Warning, description: "Entry without a dictionary_link", limit: None, check: empty_dictionary_link },
+];
+
+fn duplicate_slot(parsed: &dyn NuccBinaryParsed, _: u64) -> Vec<Finding> {
+    let param = parsed.downcast_ref::<CharacterSelectParam>().unwrap();
+    let mut findings = Vec::new();
+
+    for (index, entry) in param.entries.iter().enumerate() {
+        let slot = (entry.page_index, entry.slot_index, entry.costume_slot_index);
+
+        if let Some(first) = param.entries[..index].iter().position(|other| (other.page_index, other.slot_index, other.costume_slot_index) == slot) {
+            findings.push(Finding::new(index, "slot_index", format!(
+                "{} is already taken on page {}, costume slot {} by {}", slot.1, slot.0, slot.2, param.entries[first].searchcode
+            )));
+        }
+    }
+
+    findings
+}
+
+fn empty_dictionary_link(parsed: &dyn NuccBinaryParsed, _: u64) -> Vec<Finding> {
+    let param = parsed.downcast_ref::<CharacterSelectParam>().unwrap();
+
+    param.entries.iter().enumerate()
+        .filter(|(_, entry)| entry.dictionary_link.is_empty())
+        .map(|(index, _)| Finding::new(index, "dictionary_link", String::from("is empty, the character won't have a dictionary page")))
+        .collect()
+}
+
 impl NuccBinaryParsed for CharacterSelectParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::CharacterSelectParam
@@ -181,7 +375,7 @@ impl NuccBinaryParsed for CharacterSelectParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -212,16 +406,6 @@ impl From<&[u8]> for CharacterSelectParam {
         }
 
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -259,28 +443,6 @@ impl From<CharacterSelectParam> for Vec<u8> {
 
         writer.write_le(&character_select_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in character_select_param.entries
             .iter_mut()
             .enumerate()
@@ -2,15 +2,28 @@ use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
 use super::{NuccBinaryParsed, NuccBinaryType};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo, Credit};
+use super::named::named_values;
 
 
-// Format reversed by Kuroha Saenoki (https://www.youtube.com/@KurohaSaenoki)
+named_values!(
+    /// Type of move
+    pub mod command: i32 {
+        1 => "neutral",
+        2 => "up",
+        3 => "down",
+        4 => "throw",
+    }
+);
+
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
     pub combo_begin_type: i32,
-    pub command: i32, // Type of move (neutral = 0x1, up = 0x2 , down = 0x3, throw = 0x4, etc)
-    pub delay: i32, // Delay before next move
+    #[serde(with = "command")]
+    pub command: i32,
+    pub delay: i32,
 
 
 }
@@ -32,6 +45,33 @@ pub struct ComboPrm {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0xc,
+    entry: &[
+        Field::new("combo_begin_type", 0x0, I32),
+        Field::new("command", 0x4, I32),
+        Field::new("delay", 0x8, I32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "combo_prm.rs",
+    credit: Some(Credit::new("Kuroha Saenoki", Some("https://www.youtube.com/@KurohaSaenoki"))),
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("command", "Type of move").values(command::NAMES),
+        FieldInfo::new("delay", "Delay before the next move"),
+    ]
+};
+
 impl NuccBinaryParsed for ComboPrm {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::ComboPrm
@@ -42,7 +82,7 @@ impl NuccBinaryParsed for ComboPrm {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -1,8 +1,10 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo};
 
 use super::HEADER_SIZE;
 
@@ -20,7 +22,7 @@ pub struct Entry {
     pub characode_ptr: u64,
 
     #[brw(pad_after = 4)]
-    pub costume_index: u32, // some flag
+    pub costume_index: u32,
 
     #[serde(skip)]
     pub command_name_ptr: u64,
@@ -35,8 +37,8 @@ pub struct Entry {
     pub unk2: u32,
     pub unk3: u32,
 
-    pub command_type: u32, // Usually 2 for a playable character's command list
-    pub unk5: u32, // 0
+    pub command_type: u32,
+    pub unk5: u32,
     pub unk6: i32,
     pub unk7: i32,
 
@@ -91,6 +93,48 @@ pub struct CommandListParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x68,
+    entry: &[
+        Field::new("command_link_ptr", 0x0, Ptr),
+        Field::new("command_order", 0x8, U32),
+        Field::new("char_name_ptr", 0x10, Ptr),
+        Field::new("characode_ptr", 0x18, Ptr),
+        Field::new("costume_index", 0x20, U32),
+        Field::new("command_name_ptr", 0x28, Ptr),
+        Field::new("btn_input_ptr", 0x30, Ptr),
+        Field::new("condition1_ptr", 0x38, Ptr),
+        Field::new("condition2_ptr", 0x40, Ptr),
+        Field::new("unk2", 0x48, U32),
+        Field::new("unk3", 0x4c, U32),
+        Field::new("command_type", 0x50, U32),
+        Field::new("unk5", 0x54, U32),
+        Field::new("unk6", 0x58, I32),
+        Field::new("unk7", 0x5c, I32),
+        Field::new("additional_command_link_ptr", 0x60, Ptr),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "command_list_param.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("characode", "Characode of the character"),
+        FieldInfo::new("costume_index", "Some flag"),
+        FieldInfo::new("command_type", "Usually 2 for a playable character's command list"),
+        FieldInfo::new("unk5", "Always 0 so far"),
+    ]
+};
+
 impl NuccBinaryParsed for CommandListParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::CommandListParam
@@ -101,7 +145,7 @@ impl NuccBinaryParsed for CommandListParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -130,16 +174,6 @@ impl From<&[u8]> for CommandListParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
             .iter_mut()
             .enumerate()
@@ -177,28 +211,6 @@ impl From<CommandListParam> for Vec<u8> {
 
         writer.write_le(&command_list_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in command_list_param.entries
             .iter_mut()
             .enumerate()
@@ -1,6 +1,7 @@
 use serde::{Serialize, Deserialize};
 
 use super::{NuccBinaryParsed, NuccBinaryType};
+use super::fields::FormatInfo;
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -8,6 +9,13 @@ pub struct Dds {
     pub file: Vec<u8>
 }
 
+pub const INFO: FormatInfo = FormatInfo {
+    source: "dds.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[]
+};
+
 impl NuccBinaryParsed for Dds {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::Dds
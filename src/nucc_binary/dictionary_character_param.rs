@@ -1,12 +1,13 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo, Credit};
 
 use super::HEADER_SIZE;
 
-// Format reversed by Portable Productions (https://www.youtube.com/@PortableProductions)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
@@ -147,6 +148,49 @@ pub struct DictionaryCharacterParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x1a8,
+    entry: &[
+        Field::new("dictionary_link_ptr", 0x8, Ptr),
+        Field::new("page_entry_index", 0x10, U32),
+        Field::new("page_number", 0x14, U32),
+        Field::new("lock_link_ptr", 0x18, Ptr),
+        Field::new("char_portrait_link_ptr", 0x28, Ptr),
+        Field::new("background_ptr", 0x30, Ptr),
+        Field::new("char_name_ptr", 0x38, Ptr),
+        Field::new("char_quote_ptr", 0x40, Ptr),
+        Field::new("ninja_reg_no_ptr", 0x48, Ptr),
+        Field::new("char_birthday_ptr", 0x50, Ptr),
+        Field::new("char_affiliation_ptr", 0x58, Ptr),
+        Field::new("char_height_ptr", 0x60, Ptr),
+        Field::new("char_weight_ptr", 0x68, Ptr),
+        Field::new("dictionary_desc_ptr", 0x70, Ptr),
+        Field::new("unk1", 0x78, I32),
+        Field::new("unk2", 0x7c, I32),
+        Field::new("unk3", 0x80, I32),
+        Field::new("additional_link1_ptr", 0x118, Ptr),
+        Field::new("additional_link2_ptr", 0x120, Ptr),
+        Field::new("additional_link3_ptr", 0x128, Ptr),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "dictionary_character_param.rs",
+    credit: Some(Credit::new("Portable Productions", Some("https://www.youtube.com/@PortableProductions"))),
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("dictionary_link", "What CharacterSelectParam's dictionary_link refers to"),
+    ]
+};
+
 impl NuccBinaryParsed for DictionaryCharacterParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::DictionaryCharacterParam
@@ -157,7 +201,7 @@ impl NuccBinaryParsed for DictionaryCharacterParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -184,16 +228,6 @@ impl From<&[u8]> for DictionaryCharacterParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -243,28 +277,6 @@ impl From<DictionaryCharacterParam> for Vec<u8> {
 
         writer.write_le(&dictionary_character_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in dictionary_character_param.entries
             .iter_mut()
             .enumerate()
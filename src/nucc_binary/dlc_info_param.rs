@@ -1,13 +1,27 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo, Credit};
+use super::named::named_values;
 
 
 const HEADER_SIZE: usize = 0x10; // Size of NUCC Binary headers
 
-// Format was reversed by https://github.com/al-hydra
+named_values!(
+    /// Type of DLC
+    pub mod kind: u32 {
+        0 => "character",
+        1 => "costume",
+        2 => "bgm",
+        3 => "season_pass",
+        4 => "accessories",
+        5 => "substitution_item",
+    }
+);
+
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
@@ -28,10 +42,11 @@ pub struct Entry {
     #[serde(skip)]
     pub xbox_content_id_ptr: u64,
 
-    pub index: u32, // ???
+    pub index: u32,
     pub unk1: u32,
 
-    pub kind: u32, // Type of DLC (0 = Character, 1 = Costume, 2 = BGM, 3 = Season Pass, 4 = Accesories, 5 = Subsitution Item)
+    #[serde(with = "kind")]
+    pub kind: u32,
     pub unk2: u32,
 
     #[serde(skip)]
@@ -123,6 +138,48 @@ pub struct DlcInfoParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x78,
+    entry: &[
+        Field::new("steam_app_id_ptr", 0x0, Ptr),
+        Field::new("ps5_content_id_ptr", 0x8, Ptr),
+        Field::new("ps4_content_id_ptr", 0x10, Ptr),
+        Field::new("nintendo_content_id_ptr", 0x18, Ptr),
+        Field::new("xbox_content_id_ptr", 0x20, Ptr),
+        Field::new("index", 0x28, U32),
+        Field::new("unk1", 0x2c, U32),
+        Field::new("kind", 0x30, U32),
+        Field::new("unk2", 0x34, U32),
+        Field::new("cpk_path_ptr", 0x38, Ptr),
+        Field::new("unk3", 0x40, U32),
+        Field::new("unk4", 0x44, U32),
+        Field::new("dummy2_ptr", 0x48, Ptr),
+        Field::new("dummy3_ptr", 0x50, Ptr),
+        Field::new("dummy4_ptr", 0x58, Ptr),
+        Field::new("unk5", 0x60, U32),
+        Field::new("unk6", 0x64, U32),
+        Field::new("dummy5_ptr", 0x68, Ptr),
+        Field::new("dummy6_ptr", 0x70, Ptr),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "dlc_info_param.rs",
+    credit: Some(Credit::new("al-hydra", Some("https://github.com/al-hydra"))),
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("kind", "Type of DLC").values(kind::NAMES),
+    ]
+};
+
 impl NuccBinaryParsed for DlcInfoParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::DlcInfoParam
@@ -133,7 +190,7 @@ impl NuccBinaryParsed for DlcInfoParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -161,16 +218,6 @@ impl From<&[u8]> for DlcInfoParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -214,28 +261,6 @@ impl From<DlcInfoParam> for Vec<u8> {
 
         writer.write_le(&dlc_info_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in dlc_info_param.entries
             .iter_mut()
             .enumerate()
@@ -1,7 +1,9 @@
 use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, FixedString};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::FormatInfo;
 
 const STR_LEN: usize = 0x40;
 
@@ -12,13 +14,9 @@ pub struct Entry {
     pub effect_id: u32,
     pub effect_type: u32,
 
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub effect_path: String,
+    pub effect_path: FixedString<STR_LEN>,
 
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)]
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub effect_name: String
+    pub effect_name: FixedString<STR_LEN>
 }
 
 #[binrw]
@@ -32,6 +30,29 @@ pub struct EffectPrm {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("entry_count", 0x0, U32),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x4,
+    entry_size: 0x88,
+    entry: &[
+        Field::new("effect_id", 0x0, U32),
+        Field::new("effect_type", 0x4, U32),
+        Field::new("effect_path", 0x8, Str(STR_LEN)),
+        Field::new("effect_name", 0x48, Str(STR_LEN)),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "effectprm.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[]
+};
+
 impl NuccBinaryParsed for EffectPrm {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::EffectPrm
@@ -42,7 +63,7 @@ impl NuccBinaryParsed for EffectPrm {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -50,6 +71,6 @@ impl NuccBinaryParsed for EffectPrm {
             Self: Sized,
 
         {   
-            serde_json::from_slice(data).unwrap()
+            super::from_json(data)
         }
 }
\ No newline at end of file
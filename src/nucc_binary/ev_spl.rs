@@ -1,19 +1,19 @@
 use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, FixedString};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, Credit};
 
 const STR_LEN: usize = 0x20;
 
-// Format was reversed by TheLeonX (https://github.com/TheLeonX)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub sound_name: String,
+    pub sound_name: FixedString<STR_LEN>,
 
     pub unk0: i16,
+    #[serde(with = "super::float")]
     pub volume: f32,
 
     #[brw(pad_after = 0x4)]
@@ -21,18 +21,16 @@ pub struct Entry {
 
     pub timing: i16,
 
+    #[serde(with = "super::float")]
     pub unk4: f32,
 
+    #[serde(with = "super::float")]
     pub unk5: f32,
 
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)]
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub anm_name: String,
+    pub anm_name: FixedString<STR_LEN>,
 
 
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)]
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub bone: String,
+    pub bone: FixedString<STR_LEN>,
 
 
     #[brw(pad_after = 0x4)]
@@ -43,9 +41,7 @@ pub struct Entry {
 
     pub unk10: i32,
 
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)]
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub pl_anm: String
+    pub pl_anm: FixedString<STR_LEN>
 }
 
 #[binrw]
@@ -58,6 +54,39 @@ pub struct EvSpl {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("entry_count", 0x0, U16),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x2,
+    entry_size: 0xaa,
+    entry: &[
+        Field::new("sound_name", 0x0, Str(STR_LEN)),
+        Field::new("unk0", 0x20, I16),
+        Field::new("volume", 0x22, F32),
+        Field::new("pitch", 0x26, I16),
+        Field::new("timing", 0x2c, I16),
+        Field::new("unk4", 0x2e, F32),
+        Field::new("unk5", 0x32, F32),
+        Field::new("anm_name", 0x36, Str(STR_LEN)),
+        Field::new("bone", 0x56, Str(STR_LEN)),
+        Field::new("unk6", 0x76, I32),
+        Field::new("unk8", 0x7e, I32),
+        Field::new("unk9", 0x82, I32),
+        Field::new("unk10", 0x86, I32),
+        Field::new("pl_anm", 0x8a, Str(STR_LEN)),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "ev_spl.rs",
+    credit: Some(Credit::new("TheLeonX", Some("https://github.com/TheLeonX"))),
+    deserialize: true,
+    fields: &[]
+};
+
 impl NuccBinaryParsed for EvSpl {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::EvSpl
@@ -68,7 +97,7 @@ impl NuccBinaryParsed for EvSpl {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -76,6 +105,6 @@ impl NuccBinaryParsed for EvSpl {
             Self: Sized,
 
         {   
-            serde_json::from_slice(data).unwrap()
+            super::from_json(data)
         }
 }
\ No newline at end of file
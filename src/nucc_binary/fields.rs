@@ -0,0 +1,155 @@
+//! What is known about every format: who reversed it and what its fields mean.
+//! Names, types and offsets come from the format's `Layout`, this only adds what the layout can't say.
+//! `nuccbin explain`, the JSON Schemas from `nuccbin schema` and the README format table are all made from it.
+
+use serde::{Serialize, Serializer};
+
+use super::NuccBinaryType;
+use super::layout::{Field, FieldType};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormatInfo {
+    /// The format's file in `src/nucc_binary`
+    pub source: &'static str,
+    pub credit: Option<Credit>,
+
+    /// Whether unpacked chunks can be repacked, every format can be unpacked
+    pub deserialize: bool,
+    pub fields: &'static [FieldInfo]
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Credit {
+    pub name: &'static str,
+    pub url: Option<&'static str>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldInfo {
+    /// The field's layout name, or its json name for pointers (e.g. `char_name` for `char_name_ptr`) and arrays (`rotation` for `rotation[0]`)
+    pub name: &'static str,
+    pub meaning: &'static str,
+
+    /// Values with a known meaning, the ones written as names by `named_values!`
+    pub values: &'static [(i64, &'static str)]
+}
+
+/// Everything known about a field, from the layout and the format's `FormatInfo`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FieldMeta {
+    pub name: &'static str,
+
+    /// The key in the unpacked json, `None` for fields it doesn't show
+    pub json: Option<String>,
+
+    #[serde(rename = "type", serialize_with = "type_name")]
+    pub field_type: FieldType,
+    pub offset: usize,
+
+    /// In the header rather than in every entry
+    pub header: bool,
+    pub meaning: Option<&'static str>,
+    pub values: &'static [(i64, &'static str)],
+
+    /// The fields of each sub entry, for arrays of them
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldMeta>
+}
+
+/// The values of 0/1 flags, written as `false` and `true`
+pub const FLAG: &[(i64, &str)] = &[(0, "false"), (1, "true")];
+
+/// Shared by the formats that refer to a character by its characode
+pub const CHARACODE_INDEX: FieldInfo = FieldInfo::new("characode_index", "Index of the character in the Characode table, starting at 1");
+
+/// Shared by the formats that refer to a PlayerSettingParam preset
+pub const PLAYER_SETTING_ID: FieldInfo = FieldInfo::new("player_setting_id", "The PlayerSettingParam preset the entry is for");
+
+/// Shared by the formats that sell entries in the shop
+pub const PRICE: FieldInfo = FieldInfo::new("price", "Shop price");
+pub const UNLOCK_CONDITION: FieldInfo = FieldInfo::new("unlock_condition", "What unlocks the entry in the shop");
+
+/// Header fields the unpacked json leaves out, they're written back on repack
+const HIDDEN_HEADER: &[&str] = &["size", "version", "unk0", "entry_ptr"];
+
+
+impl Credit {
+    pub const fn new(name: &'static str, url: Option<&'static str>) -> Self {
+        Self { name, url }
+    }
+}
+
+impl FieldInfo {
+    pub const fn new(name: &'static str, meaning: &'static str) -> Self {
+        Self { name, meaning, values: &[] }
+    }
+
+    pub const fn values(self, values: &'static [(i64, &'static str)]) -> Self {
+        Self { values, ..self }
+    }
+
+    /// Also describes every element of an array (`rotation[0]`) and every field of a struct (`render_settings.rot1P`) named after it,
+    /// except the struct's `unk` fields
+    fn describes(&self, field: &Field) -> bool {
+        field.name == self.name
+            || field.name.strip_suffix("_ptr") == Some(self.name)
+            || field.name.strip_prefix(self.name).map_or(false, |rest| rest.starts_with('[') || (rest.starts_with('.') && !rest.starts_with(".unk")))
+    }
+}
+
+impl FieldMeta {
+    /// Whether `name` is the field's layout name or its json name, or the array it's an element of
+    pub fn is(&self, name: &str) -> bool {
+        self.name == name || self.json.as_deref().map_or(false, |json| json == name || json.split('[').next() == Some(name))
+    }
+}
+
+
+/// The key a layout field has in the unpacked json, strings keep the name of their pointer without `_ptr`.
+/// Arrays and structs are written as paths, e.g. `location[0]` and `render_settings.rot1P`
+pub fn json_name(field: &Field, header: bool) -> Option<String> {
+    if header && HIDDEN_HEADER.contains(&field.name) {
+        return None;
+    }
+
+    Some(field.name.strip_suffix("_ptr").unwrap_or(field.name).to_string())
+}
+
+/// Every field of a format with what is known about it, header fields first. Empty for formats without a layout
+pub fn describe(nucc_binary_type: NuccBinaryType) -> Vec<FieldMeta> {
+    let layout = match nucc_binary_type.layout() {
+        Some(layout) => layout,
+        None => return Vec::new()
+    };
+
+    let info = nucc_binary_type.info();
+
+    layout.header.iter().map(|field| meta(info, field, true))
+        .chain(layout.entry.iter().map(|field| meta(info, field, false)))
+        .collect()
+}
+
+fn meta(info: &FormatInfo, field: &Field, header: bool) -> FieldMeta {
+    let known = info.fields.iter().find(|known| known.describes(field));
+
+    let fields = match field.field_type {
+        FieldType::Array { fields, .. } => fields.iter().map(|field| meta(info, field, false)).collect(),
+        _ => Vec::new()
+    };
+
+    FieldMeta {
+        name: field.name,
+        json: json_name(field, header),
+        field_type: field.field_type,
+        offset: field.offset,
+        header,
+        meaning: known.map(|known| known.meaning),
+        values: known.map_or(&[], |known| known.values),
+        fields
+    }
+}
+
+fn type_name<S: Serializer>(field_type: &FieldType, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&field_type.name())
+}
\ No newline at end of file
@@ -1,19 +1,20 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, FieldInfo, Credit};
 
 
 const HEADER_SIZE: usize = 0x14; // Size of NUCC Binary headers
 const SPL_VICTIM_COUNT: usize = 50;
 
-// Format reversed by EliteAce170 (https://www.youtube.com/c/EliteAce)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
     pub story_mode_id: i32,
-    pub pair_sp_skill_id: i32, // Team Ultimate Jutsu ID
+    pub pair_sp_skill_id: i32,
     pub player_setting_id: i32,
 
     pub costume_slot_index: i32,
@@ -83,6 +84,44 @@ pub struct FinalSpSkillCutIn {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("size", 0x0, U32Be),
+        Field::new("version", 0x4, U32),
+        Field::new("entry_count", 0x8, U16),
+        Field::new("unk0", 0xa, U16),
+        Field::new("entry_ptr", 0xc, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x14,
+    entry_size: 0x4d4,
+    entry: &[
+        Field::new("story_mode_id", 0x0, I32),
+        Field::new("pair_sp_skill_id", 0x4, I32),
+        Field::new("player_setting_id", 0x8, I32),
+        Field::new("costume_slot_index", 0xc, I32),
+        Field::new("sp_skill_1_name_ptr", 0x10, Ptr),
+        Field::new("sp_skill_2_name_ptr", 0x18, Ptr),
+        Field::new("spl_fin_victims", 0x24, Array { count: SPL_VICTIM_COUNT, stride: 0x18, fields: &[
+            Field::new("victim_player_setting_id", 0x0, I32),
+            Field::new("victim_name_ptr", 0x4, Ptr),
+            Field::new("victim_texture_name_ptr", 0xc, Ptr),
+        ] }),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "final_sp_skill_cutin.rs",
+    credit: Some(Credit::new("EliteAce170", Some("https://www.youtube.com/c/EliteAce"))),
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("pair_sp_skill_id", "Team Ultimate Jutsu id"),
+        fields::PLAYER_SETTING_ID,
+        FieldInfo::new("victim_player_setting_id", "The PlayerSettingParam preset of the victim"),
+    ]
+};
+
 impl NuccBinaryParsed for FinalSpSkillCutIn {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::FinalSpSkillCutIn
@@ -93,7 +132,7 @@ impl NuccBinaryParsed for FinalSpSkillCutIn {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -124,16 +163,6 @@ impl From<&[u8]> for FinalSpSkillCutIn {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -190,29 +219,6 @@ impl From<FinalSpSkillCutIn> for Vec<u8> {
 
         writer.write_le(&final_spl_cutin.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
-
         for (current_offset, entry) in final_spl_cutin.entries
             .iter_mut()
             .enumerate()
@@ -0,0 +1,240 @@
+use std::fmt;
+use std::io::{Read, Seek, Write};
+
+use binrw::{BinRead, BinResult, BinWrite, ReadOptions, WriteOptions};
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+
+use super::text_encoding::{decode, encode};
+
+
+/// A string stored inline in a fixed `N` byte field, padded with null bytes.
+///
+/// Some files have leftover bytes after the terminator, those are kept in `trailing`
+/// so that repacking writes the field back exactly as it was read.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FixedString<const N: usize> {
+    text: String,
+    trailing: Vec<u8>
+}
+
+impl<const N: usize> FixedString<N> {
+    pub fn new(text: &str) -> Result<Self, String> {
+        Self::with_trailing(text, Vec::new())
+    }
+
+    pub fn with_trailing(text: &str, mut trailing: Vec<u8>) -> Result<Self, String> {
+        // Zeros at the end are just padding, they get written back regardless
+        while trailing.last() == Some(&0) {
+            trailing.pop();
+        }
+
+        let encoded = encode(text)?;
+
+        if encoded.contains(&0) {
+            return Err(format!("{:?} contains a null byte, which would cut the string short", text));
+        }
+
+        let size = if trailing.is_empty() { encoded.len() } else { encoded.len() + 1 + trailing.len() };
+        if size > N {
+            return Err(format!("{:?} is {} bytes long but this field holds at most {} bytes", text, size, N));
+        }
+
+        Ok(Self {
+            text: text.to_string(),
+            trailing
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn trailing(&self) -> &[u8] {
+        &self.trailing
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = encode(&self.text)?;
+
+        if !self.trailing.is_empty() {
+            bytes.push(0);
+            bytes.extend_from_slice(&self.trailing);
+        }
+
+        bytes.resize(N.max(bytes.len()), 0);
+        Ok(bytes)
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl<const N: usize> BinRead for FixedString<N> {
+    type Args = ();
+
+    fn read_options<R: Read + Seek>(reader: &mut R, _: &ReadOptions, _: Self::Args) -> BinResult<Self> {
+        let mut bytes = [0u8; N];
+        reader.read_exact(&mut bytes)?;
+
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(N);
+        let trailing = bytes.get(end + 1..).unwrap_or_default().to_vec();
+
+        let mut string = Self {
+            text: decode(&bytes[..end]),
+            trailing
+        };
+
+        while string.trailing.last() == Some(&0) {
+            string.trailing.pop();
+        }
+
+        Ok(string)
+    }
+}
+
+impl<const N: usize> BinWrite for FixedString<N> {
+    type Args = ();
+
+    fn write_options<W: Write + Seek>(&self, writer: &mut W, _: &WriteOptions, _: Self::Args) -> BinResult<()> {
+        let message = match self.to_bytes() {
+            Ok(bytes) if bytes.len() <= N => {
+                writer.write_all(&bytes)?;
+                return Ok(());
+            }
+            Ok(bytes) => format!("{:?} is {} bytes long but this field holds at most {} bytes", self.text, bytes.len(), N),
+            Err(message) => message
+        };
+
+        Err(binrw::Error::AssertFail { pos: writer.stream_position()?, message })
+    }
+}
+
+impl<const N: usize> Serialize for FixedString<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.trailing.is_empty() {
+            return serializer.serialize_str(&self.text);
+        }
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("text", &self.text)?;
+        map.serialize_entry("trailing", &hex::encode(&self.trailing))?;
+        map.end()
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for FixedString<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FixedStringVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for FixedStringVisitor<N> {
+            type Value = FixedString<N>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a string of at most {} bytes, or an object with \"text\" and \"trailing\"", N)
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<Self::Value, E> {
+                FixedString::new(text).map_err(E::custom)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut text: Option<String> = None;
+                let mut trailing = Vec::new();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "text" => text = Some(map.next_value()?),
+                        "trailing" => {
+                            let hex_string: String = map.next_value()?;
+                            trailing = hex::decode(hex_string).map_err(de::Error::custom)?;
+                        }
+                        _ => return Err(de::Error::unknown_field(&key, &["text", "trailing"]))
+                    }
+                }
+
+                let text = text.ok_or_else(|| de::Error::missing_field("text"))?;
+                FixedString::with_trailing(&text, trailing).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(FixedStringVisitor::<N>)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::{BinReaderExt, BinWriterExt};
+
+    use super::FixedString;
+
+    fn round_trip<const N: usize>(bytes: &[u8]) -> (FixedString<N>, Vec<u8>) {
+        let string = Cursor::new(bytes).read_le::<FixedString<N>>().unwrap();
+
+        let mut writer = Cursor::new(Vec::new());
+        writer.write_le(&string).unwrap();
+
+        (string, writer.into_inner())
+    }
+
+    #[test]
+    fn overflow_test() {
+        assert!(FixedString::<4>::new("abcd").is_ok());
+        assert!(FixedString::<4>::new("abcde").is_err());
+
+        // The terminator between the text and its trailing bytes counts too
+        assert!(FixedString::<4>::with_trailing("ab", vec![1]).is_ok());
+        assert!(FixedString::<4>::with_trailing("ab", vec![1, 2]).is_err());
+
+        // Zeros at the end of the trailing bytes are padding, not part of the size
+        assert!(FixedString::<4>::with_trailing("ab", vec![1, 0, 0]).is_ok());
+
+        // A null byte would end the string early on the next read
+        assert!(FixedString::<4>::new("a\0b").is_err());
+    }
+
+    #[test]
+    fn trailing_test() {
+        let cases: &[&[u8]] = &[
+            b"abc\0\0\0\0\0",
+            b"abc\0xy\0\0",
+            b"\0\0\x01\0\0\0\0\0",
+            b"abcdefgh"
+        ];
+
+        for &bytes in cases {
+            let (string, written) = round_trip::<8>(bytes);
+            assert_eq!(written, bytes, "{:?}", string);
+
+            // Through json as well
+            let json = serde_json::to_string(&string).unwrap();
+            assert_eq!(serde_json::from_str::<FixedString<8>>(&json).unwrap(), string);
+        }
+
+        let (string, _) = round_trip::<8>(b"abc\0xy\0\0");
+        assert_eq!(string.as_str(), "abc");
+        assert_eq!(string.trailing(), b"xy");
+        assert_eq!(serde_json::to_string(&string).unwrap(), r#"{"text":"abc","trailing":"7879"}"#);
+    }
+
+    #[test]
+    fn multi_byte_test() {
+        // "é" is two bytes in UTF-8, so the limit is in bytes rather than characters
+        assert!(FixedString::<4>::new("éé").is_ok());
+        assert!(FixedString::<4>::new("ééa").is_err());
+        assert!(FixedString::<5>::new("ééa").is_ok());
+
+        // Filling the field up to its last byte leaves no room for a terminator, which reading doesn't need
+        let (string, written) = round_trip::<4>("éé".as_bytes());
+        assert_eq!(string.as_str(), "éé");
+        assert_eq!(written, "éé".as_bytes());
+    }
+}
\ No newline at end of file
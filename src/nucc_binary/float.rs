@@ -0,0 +1,118 @@
+//! Serde helpers for `f32` fields, used as `#[serde(with = "super::float")]`.
+//!
+//! Finite values are written as plain numbers, using the shortest decimal that reads back as the same `f32`.
+//! NaN, infinity and other values JSON can't hold are written as their bits in hex (e.g. `"0x7FC00000"`),
+//! so every bit pattern survives unpacking and repacking.
+
+use std::cell::Cell;
+use std::fmt;
+
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+use serde::de::{self, Visitor};
+
+
+thread_local! {
+    static PRECISION: Cell<Option<usize>> = Cell::new(None);
+}
+
+/// Rounds finite values to `digits` decimal places when serializing, to make them easier to read.
+/// Repacking those files writes the rounded values, so this isn't lossless.
+pub fn set_precision(digits: Option<usize>) {
+    PRECISION.with(|p| p.set(digits));
+}
+
+
+pub trait Floats: Sized {
+    fn serialize_floats<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+    fn deserialize_floats<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl Floats for f32 {
+    fn serialize_floats<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Float(*self).serialize(serializer)
+    }
+
+    fn deserialize_floats<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Float::deserialize(deserializer).map(|float| float.0)
+    }
+}
+
+impl<const N: usize> Floats for [f32; N] {
+    fn serialize_floats<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|&value| Float(value)))
+    }
+
+    fn deserialize_floats<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let floats = Vec::<Float>::deserialize(deserializer)?;
+        let len = floats.len();
+
+        floats.into_iter()
+            .map(|float| float.0)
+            .collect::<Vec<f32>>()
+            .try_into()
+            .map_err(|_| de::Error::invalid_length(len, &format!("{} floats", N).as_str()))
+    }
+}
+
+pub fn serialize<T: Floats, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.serialize_floats(serializer)
+}
+
+pub fn deserialize<'de, T: Floats, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize_floats(deserializer)
+}
+
+
+struct Float(f32);
+
+impl Serialize for Float {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = self.0;
+
+        if !value.is_finite() {
+            return serializer.serialize_str(&format!("0x{:08X}", value.to_bits()));
+        }
+
+        match PRECISION.with(|p| p.get()) {
+            Some(digits) => serializer.serialize_f64(format!("{:.*}", digits, value).parse().unwrap()),
+            None => serializer.serialize_f32(value)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Float {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FloatVisitor;
+
+        impl<'de> Visitor<'de> for FloatVisitor {
+            type Value = Float;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a number, or the bits of a float in hex like \"0x7FC00000\"")
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+                Ok(Float(value as f32))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                Ok(Float(value as f32))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(Float(value as f32))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                let bits = value.strip_prefix("0x")
+                    .or_else(|| value.strip_prefix("0X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| E::custom(format!("{:?} isn't a float or hex bits like \"0x7FC00000\"", value)))?;
+
+                Ok(Float(f32::from_bits(bits)))
+            }
+        }
+
+        deserializer.deserialize_any(FloatVisitor)
+    }
+}
@@ -0,0 +1,211 @@
+/// Where every field of a format sits in the chunk, for the formats that are a header followed by a table of fixed size entries.
+/// Offsets of header fields are from the start of the chunk, offsets of entry fields are from the start of their entry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub header: &'static [Field],
+
+    /// The header field holding the number of entries, `None` when the chunk is a single entry
+    pub entry_count: Option<&'static str>,
+
+    pub entries_offset: usize,
+    pub entry_size: usize,
+    pub entry: &'static [Field],
+
+    /// Some formats point 4 bytes further than where the pointer is stored
+    pub ptr_bias: usize
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Field {
+    pub name: &'static str,
+    pub offset: usize,
+    pub field_type: FieldType
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    U32Be,
+    I32,
+    U64,
+    I64,
+    F32,
+
+    /// Bytes that are shown as hex, e.g. a crc32
+    Bytes(usize),
+
+    /// A string stored inline in a fixed size field
+    Str(usize),
+
+    /// A u64 pointing to a null terminated string, relative to where the pointer is stored
+    Ptr,
+
+    /// The same as `Ptr`, stored in a u32
+    Ptr32,
+
+    /// A fixed number of sub entries stored inline
+    Array { count: usize, stride: usize, fields: &'static [Field] }
+}
+
+impl Field {
+    pub const fn new(name: &'static str, offset: usize, field_type: FieldType) -> Self {
+        Self { name, offset, field_type }
+    }
+
+    pub const fn size(&self) -> usize {
+        self.field_type.size()
+    }
+}
+
+impl FieldType {
+    pub const fn size(&self) -> usize {
+        match self {
+            FieldType::U8 | FieldType::I8 => 1,
+            FieldType::U16 | FieldType::I16 => 2,
+            FieldType::U32 | FieldType::U32Be | FieldType::I32 | FieldType::F32 | FieldType::Ptr32 => 4,
+            FieldType::U64 | FieldType::I64 | FieldType::Ptr => 8,
+            FieldType::Bytes(size) | FieldType::Str(size) => *size,
+            FieldType::Array { count, stride, .. } => *count * *stride
+        }
+    }
+
+    /// The smallest and largest value of an integer field, `None` for the other types
+    pub const fn int_range(&self) -> Option<(i64, i64)> {
+        match self {
+            FieldType::U8 => Some((0, u8::MAX as i64)),
+            FieldType::I8 => Some((i8::MIN as i64, i8::MAX as i64)),
+            FieldType::U16 => Some((0, u16::MAX as i64)),
+            FieldType::I16 => Some((i16::MIN as i64, i16::MAX as i64)),
+            FieldType::U32 | FieldType::U32Be => Some((0, u32::MAX as i64)),
+            FieldType::I32 => Some((i32::MIN as i64, i32::MAX as i64)),
+            FieldType::U64 => Some((0, i64::MAX)),
+            FieldType::I64 => Some((i64::MIN, i64::MAX)),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            FieldType::U8 => String::from("u8"),
+            FieldType::I8 => String::from("i8"),
+            FieldType::U16 => String::from("u16"),
+            FieldType::I16 => String::from("i16"),
+            FieldType::U32 => String::from("u32"),
+            FieldType::U32Be => String::from("u32be"),
+            FieldType::I32 => String::from("i32"),
+            FieldType::U64 => String::from("u64"),
+            FieldType::I64 => String::from("i64"),
+            FieldType::F32 => String::from("f32"),
+            FieldType::Bytes(size) => format!("bytes[{:#x}]", size),
+            FieldType::Str(size) => format!("str[{:#x}]", size),
+            FieldType::Ptr => String::from("ptr"),
+            FieldType::Ptr32 => String::from("ptr32"),
+            FieldType::Array { count, .. } => format!("array[{}]", count)
+        }
+    }
+}
+
+impl Layout {
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.header.iter().chain(self.entry).find(|field| field.name == name)
+    }
+
+    pub fn has_pointers(&self) -> bool {
+        fn any_pointer(fields: &[Field]) -> bool {
+            fields.iter().any(|field| match field.field_type {
+                FieldType::Ptr | FieldType::Ptr32 => true,
+                FieldType::Array { fields, .. } => any_pointer(fields),
+                _ => false
+            })
+        }
+
+        any_pointer(self.header) || any_pointer(self.entry)
+    }
+
+    pub fn header_size(&self) -> usize {
+        self.header.iter().map(|field| field.offset + field.size()).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use binrw::{BinReaderExt, BinWriterExt};
+    use binrw::io::Cursor;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::NuccBinaryType;
+    use crate::nucc_binary::*;
+
+    /// A chunk of `count` zeroed entries, with the count set in its header field
+    fn zeroed(layout: &Layout, count: usize) -> Vec<u8> {
+        let mut data = vec![0; layout.entries_offset + count * layout.entry_size];
+
+        if let Some(field) = layout.entry_count.and_then(|name| layout.field(name)) {
+            let bytes = match field.field_type {
+                FieldType::U16 => (count as u16).to_le_bytes().to_vec(),
+                FieldType::U32 | FieldType::I32 => (count as u32).to_le_bytes().to_vec(),
+                FieldType::U32Be => (count as u32).to_be_bytes().to_vec(),
+                FieldType::U64 => (count as u64).to_le_bytes().to_vec(),
+                field_type => panic!("{} can't hold the entry count", field_type.name())
+            };
+
+            data[field.offset..field.offset + bytes.len()].copy_from_slice(&bytes);
+        }
+
+        data
+    }
+
+    /// How many bytes the format's binrw struct reads from a zeroed chunk and writes back
+    macro_rules! binrw_size {
+        ($nucc_binary_type:expr, $data:expr, $($format:ident),*) => {
+            match $nucc_binary_type {
+                $(NuccBinaryType::$format => {
+                    let mut reader = Cursor::new($data);
+                    let parsed = reader.read_le::<$format>().unwrap();
+
+                    let mut writer = Cursor::new(Vec::new());
+                    writer.write_le(&parsed).unwrap();
+
+                    assert_eq!(reader.position() as usize, $data.len(), "{} read", $nucc_binary_type);
+                    writer.into_inner().len()
+                })*
+                _ => panic!("{} has a layout but isn't compared", $nucc_binary_type)
+            }
+        };
+    }
+
+    #[test]
+    fn binrw_size_test() {
+        for nucc_binary_type in NuccBinaryType::iter() {
+            let layout = match nucc_binary_type.layout() {
+                Some(layout) => layout,
+                None => continue
+            };
+
+            // Formats without an entry count are a single entry
+            let counts = match layout.entry_count {
+                Some(_) => vec![0, 1, 2],
+                None => vec![1]
+            };
+
+            for count in counts {
+                let data = zeroed(layout, count);
+
+                let size = binrw_size!(nucc_binary_type, &data[..],
+                    AccessoriesParam, AccessoryExceptionParam, AccessoryParam, AnimeSongBgmParam, Anmofs, BodAcc, Characode,
+                    CharaPoseParam, CharacterSelectParam, ComboPrm, CommandListParam, CostumeBreakParam, CostumeParam,
+                    DictionaryCharacterParam, DlcInfoParam, EffectPrm, Ev, EvSpl, FinalSpSkillCutIn, MessageInfo, OugiFinishParam,
+                    PlayerDoubleEffectParam, PlayerSettingParam, PlayerIcon, PrmBas, PrmLoad, ProhibitedSubstringParam,
+                    SkillIndexSettingParam, Snd, StaffRollTextParam, SupportActionParam, SupportSkillRecoverySpeedParam, UpdateInfoParam);
+
+                assert_eq!(size, data.len(), "{} with {} entries", nucc_binary_type, count);
+                assert!(layout.header_size() <= layout.entries_offset, "{}", nucc_binary_type);
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+
+use super::NuccBinaryParsed;
+
+
+/// How much a lint finding matters, `Off` turns a rule off
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Info,
+    Warning,
+    Error
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Off => f.pad("off"),
+            Severity::Info => f.pad("info"),
+            Severity::Warning => f.pad("warning"),
+            Severity::Error => f.pad("error")
+        }
+    }
+}
+
+/// A check for suspicious data that a format contributes through its `LINT_RULES`
+#[derive(Clone, Copy)]
+pub struct Rule {
+    /// Unique within the format, the full id is `<format>/<name>`
+    pub name: &'static str,
+    pub severity: Severity,
+    pub description: &'static str,
+
+    /// The default of the value the rule compares with, for the rules that have one. The project's `lint_limits` change it.
+    pub limit: Option<u64>,
+
+    /// Gets the format's parsed struct and the rule's limit, 0 for the rules without one
+    pub check: fn(&dyn NuccBinaryParsed, u64) -> Vec<Finding>
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rule").field("name", &self.name).field("severity", &self.severity).field("limit", &self.limit).finish()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub entry: usize,
+    pub field: String,
+    pub message: String
+}
+
+impl Finding {
+    pub fn new(entry: usize, field: &str, message: String) -> Self {
+        Self { entry, field: field.to_string(), message }
+    }
+}
+
+
+/// Shared by the formats that sell entries in the shop: a price of 0 on an entry that has to be unlocked first
+pub fn zero_price_locked(entries: impl Iterator<Item = (u32, u32)>) -> Vec<Finding> {
+    entries.enumerate()
+        .filter(|(_, (price, unlock_condition))| *price == 0 && *unlock_condition != 0)
+        .map(|(index, (_, unlock_condition))| Finding::new(index, "price", format!("is 0 but unlock_condition is set ({})", unlock_condition)))
+        .collect()
+}
+
+pub const ZERO_PRICE_LOCKED: &str = "Price of 0 on an entry with an unlock condition";
\ No newline at end of file
@@ -2,6 +2,7 @@ use serde::{Serialize, Deserialize};
 
 
 use super::{NuccBinaryParsed, NuccBinaryType};
+use super::fields::FormatInfo;
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -9,6 +10,13 @@ pub struct Lua {
     pub file: Vec<u8>
 }
 
+pub const INFO: FormatInfo = FormatInfo {
+    source: "lua.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[]
+};
+
 impl NuccBinaryParsed for Lua {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::Lua
@@ -1,13 +1,14 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, FieldInfo, Credit};
 
 use super::HEADER_SIZE;
 
-// Format was reversed by TheLeonX (https://github.com/TheLeonX)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Entry {
@@ -62,6 +63,40 @@ pub struct MessageInfo {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x30,
+    entry: &[
+        Field::new("crc32", 0x0, Bytes(4)),
+        Field::new("disable", 0x4, U32),
+        Field::new("text1_ptr", 0x8, Ptr),
+        Field::new("text2_ptr", 0x10, Ptr),
+        Field::new("text3_ptr", 0x18, Ptr),
+        Field::new("unk2", 0x20, U32),
+        Field::new("unk3", 0x24, I16),
+        Field::new("acb_file_id", 0x26, I16),
+        Field::new("acb_cue_id", 0x28, I16),
+        Field::new("unk4", 0x2a, I16),
+        Field::new("unk5", 0x2c, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "message_info.rs",
+    credit: Some(Credit::new("TheLeonX", Some("https://github.com/TheLeonX"))),
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("crc32", "Crc32 of the message id, unique within the chunk. Every language's chunk has the same ones"),
+    ]
+};
+
 impl NuccBinaryParsed for MessageInfo {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::MessageInfo
@@ -72,7 +107,7 @@ impl NuccBinaryParsed for MessageInfo {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -101,16 +136,6 @@ impl From<&[u8]> for MessageInfo {
 
 
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -143,29 +168,6 @@ impl From<MessageInfo> for Vec<u8> {
 
         writer.write_le(&message_info.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
-
         for (current_offset, entry) in message_info.entries
             .iter_mut()
             .enumerate()
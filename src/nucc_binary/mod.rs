@@ -4,6 +4,7 @@ mod accessory_param;
 mod anime_song_bgm_param;
 mod anm_offset;
 mod bodacc;
+pub mod canonical;
 mod characode;
 mod chara_pose_param;
 mod character_select_param;
@@ -17,30 +18,41 @@ mod dlc_info_param;
 mod effectprm;
 mod ev;
 mod ev_spl;
+pub mod fields;
 mod final_sp_skill_cutin;
+mod fixed_string;
+pub mod float;
+pub mod layout;
+pub mod lint;
 mod lua;
 pub mod message_info;
+pub mod named;
 mod ougi_finish_param;
 mod player_double_effect_param; 
 mod player_setting_param;
 mod player_icon;
 mod png;
+pub mod pointers;
 mod prm_bas;
 mod prm_load;
 mod prohibited_substring_param;
+pub mod raw;
 mod skill_index_setting_param;
 mod snd;
 mod staff_roll_text_param;
 mod support_action_param;
 mod support_skill_recovery_speed_param;
+pub mod text_encoding;
 mod update_info_param;
 mod xml;
 
-use binrw::{BinReaderExt, BinWriterExt};
-use binrw::io::Cursor;
+use binrw::{BinReaderExt, BinWriterExt, NullString};
+use binrw::io::{Cursor, Seek, SeekFrom};
 use downcast_rs::{impl_downcast, Downcast};
 
 use super::NuccBinaryType;
+use fields::FormatInfo;
+use layout::Layout;
 
 pub const HEADER_SIZE: usize = 0x10; // Size of NUCC Binary headers
 
@@ -64,6 +76,7 @@ pub use dlc_info_param::DlcInfoParam;
 pub use ev::Ev;
 pub use ev_spl::EvSpl;
 pub use final_sp_skill_cutin::FinalSpSkillCutIn;
+pub use fixed_string::FixedString;
 pub use effectprm::EffectPrm;
 pub use lua::Lua;
 pub use message_info::MessageInfo;
@@ -97,6 +110,193 @@ pub trait NuccBinaryParsed: Downcast {
 
 impl_downcast!(NuccBinaryParsed);
 
+/// Serializes a format to pretty JSON, ending with a newline in canonical mode
+pub fn to_json<T: serde::Serialize>(value: &T) -> Vec<u8> {
+    let mut json = serde_json::to_string_pretty(value).unwrap();
+
+    if canonical::is_canonical() {
+        json.push('\n');
+    }
+
+    json.into()
+}
+
+/// Deserializes a format from its JSON, naming the offending field (e.g. `entries[3].sound_name`) if a value is rejected.
+pub fn from_json<T: serde::de::DeserializeOwned>(data: &[u8]) -> T {
+    try_from_json(data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// `from_json` returning the offending field and why it was rejected, e.g. `entries[3].sound_name: invalid type: integer`
+pub fn try_from_json<T: serde::de::DeserializeOwned>(data: &[u8]) -> Result<T, String> {
+    let deserializer = &mut serde_json::Deserializer::from_slice(data);
+
+    serde_path_to_error::deserialize(deserializer)
+        .map_err(|err| format!("{}: {}", err.path(), err.inner()))
+}
+
+/// Reads the null terminated string `ptr` bytes after `current_offset`, decoded with the current text encoding.
+/// A pointer past the end of the chunk is read as an empty string, like `--lenient` does, `check_pointers` is what reports it.
+pub fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, current_offset: u64) -> String {
+    let target = current_offset.saturating_add(ptr);
+
+    if ptr == 0 || target >= reader.get_ref().len() as u64 {
+        return String::from("");
+    }
+
+    reader.seek(SeekFrom::Start(target)).unwrap();
+
+    match reader.read_be::<NullString>() {
+        Ok(string) => text_encoding::decode(&string.0),
+        Err(_) => String::from("")
+    }
+}
+
+/// Appends `string` to the end of the buffer and writes a pointer to it at `current_offset + adjustment`.
+pub fn write_ptr_to_string(
+    writer: &mut Cursor<Vec<u8>>,
+    string: &String,
+    current_offset: u64,
+    adjustment: u64,
+) {
+    if !string.is_empty() {
+        let string_pos = append_string(writer, string);
+
+        writer.seek(SeekFrom::Start(current_offset + adjustment)).unwrap();
+        writer.write_le::<u64>(&(string_pos - current_offset - adjustment)).unwrap();
+    }
+}
+
+/// The same as `write_ptr_to_string`, for pointers stored in a u32
+pub fn write_ptr32_to_string(
+    writer: &mut Cursor<Vec<u8>>,
+    string: &str,
+    current_offset: u64,
+    adjustment: u64,
+) {
+    if !string.is_empty() {
+        let string_pos = append_string(writer, string);
+
+        writer.seek(SeekFrom::Start(current_offset + adjustment)).unwrap();
+        writer.write_le::<u32>(&((string_pos - current_offset - adjustment) as u32)).unwrap();
+    }
+}
+
+/// Appends a null terminated string padded to 8 bytes, returns where it starts
+fn append_string(writer: &mut Cursor<Vec<u8>>, string: &str) -> u64 {
+    let bytes = text_encoding::encode(string).unwrap_or_else(|err| panic!("{}", err));
+
+    let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
+    writer.write_be::<NullString>(&NullString(bytes)).unwrap();
+
+    // Align to 8 bytes
+    let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
+    if 8 - (pos % 8) != 8  {
+        writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
+    }
+
+    string_pos
+}
+
+
+impl NuccBinaryType {
+    /// Where every field sits in the chunk, `None` for formats that aren't a table of fixed size entries (dds, lua, png and xml)
+    pub fn layout(&self) -> Option<&'static Layout> {
+        match self {
+            NuccBinaryType::AccessoriesParam => Some(&accessories_param::LAYOUT),
+            NuccBinaryType::AccessoryExceptionParam => Some(&accessory_exception_param::LAYOUT),
+            NuccBinaryType::AccessoryParam => Some(&accessory_param::LAYOUT),
+            NuccBinaryType::AnimeSongBgmParam => Some(&anime_song_bgm_param::LAYOUT),
+            NuccBinaryType::Anmofs => Some(&anm_offset::LAYOUT),
+            NuccBinaryType::BodAcc => Some(&bodacc::LAYOUT),
+            NuccBinaryType::Characode => Some(&characode::LAYOUT),
+            NuccBinaryType::CharaPoseParam => Some(&chara_pose_param::LAYOUT),
+            NuccBinaryType::CharacterSelectParam => Some(&character_select_param::LAYOUT),
+            NuccBinaryType::ComboPrm => Some(&combo_prm::LAYOUT),
+            NuccBinaryType::CommandListParam => Some(&command_list_param::LAYOUT),
+            NuccBinaryType::CostumeBreakParam => Some(&costume_break_param::LAYOUT),
+            NuccBinaryType::CostumeParam => Some(&costume_param::LAYOUT),
+            NuccBinaryType::DictionaryCharacterParam => Some(&dictionary_character_param::LAYOUT),
+            NuccBinaryType::DlcInfoParam => Some(&dlc_info_param::LAYOUT),
+            NuccBinaryType::EffectPrm => Some(&effectprm::LAYOUT),
+            NuccBinaryType::Ev => Some(&ev::LAYOUT),
+            NuccBinaryType::EvSpl => Some(&ev_spl::LAYOUT),
+            NuccBinaryType::FinalSpSkillCutIn => Some(&final_sp_skill_cutin::LAYOUT),
+            NuccBinaryType::MessageInfo => Some(&message_info::LAYOUT),
+            NuccBinaryType::OugiFinishParam => Some(&ougi_finish_param::LAYOUT),
+            NuccBinaryType::PlayerDoubleEffectParam => Some(&player_double_effect_param::LAYOUT),
+            NuccBinaryType::PlayerSettingParam => Some(&player_setting_param::LAYOUT),
+            NuccBinaryType::PlayerIcon => Some(&player_icon::LAYOUT),
+            NuccBinaryType::PrmBas => Some(&prm_bas::LAYOUT),
+            NuccBinaryType::PrmLoad => Some(&prm_load::LAYOUT),
+            NuccBinaryType::ProhibitedSubstringParam => Some(&prohibited_substring_param::LAYOUT),
+            NuccBinaryType::SkillIndexSettingParam => Some(&skill_index_setting_param::LAYOUT),
+            NuccBinaryType::Snd => Some(&snd::LAYOUT),
+            NuccBinaryType::StaffRollTextParam => Some(&staff_roll_text_param::LAYOUT),
+            NuccBinaryType::SupportActionParam => Some(&support_action_param::LAYOUT),
+            NuccBinaryType::SupportSkillRecoverySpeedParam => Some(&support_skill_recovery_speed_param::LAYOUT),
+            NuccBinaryType::UpdateInfoParam => Some(&update_info_param::LAYOUT),
+            NuccBinaryType::Dds | NuccBinaryType::Lua | NuccBinaryType::Png | NuccBinaryType::Xml => None
+        }
+    }
+
+    /// What is known about the format's fields and who reversed it
+    pub fn info(&self) -> &'static FormatInfo {
+        match self {
+            NuccBinaryType::AccessoriesParam => &accessories_param::INFO,
+            NuccBinaryType::AccessoryExceptionParam => &accessory_exception_param::INFO,
+            NuccBinaryType::AccessoryParam => &accessory_param::INFO,
+            NuccBinaryType::AnimeSongBgmParam => &anime_song_bgm_param::INFO,
+            NuccBinaryType::Anmofs => &anm_offset::INFO,
+            NuccBinaryType::BodAcc => &bodacc::INFO,
+            NuccBinaryType::Characode => &characode::INFO,
+            NuccBinaryType::CharaPoseParam => &chara_pose_param::INFO,
+            NuccBinaryType::CharacterSelectParam => &character_select_param::INFO,
+            NuccBinaryType::ComboPrm => &combo_prm::INFO,
+            NuccBinaryType::CommandListParam => &command_list_param::INFO,
+            NuccBinaryType::CostumeBreakParam => &costume_break_param::INFO,
+            NuccBinaryType::CostumeParam => &costume_param::INFO,
+            NuccBinaryType::Dds => &dds::INFO,
+            NuccBinaryType::DictionaryCharacterParam => &dictionary_character_param::INFO,
+            NuccBinaryType::DlcInfoParam => &dlc_info_param::INFO,
+            NuccBinaryType::EffectPrm => &effectprm::INFO,
+            NuccBinaryType::Ev => &ev::INFO,
+            NuccBinaryType::EvSpl => &ev_spl::INFO,
+            NuccBinaryType::FinalSpSkillCutIn => &final_sp_skill_cutin::INFO,
+            NuccBinaryType::Lua => &lua::INFO,
+            NuccBinaryType::MessageInfo => &message_info::INFO,
+            NuccBinaryType::OugiFinishParam => &ougi_finish_param::INFO,
+            NuccBinaryType::PlayerDoubleEffectParam => &player_double_effect_param::INFO,
+            NuccBinaryType::PlayerSettingParam => &player_setting_param::INFO,
+            NuccBinaryType::PlayerIcon => &player_icon::INFO,
+            NuccBinaryType::Png => &png::INFO,
+            NuccBinaryType::PrmBas => &prm_bas::INFO,
+            NuccBinaryType::PrmLoad => &prm_load::INFO,
+            NuccBinaryType::ProhibitedSubstringParam => &prohibited_substring_param::INFO,
+            NuccBinaryType::SkillIndexSettingParam => &skill_index_setting_param::INFO,
+            NuccBinaryType::Snd => &snd::INFO,
+            NuccBinaryType::StaffRollTextParam => &staff_roll_text_param::INFO,
+            NuccBinaryType::SupportActionParam => &support_action_param::INFO,
+            NuccBinaryType::SupportSkillRecoverySpeedParam => &support_skill_recovery_speed_param::INFO,
+            NuccBinaryType::UpdateInfoParam => &update_info_param::INFO,
+            NuccBinaryType::Xml => &xml::INFO,
+        }
+    }
+
+    /// The checks `nuccbin lint` runs on chunks of this format
+    pub fn lint_rules(&self) -> &'static [lint::Rule] {
+        match self {
+            NuccBinaryType::AccessoriesParam => accessories_param::LINT_RULES,
+            NuccBinaryType::AnimeSongBgmParam => anime_song_bgm_param::LINT_RULES,
+            NuccBinaryType::Anmofs => anm_offset::LINT_RULES,
+            NuccBinaryType::CharaPoseParam => chara_pose_param::LINT_RULES,
+            NuccBinaryType::CharacterSelectParam => character_select_param::LINT_RULES,
+            NuccBinaryType::CostumeParam => costume_param::LINT_RULES,
+            NuccBinaryType::OugiFinishParam => ougi_finish_param::LINT_RULES,
+            _ => &[]
+        }
+    }
+}
+
 
 pub struct NuccBinaryParsedReader<'a> (pub NuccBinaryType, pub &'a [u8]);
 
@@ -325,6 +525,51 @@ impl From<NuccBinaryParsedSerializer> for Vec<u8> {
 #[derive(Debug)]
 pub struct NuccBinaryParsedDeserializer(pub NuccBinaryType, pub Vec<u8>);
 
+impl NuccBinaryParsedDeserializer {
+    /// Checks that the json deserializes into the format's struct, naming the offending field if it doesn't
+    /// rather than panicking when it's converted
+    pub fn check(&self) -> Result<(), String> {
+        let NuccBinaryParsedDeserializer(nucc_binary_type, data) = self;
+
+        match nucc_binary_type {
+            NuccBinaryType::AccessoriesParam => try_from_json::<AccessoriesParam>(data).map(drop),
+            NuccBinaryType::AccessoryExceptionParam => try_from_json::<AccessoryExceptionParam>(data).map(drop),
+            NuccBinaryType::AccessoryParam => try_from_json::<AccessoryParam>(data).map(drop),
+            NuccBinaryType::AnimeSongBgmParam => try_from_json::<AnimeSongBgmParam>(data).map(drop),
+            NuccBinaryType::Anmofs => try_from_json::<Anmofs>(data).map(drop),
+            NuccBinaryType::BodAcc => try_from_json::<BodAcc>(data).map(drop),
+            NuccBinaryType::Characode => try_from_json::<Characode>(data).map(drop),
+            NuccBinaryType::CharaPoseParam => try_from_json::<CharaPoseParam>(data).map(drop),
+            NuccBinaryType::CharacterSelectParam => try_from_json::<CharacterSelectParam>(data).map(drop),
+            NuccBinaryType::ComboPrm => try_from_json::<ComboPrm>(data).map(drop),
+            NuccBinaryType::CommandListParam => try_from_json::<CommandListParam>(data).map(drop),
+            NuccBinaryType::CostumeBreakParam => try_from_json::<CostumeBreakParam>(data).map(drop),
+            NuccBinaryType::CostumeParam => try_from_json::<CostumeParam>(data).map(drop),
+            NuccBinaryType::DictionaryCharacterParam => try_from_json::<DictionaryCharacterParam>(data).map(drop),
+            NuccBinaryType::DlcInfoParam => try_from_json::<DlcInfoParam>(data).map(drop),
+            NuccBinaryType::EffectPrm => try_from_json::<EffectPrm>(data).map(drop),
+            NuccBinaryType::Ev => try_from_json::<Ev>(data).map(drop),
+            NuccBinaryType::EvSpl => try_from_json::<EvSpl>(data).map(drop),
+            NuccBinaryType::FinalSpSkillCutIn => try_from_json::<FinalSpSkillCutIn>(data).map(drop),
+            NuccBinaryType::MessageInfo => try_from_json::<MessageInfo>(data).map(drop),
+            NuccBinaryType::OugiFinishParam => try_from_json::<OugiFinishParam>(data).map(drop),
+            NuccBinaryType::PlayerDoubleEffectParam => try_from_json::<PlayerDoubleEffectParam>(data).map(drop),
+            NuccBinaryType::PlayerSettingParam => try_from_json::<PlayerSettingParam>(data).map(drop),
+            NuccBinaryType::PlayerIcon => try_from_json::<PlayerIcon>(data).map(drop),
+            NuccBinaryType::PrmBas => try_from_json::<PrmBas>(data).map(drop),
+            NuccBinaryType::PrmLoad => try_from_json::<PrmLoad>(data).map(drop),
+            NuccBinaryType::ProhibitedSubstringParam => try_from_json::<ProhibitedSubstringParam>(data).map(drop),
+            NuccBinaryType::SkillIndexSettingParam => try_from_json::<SkillIndexSettingParam>(data).map(drop),
+            NuccBinaryType::Snd => try_from_json::<Snd>(data).map(drop),
+            NuccBinaryType::StaffRollTextParam => try_from_json::<StaffRollTextParam>(data).map(drop),
+            NuccBinaryType::SupportActionParam => try_from_json::<SupportActionParam>(data).map(drop),
+            NuccBinaryType::SupportSkillRecoverySpeedParam => try_from_json::<SupportSkillRecoverySpeedParam>(data).map(drop),
+            NuccBinaryType::UpdateInfoParam => try_from_json::<UpdateInfoParam>(data).map(drop),
+            NuccBinaryType::Dds | NuccBinaryType::Lua | NuccBinaryType::Png | NuccBinaryType::Xml => Ok(())
+        }
+    }
+}
+
 impl From<NuccBinaryParsedDeserializer> for Box<dyn NuccBinaryParsed> {
     fn from(deserializer: NuccBinaryParsedDeserializer) -> Self {
        let NuccBinaryParsedDeserializer(nucc_binary_type, data) = deserializer;
@@ -0,0 +1,120 @@
+//! Serde helpers for integer fields with known meanings.
+//!
+//! Fields with named values use a module made by `named_values!`, e.g. `#[serde(with = "kind")]`. Known values are written
+//! as their name and every other value as the number, so nothing is lost. 0/1 flags use `#[serde(with = "super::named::flag")]`
+//! and are written as `true`/`false`, other values as the number. Names and numbers are both read back.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::{Serializer, Deserializer};
+use serde::de::{self, Visitor};
+
+
+/// Makes a `with` module for an integer field from its known values, e.g.
+/// `named_values!(pub mod kind: u32 { 0 => "character", 1 => "costume" });`
+macro_rules! named_values {
+    ($(#[$meta:meta])* $vis:vis mod $name:ident: $ty:ty { $($value:literal => $label:literal),* $(,)? }) => {
+        $(#[$meta])*
+        $vis mod $name {
+            /// The known values and their names
+            #[allow(dead_code)]
+            pub const NAMES: &[(i64, &str)] = &[$(($value, $label)),*];
+
+            pub fn serialize<S: serde::Serializer>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::nucc_binary::named::serialize_named(i64::from(*value), NAMES, serializer)
+            }
+
+            pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                $crate::nucc_binary::named::deserialize_named(NAMES, deserializer)
+            }
+        }
+    };
+}
+
+pub(crate) use named_values;
+
+
+pub fn serialize_named<S: Serializer>(value: i64, names: &[(i64, &str)], serializer: S) -> Result<S::Ok, S::Error> {
+    match names.iter().find(|(known, _)| *known == value) {
+        Some((_, name)) => serializer.serialize_str(name),
+        None => serializer.serialize_i64(value)
+    }
+}
+
+pub fn deserialize_named<'de, T: TryFrom<i64>, D: Deserializer<'de>>(names: &'static [(i64, &'static str)], deserializer: D) -> Result<T, D::Error> {
+    deserializer.deserialize_any(NamedVisitor { names, flag: false, marker: PhantomData })
+}
+
+/// Whether a flag is set in unpacked json, written either way
+pub fn is_set(value: &serde_json::Value) -> bool {
+    value.as_bool().unwrap_or_else(|| value.as_i64().map_or(false, |value| value != 0))
+}
+
+
+pub mod flag {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        match *value {
+            0 => serializer.serialize_bool(false),
+            1 => serializer.serialize_bool(true),
+            value => serializer.serialize_u32(value)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        deserializer.deserialize_any(NamedVisitor { names: &[], flag: true, marker: PhantomData })
+    }
+}
+
+
+struct NamedVisitor<T> {
+    names: &'static [(i64, &'static str)],
+
+    /// Also reads `true` and `false`
+    flag: bool,
+    marker: PhantomData<T>
+}
+
+impl<T> NamedVisitor<T> {
+    fn names(&self) -> String {
+        self.names.iter().map(|(_, name)| *name).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl<'de, T: TryFrom<i64>> Visitor<'de> for NamedVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.flag, self.names.is_empty()) {
+            (true, _) => f.write_str("true, false or a number"),
+            (_, true) => f.write_str("a number"),
+            _ => write!(f, "a number or one of {}", self.names())
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        T::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        i64::try_from(value).ok()
+            .and_then(|value| T::try_from(value).ok())
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+        match self.flag {
+            true => self.visit_i64(i64::from(value)),
+            false => Err(E::invalid_type(de::Unexpected::Bool(value), &self))
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        match self.names.iter().find(|(_, name)| *name == value) {
+            Some(&(known, _)) => self.visit_i64(known),
+            None => Err(E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+}
\ No newline at end of file
@@ -1,13 +1,16 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, write_ptr_to_string, write_ptr32_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, FieldInfo, Credit};
+use super::lint::{self, Rule, Severity, Finding};
+use super::pointers;
 
 use super::HEADER_SIZE;
 
-// Format reversed by Portable Productions (https://www.youtube.com/@PortableProductions)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
@@ -100,6 +103,56 @@ pub struct OugiFinishParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x50,
+    entry: &[
+        Field::new("char_name_ptr", 0x0, Ptr),
+        Field::new("ougi_fin_link_ptr", 0x8, Ptr),
+        Field::new("unk_ptr", 0x10, Ptr),
+        Field::new("spl_fin_ptr", 0x18, Ptr32),
+        Field::new("index", 0x1c, U32),
+        Field::new("spl_fin_path_ptr", 0x20, Ptr32),
+        Field::new("unk4", 0x24, U32),
+        Field::new("spl_fin_small_ptr", 0x28, Ptr),
+        Field::new("spl_fin_big_ptr", 0x30, Ptr),
+        Field::new("price", 0x38, U32),
+        Field::new("unlock_condition", 0x3c, U32),
+        Field::new("search_code_ptr", 0x40, Ptr32),
+        Field::new("unk1", 0x44, U32),
+        Field::new("section_id_ptr", 0x48, Ptr32),
+        Field::new("unk2", 0x4c, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "ougi_finish_param.rs",
+    credit: Some(Credit::new("Portable Productions", Some("https://www.youtube.com/@PortableProductions"))),
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("index", "Id of the entry"),
+        FieldInfo::new("search_code", "searchcode of the PlayerSettingParam preset"),
+        fields::PRICE,
+        fields::UNLOCK_CONDITION,
+    ]
+};
+
+pub const LINT_RULES: &[Rule] = &[
+    Rule { name: "zero-price-locked", severity: Severity::Warning, description: lint::ZERO_PRICE_LOCKED, limit: None, check: zero_price_locked },
+];
+
+fn zero_price_locked(parsed: &dyn NuccBinaryParsed, _: u64) -> Vec<Finding> {
+    let param = parsed.downcast_ref::<OugiFinishParam>().unwrap();
+    lint::zero_price_locked(param.entries.iter().map(|entry| (entry.price, entry.unlock_condition)))
+}
+
 impl NuccBinaryParsed for OugiFinishParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::OugiFinishParam
@@ -110,7 +163,7 @@ impl NuccBinaryParsed for OugiFinishParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -138,11 +191,9 @@ impl From<&[u8]> for OugiFinishParam {
         }
 
         fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            // If the pointer is not 0 or -1, read the string from the pointer
-            if ptr != 0 && ptr < 100 && ptr != 0xffffffff as u64 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
+            // The same pointers check_pointers checks, 0 and -1 are missing strings
+            if !pointers::is_missing(NuccBinaryType::OugiFinishParam, ptr) {
+                super::read_string_from_ptr(reader, ptr, curent_offset)
             } else {
                 String::from("")
             }
@@ -188,28 +239,6 @@ impl From<OugiFinishParam> for Vec<u8> {
 
         writer.write_le(&ougi_finish_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in ougi_finish_param.entries
             .iter_mut()
             .enumerate()
@@ -217,12 +246,13 @@ impl From<OugiFinishParam> for Vec<u8> {
         {
             write_ptr_to_string(&mut writer, &entry.char_name, current_offset as u64, 0x0);
             write_ptr_to_string(&mut writer, &entry.ougi_fin_link, current_offset as u64, 0x8);
-            write_ptr_to_string(&mut writer, &entry.spl_fin, current_offset as u64, 0x18);
-            write_ptr_to_string(&mut writer, &entry.spl_fin_path, current_offset as u64, 0x20);
+            write_ptr_to_string(&mut writer, &entry.unk, current_offset as u64, 0x10);
+            write_ptr32_to_string(&mut writer, &entry.spl_fin, current_offset as u64, 0x18);
+            write_ptr32_to_string(&mut writer, &entry.spl_fin_path, current_offset as u64, 0x20);
             write_ptr_to_string(&mut writer, &entry.spl_fin_small, current_offset as u64, 0x28);
             write_ptr_to_string(&mut writer, &entry.spl_fin_big, current_offset as u64, 0x30);
-            write_ptr_to_string(&mut writer, &entry.search_code, current_offset as u64, 0x40);
-            write_ptr_to_string(&mut writer, &entry.section_id, current_offset as u64, 0x48);
+            write_ptr32_to_string(&mut writer, &entry.search_code, current_offset as u64, 0x40);
+            write_ptr32_to_string(&mut writer, &entry.section_id, current_offset as u64, 0x48);
         }
 
         // Update the indices in case they were changed
@@ -1,13 +1,14 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, Credit};
 
 
 use super::HEADER_SIZE;
 
-// Format reversed by EliteAce170 (https://www.youtube.com/c/EliteAce)
 
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
@@ -42,7 +43,9 @@ pub struct Entry {
     #[serde(skip)]
     pub anm5_name_ptr: u64,
 
+    #[serde(with = "super::float")]
     pub unk4: f32,
+    #[serde(with = "super::float")]
     pub spawn_location: f32,
 
     pub common_sound_id: i32,
@@ -103,6 +106,51 @@ pub struct PlayerDoubleEffectParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x78,
+    entry: &[
+        Field::new("characode_index", 0x0, U32),
+        Field::new("unk1", 0x4, I32),
+        Field::new("bone_name_ptr", 0x8, Ptr),
+        Field::new("animation_start_frame", 0x10, I32),
+        Field::new("animation_end_frame", 0x14, I32),
+        Field::new("unk2", 0x18, I32),
+        Field::new("unk3", 0x1c, I32),
+        Field::new("effect_name_ptr", 0x20, Ptr),
+        Field::new("anm1_name_ptr", 0x28, Ptr),
+        Field::new("anm2_name_ptr", 0x30, Ptr),
+        Field::new("anm3_name_ptr", 0x38, Ptr),
+        Field::new("anm4_name_ptr", 0x40, Ptr),
+        Field::new("anm5_name_ptr", 0x48, Ptr),
+        Field::new("unk4", 0x50, F32),
+        Field::new("spawn_location", 0x54, F32),
+        Field::new("common_sound_id", 0x58, I32),
+        Field::new("unk5", 0x5c, U32),
+        Field::new("unk6", 0x60, U32),
+        Field::new("unk7", 0x64, U32),
+        Field::new("unk8", 0x68, U32),
+        Field::new("unk9", 0x6c, U32),
+        Field::new("unk10", 0x70, U32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "player_double_effect_param.rs",
+    credit: Some(Credit::new("EliteAce170", Some("https://www.youtube.com/c/EliteAce"))),
+    deserialize: true,
+    fields: &[
+        fields::CHARACODE_INDEX,
+    ]
+};
+
 impl NuccBinaryParsed for PlayerDoubleEffectParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::PlayerDoubleEffectParam
@@ -113,7 +161,7 @@ impl NuccBinaryParsed for PlayerDoubleEffectParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -141,16 +189,6 @@ impl From<&[u8]> for PlayerDoubleEffectParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -188,28 +226,6 @@ impl From<PlayerDoubleEffectParam> for Vec<u8> {
 
         writer.write_le(&player_double_effect_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in player_double_effect_param.entries
             .iter_mut()
             .enumerate()
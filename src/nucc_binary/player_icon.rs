@@ -1,12 +1,13 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, Credit};
 
 use super::HEADER_SIZE;
 
-// Format was reversed by TheLeonX (https://github.com/TheLeonX)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Entry {
@@ -58,6 +59,35 @@ pub struct PlayerIcon {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x28,
+    entry: &[
+        Field::new("characode_index", 0x0, U32),
+        Field::new("duel_player_param_costume_index", 0x4, I32),
+        Field::new("icon_id_ptr", 0x8, Ptr),
+        Field::new("awakening_icon_id_ptr", 0x10, Ptr),
+        Field::new("name_id_ptr", 0x18, Ptr),
+        Field::new("sub_ninjutsu_icon_id_ptr", 0x20, Ptr),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "player_icon.rs",
+    credit: Some(Credit::new("TheLeonX", Some("https://github.com/TheLeonX"))),
+    deserialize: true,
+    fields: &[
+        fields::CHARACODE_INDEX,
+    ]
+};
+
 impl NuccBinaryParsed for PlayerIcon {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::PlayerIcon
@@ -68,7 +98,7 @@ impl NuccBinaryParsed for PlayerIcon {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -96,16 +126,6 @@ impl From<&[u8]> for PlayerIcon {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -140,28 +160,6 @@ impl From<PlayerIcon> for Vec<u8> {
 
         writer.write_le(&player_icon.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in player_icon.entries
             .iter_mut()
             .enumerate()
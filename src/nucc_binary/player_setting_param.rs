@@ -1,8 +1,10 @@
-use binrw::{binrw, BinReaderExt, BinWriterExt, NullString};
-use binrw::io::{Cursor, Seek, SeekFrom};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+use binrw::io::Cursor;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, read_string_from_ptr, write_ptr_to_string};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, FieldInfo};
 
 
 const HEADER_SIZE: usize = 0x14; // Size of NUCC Binary headers
@@ -28,7 +30,7 @@ pub struct Entry {
 
 
     pub dlc_id: i32,
-    pub main_player_setting_id: i32, // Reference id to main preset
+    pub main_player_setting_id: i32,
     pub main_characode_index: u32,
     pub unk2: i32,
 
@@ -62,6 +64,46 @@ pub struct PlayerSettingParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x40,
+    entry: &[
+        Field::new("player_setting_id", 0x0, U32),
+        Field::new("characode_index", 0x4, U32),
+        Field::new("duel_player_param_model_index", 0x8, I32),
+        Field::new("unk1", 0xc, U32),
+        Field::new("searchcode_ptr", 0x10, Ptr),
+        Field::new("default_jutsu", 0x18, I32),
+        Field::new("default_uj", 0x1c, I32),
+        Field::new("cha_a_id_ptr", 0x20, Ptr),
+        Field::new("cha_b_id_ptr", 0x28, Ptr),
+        Field::new("dlc_id", 0x30, I32),
+        Field::new("main_player_setting_id", 0x34, I32),
+        Field::new("main_characode_index", 0x38, U32),
+        Field::new("unk2", 0x3c, I32),
+    ],
+    ptr_bias: 4
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "player_setting_param.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[
+        FieldInfo::new("player_setting_id", "Id of the preset, the other params refer to it"),
+        fields::CHARACODE_INDEX,
+        FieldInfo::new("searchcode", "Unique name of the preset"),
+        FieldInfo::new("main_player_setting_id", "Reference id to the main preset"),
+        FieldInfo::new("main_characode_index", "Characode index of the main preset"),
+    ]
+};
+
 impl NuccBinaryParsed for PlayerSettingParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::PlayerSettingParam
@@ -72,7 +114,7 @@ impl NuccBinaryParsed for PlayerSettingParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -101,16 +143,6 @@ impl From<&[u8]> for PlayerSettingParam {
             entries.push(entry);
         }
 
-        fn read_string_from_ptr(reader: &mut Cursor<&[u8]>, ptr: u64, curent_offset: u64) -> String {
-            if ptr != 0 {
-                reader.seek(SeekFrom::Start(curent_offset as u64)).unwrap();
-                reader.seek(SeekFrom::Current(ptr as i64)).unwrap();
-                reader.read_be::<NullString>().unwrap().to_string()
-            } else {
-                String::from("")
-            }
-        }
-
         for (current_offset, entry) in entries
         .iter_mut()
         .enumerate()
@@ -144,28 +176,6 @@ impl From<PlayerSettingParam> for Vec<u8> {
 
         writer.write_le(&player_setting_param.entries).unwrap();
 
-        fn write_ptr_to_string(
-            writer: &mut Cursor<Vec<u8>>,
-            string: &String,
-            current_offset: u64,
-            adjustment: u64,
-        ) {
-            if !string.is_empty() {
-               writer.seek(SeekFrom::End(0)).unwrap();
-                let string_pos = writer.seek(SeekFrom::End(0)).unwrap();
-                writer.write_be::<NullString>(&NullString::from(string.clone())).unwrap();
-
-                // Align to 8 bytes
-                let pos = writer.seek(SeekFrom::Current(0)).unwrap() - string_pos;
-                if 8 - (pos % 8) != 8  {
-                    writer.write_le::<Vec<u8>>(&vec![0; 8 - (pos % 8) as usize]).unwrap();
-                }
-
-                writer.seek(SeekFrom::Start((current_offset + adjustment) as u64)).unwrap();
-                writer.write_le::<u64>(&(string_pos - current_offset - &adjustment)).unwrap();
-                
-            }
-        }
         for (current_offset, entry) in player_setting_param.entries
             .iter_mut()
             .enumerate()
@@ -2,6 +2,7 @@ use serde::{Serialize, Deserialize};
 
 
 use super::{NuccBinaryParsed, NuccBinaryType};
+use super::fields::FormatInfo;
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -9,6 +10,13 @@ pub struct Png {
     pub file: Vec<u8>
 }
 
+pub const INFO: FormatInfo = FormatInfo {
+    source: "png.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[]
+};
+
 impl NuccBinaryParsed for Png {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::Png
@@ -0,0 +1,155 @@
+use std::fmt;
+
+use super::layout::{Field, FieldType, Layout};
+use super::NuccBinaryType;
+
+
+/// A string pointer that doesn't point to a string in the chunk's string pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerError {
+    pub entry: usize,
+    pub field: String,
+
+    /// Where the pointer itself is stored, and its size
+    pub offset: usize,
+    pub size: usize,
+    pub ptr: u64,
+    pub message: String
+}
+
+impl fmt::Display for PointerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "entry {}, {} (at {:#x}) = {:#x}: {}", self.entry, self.field, self.offset, self.ptr, self.message)
+    }
+}
+
+
+/// Checks that every string pointer lands inside the chunk, after the entry table and on a null terminated string
+pub fn check_pointers(nucc_binary_type: NuccBinaryType, data: &[u8]) -> Vec<PointerError> {
+    let layout = match nucc_binary_type.layout() {
+        Some(layout) if layout.has_pointers() => layout,
+        _ => return Vec::new()
+    };
+
+    let entry_count = match entry_count(layout, data) {
+        Some(entry_count) => entry_count,
+        None => return Vec::new()
+    };
+
+    let table_end = layout.entries_offset + entry_count * layout.entry_size;
+    let mut errors = Vec::new();
+
+    for entry in 0..entry_count {
+        let checker = Checker { nucc_binary_type, layout, data, table_end, entry };
+        checker.check_fields(layout.entry, layout.entries_offset + entry * layout.entry_size, "", &mut errors);
+    }
+
+    errors
+}
+
+/// Whether a pointer stands for a missing string rather than pointing to one.
+/// Readers go by this too, so they follow exactly the pointers that were checked
+pub fn is_missing(nucc_binary_type: NuccBinaryType, ptr: u64) -> bool {
+    // OugiFinishParam also uses -1 for missing strings
+    ptr == 0 || (nucc_binary_type == NuccBinaryType::OugiFinishParam && ptr == 0xffffffff)
+}
+
+/// Zeroes the pointers that failed the check, so they're read as empty strings
+pub fn clear_pointers(data: &mut [u8], errors: &[PointerError]) {
+    for error in errors {
+        if let Some(ptr) = data.get_mut(error.offset..error.offset + error.size) {
+            ptr.fill(0);
+        }
+    }
+}
+
+
+fn entry_count(layout: &Layout, data: &[u8]) -> Option<usize> {
+    let field = match layout.entry_count {
+        Some(name) => layout.field(name)?,
+        None => return Some(1)
+    };
+
+    let bytes = data.get(field.offset..field.offset + field.size())?;
+
+    match field.field_type {
+        FieldType::U16 => Some(u16::from_le_bytes(bytes.try_into().unwrap()) as usize),
+        FieldType::U32 => Some(u32::from_le_bytes(bytes.try_into().unwrap()) as usize),
+        _ => None
+    }
+}
+
+struct Checker<'a> {
+    nucc_binary_type: NuccBinaryType,
+    layout: &'a Layout,
+    data: &'a [u8],
+    table_end: usize,
+    entry: usize
+}
+
+impl Checker<'_> {
+    fn check_fields(&self, fields: &[Field], base: usize, prefix: &str, errors: &mut Vec<PointerError>) {
+        for field in fields {
+            let offset = base + field.offset;
+
+            match field.field_type {
+                FieldType::Ptr | FieldType::Ptr32 => {
+                    if let Some(error) = self.check_pointer(offset, field.size()) {
+                        errors.push(PointerError {
+                            entry: self.entry,
+                            field: format!("{}{}", prefix, field.name),
+                            offset,
+                            size: field.size(),
+                            ptr: self.read_ptr(offset, field.size()).unwrap_or_default(),
+                            message: error
+                        });
+                    }
+                }
+
+                FieldType::Array { count, stride, fields } => {
+                    for index in 0..count {
+                        let prefix = format!("{}{}[{}].", prefix, field.name, index);
+                        self.check_fields(fields, offset + index * stride, &prefix, errors);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn check_pointer(&self, offset: usize, size: usize) -> Option<String> {
+        let ptr = match self.read_ptr(offset, size) {
+            Some(ptr) => ptr,
+            None => return Some(format!("the entry table runs past the end of the chunk ({:#x})", self.data.len()))
+        };
+
+        if is_missing(self.nucc_binary_type, ptr) {
+            return None;
+        }
+
+        let target = match ((offset + self.layout.ptr_bias) as u64).checked_add(ptr) {
+            Some(target) if target < self.data.len() as u64 => target as usize,
+            _ => return Some(format!("points past the end of the chunk ({:#x})", self.data.len()))
+        };
+
+        if target < self.table_end {
+            return Some(format!("points to {:#x}, inside the entry table which ends at {:#x}", target, self.table_end));
+        }
+
+        if !self.data[target..].contains(&0) {
+            return Some(format!("points to {:#x}, but the string there isn't null terminated", target));
+        }
+
+        None
+    }
+
+    fn read_ptr(&self, offset: usize, size: usize) -> Option<u64> {
+        let bytes = self.data.get(offset..offset + size)?;
+
+        match size {
+            4 => Some(u32::from_le_bytes(bytes.try_into().unwrap()) as u64),
+            _ => Some(u64::from_le_bytes(bytes.try_into().unwrap()))
+        }
+    }
+}
@@ -1,16 +1,16 @@
 use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, FixedString};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::FormatInfo;
 
 const STR_LEN: usize = 0x8;
 
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub characode: String,
+    pub characode: FixedString<STR_LEN>,
 
     pub modelcodes: [VecString; 0x10],
 
@@ -25,9 +25,7 @@ pub struct Entry {
 #[binrw]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct VecString {
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)]
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub code: String
+    pub code: FixedString<STR_LEN>
 }
 
 
@@ -37,6 +35,31 @@ pub struct PrmBas {
     pub entry: Entry
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[],
+    entry_count: None,
+    entries_offset: 0x0,
+    entry_size: 0x2e0,
+    entry: &[
+        Field::new("characode", 0x0, Str(STR_LEN)),
+        Field::new("modelcodes", 0x8, Array { count: 0x10, stride: 0x8, fields: &[
+            Field::new("code", 0x0, Str(STR_LEN)),
+        ] }),
+        Field::new("awamodelcodes", 0xa0, Array { count: 0x10, stride: 0x8, fields: &[
+            Field::new("code", 0x0, Str(STR_LEN)),
+        ] }),
+        Field::new("extra", 0x120, Bytes(0x1C0)),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "prm_bas.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[]
+};
+
 impl NuccBinaryParsed for PrmBas {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::PrmBas
@@ -47,7 +70,7 @@ impl NuccBinaryParsed for PrmBas {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -55,7 +78,7 @@ impl NuccBinaryParsed for PrmBas {
             Self: Sized,
 
         {   
-            serde_json::from_slice(data).unwrap()
+            super::from_json(data)
         }
 }
 
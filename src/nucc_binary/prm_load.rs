@@ -2,23 +2,20 @@ use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, FixedString};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{FormatInfo, Credit};
 
 const STR_LEN: usize = 0x20;
 
-// Format was reversed by TheLeonX (https://github.com/TheLeonX)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
     pub unk1: i32,
 
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub folder: String,
+    pub folder: FixedString<STR_LEN>,
 
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub code: String,
+    pub code: FixedString<STR_LEN>,
 
     pub file_type: i32,
     pub unk2: i32,
@@ -36,6 +33,30 @@ pub struct PrmLoad {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("entry_count", 0x0, U32),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x4,
+    entry_size: 0x50,
+    entry: &[
+        Field::new("unk1", 0x0, I32),
+        Field::new("folder", 0x4, Str(STR_LEN)),
+        Field::new("code", 0x24, Str(STR_LEN)),
+        Field::new("file_type", 0x44, I32),
+        Field::new("unk2", 0x48, I32),
+        Field::new("load_condition", 0x4c, I32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "prm_load.rs",
+    credit: Some(Credit::new("TheLeonX", Some("https://github.com/TheLeonX"))),
+    deserialize: true,
+    fields: &[]
+};
 
 impl NuccBinaryParsed for PrmLoad {
     fn binary_type(&self) -> NuccBinaryType {
@@ -47,7 +68,7 @@ impl NuccBinaryParsed for PrmLoad {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -55,6 +76,6 @@ impl NuccBinaryParsed for PrmLoad {
             Self: Sized,
 
         {   
-            serde_json::from_slice(data).unwrap()
+            super::from_json(data)
         }
 }
\ No newline at end of file
@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::layout::{Field, FieldType, Layout};
+use super::pointers::is_missing;
+use super::text_encoding::decode;
+use super::NuccBinaryType;
+
+
+/// Every field of a chunk with its absolute offset, including the pointers and versions that the normal export hides.
+/// Meant for reversing formats, it can't be repacked.
+#[derive(Serialize, Debug)]
+pub struct RawChunk {
+    pub size: String,
+    pub header: Vec<RawField>,
+    pub entries: Vec<RawEntry>,
+    pub strings: Vec<RawString>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unread: Option<RawBytes>
+}
+
+#[derive(Serialize, Debug)]
+pub struct RawEntry {
+    pub index: usize,
+    pub offset: String,
+    pub fields: Vec<RawField>
+}
+
+#[derive(Serialize, Debug)]
+pub struct RawField {
+    pub name: String,
+    pub offset: String,
+
+    #[serde(rename = "type")]
+    pub field_type: String,
+
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub bytes: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+
+    /// Where a pointer points to, and the string found there
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub string: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<Vec<RawEntry>>
+}
+
+#[derive(Serialize, Debug)]
+pub struct RawString {
+    pub offset: String,
+    pub text: String,
+
+    /// How many pointers point to this string
+    pub references: usize
+}
+
+#[derive(Serialize, Debug)]
+pub struct RawBytes {
+    pub offset: String,
+    pub bytes: String
+}
+
+
+pub fn raw_export(nucc_binary_type: NuccBinaryType, layout: &Layout, data: &[u8]) -> Result<RawChunk, String> {
+    let mut targets = BTreeMap::new();
+
+    let header = read_fields(nucc_binary_type, layout.header, 0, layout.header_size(), layout, data, &mut targets)?;
+
+    let entry_count = match layout.entry_count {
+        Some(name) => {
+            let field = layout.field(name).ok_or_else(|| format!("{} isn't a header field", name))?;
+            read_uint(field, data)? as usize
+        }
+        None => 1
+    };
+
+    let mut entries = Vec::new();
+
+    for index in 0..entry_count {
+        let offset = layout.entries_offset + index * layout.entry_size;
+
+        entries.push(RawEntry {
+            index,
+            offset: hex(offset),
+            fields: read_fields(nucc_binary_type, layout.entry, offset, layout.entry_size, layout, data, &mut targets)?
+        });
+    }
+
+    // Formats with pointers keep their strings after the entries, anything else left over is shown as is
+    let table_end = (layout.entries_offset + entry_count * layout.entry_size).min(data.len());
+    let rest = &data[table_end..];
+
+    let (strings, unread) = if layout.has_pointers() {
+        (read_strings(rest, table_end, &targets), None)
+    } else if rest.iter().any(|&b| b != 0) {
+        (Vec::new(), Some(RawBytes { offset: hex(table_end), bytes: hex::encode(rest) }))
+    } else {
+        (Vec::new(), None)
+    };
+
+    Ok(RawChunk {
+        size: hex(data.len()),
+        header,
+        entries,
+        strings,
+        unread
+    })
+}
+
+fn read_fields(nucc_binary_type: NuccBinaryType, fields: &[Field], base: usize, size: usize, layout: &Layout, data: &[u8], targets: &mut BTreeMap<usize, usize>) -> Result<Vec<RawField>, String> {
+    let mut raw_fields = Vec::new();
+    let mut end = 0;
+
+    let mut fields = fields.to_vec();
+    fields.sort_by_key(|field| field.offset);
+
+    for field in &fields {
+        if field.offset > end {
+            raw_fields.push(padding(base + end, slice(data, base + end, field.offset - end, "padding")?));
+        }
+
+        raw_fields.push(read_field(nucc_binary_type, field, base, layout, data, targets)?);
+        end = end.max(field.offset + field.size());
+    }
+
+    if size > end {
+        raw_fields.push(padding(base + end, slice(data, base + end, size - end, "padding")?));
+    }
+
+    Ok(raw_fields)
+}
+
+fn read_field(nucc_binary_type: NuccBinaryType, field: &Field, base: usize, layout: &Layout, data: &[u8], targets: &mut BTreeMap<usize, usize>) -> Result<RawField, String> {
+    let offset = base + field.offset;
+    let bytes = slice(data, offset, field.size(), field.name)?;
+
+    let mut raw_field = RawField {
+        name: field.name.to_string(),
+        offset: hex(offset),
+        field_type: field.field_type.name(),
+        bytes: hex::encode(bytes),
+        value: None,
+        target: None,
+        string: None,
+        entries: None
+    };
+
+    let value = match field.field_type {
+        FieldType::U8 => bytes[0].into(),
+        FieldType::I8 => (bytes[0] as i8).into(),
+        FieldType::U16 => u16::from_le_bytes(bytes.try_into().unwrap()).into(),
+        FieldType::I16 => i16::from_le_bytes(bytes.try_into().unwrap()).into(),
+        FieldType::U32 => u32::from_le_bytes(bytes.try_into().unwrap()).into(),
+        FieldType::U32Be => u32::from_be_bytes(bytes.try_into().unwrap()).into(),
+        FieldType::I32 => i32::from_le_bytes(bytes.try_into().unwrap()).into(),
+        FieldType::U64 => u64::from_le_bytes(bytes.try_into().unwrap()).into(),
+        FieldType::I64 => i64::from_le_bytes(bytes.try_into().unwrap()).into(),
+
+        FieldType::F32 => {
+            let value = f32::from_le_bytes(bytes.try_into().unwrap());
+            super::float::serialize(&value, serde_json::value::Serializer).unwrap()
+        }
+
+        FieldType::Bytes(_) => return Ok(raw_field),
+
+        FieldType::Str(_) => {
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            decode(&bytes[..end]).into()
+        }
+
+        FieldType::Ptr | FieldType::Ptr32 => {
+            let ptr = match bytes.len() {
+                4 => u32::from_le_bytes(bytes.try_into().unwrap()) as u64,
+                _ => u64::from_le_bytes(bytes.try_into().unwrap())
+            };
+
+            let target = ((offset + layout.ptr_bias) as u64).checked_add(ptr).filter(|_| !is_missing(nucc_binary_type, ptr));
+
+            if let Some(target) = target {
+                raw_field.target = Some(format!("{:#x}", target));
+
+                if let Some(string) = data.get(target as usize..).and_then(|rest| rest.split(|&b| b == 0).next()) {
+                    raw_field.string = Some(decode(string));
+                    *targets.entry(target as usize).or_default() += 1;
+                }
+            }
+
+            ptr.into()
+        }
+
+        FieldType::Array { count, stride, fields } => {
+            let mut entries = Vec::new();
+
+            for index in 0..count {
+                let offset = offset + index * stride;
+
+                entries.push(RawEntry {
+                    index,
+                    offset: hex(offset),
+                    fields: read_fields(nucc_binary_type, fields, offset, stride, layout, data, targets)?
+                });
+            }
+
+            raw_field.bytes = String::new();
+            raw_field.entries = Some(entries);
+            return Ok(raw_field);
+        }
+    };
+
+    raw_field.value = Some(value);
+    Ok(raw_field)
+}
+
+fn read_uint(field: &Field, data: &[u8]) -> Result<u64, String> {
+    let bytes = slice(data, field.offset, field.size(), field.name)?;
+
+    match field.field_type {
+        FieldType::U8 => Ok(bytes[0] as u64),
+        FieldType::U16 => Ok(u16::from_le_bytes(bytes.try_into().unwrap()) as u64),
+        FieldType::U32 | FieldType::I32 => Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as u64),
+        FieldType::U64 => Ok(u64::from_le_bytes(bytes.try_into().unwrap())),
+        _ => Err(format!("{} isn't an integer", field.name))
+    }
+}
+
+fn slice<'a>(data: &'a [u8], offset: usize, size: usize, name: &str) -> Result<&'a [u8], String> {
+    data.get(offset..offset + size)
+        .ok_or_else(|| format!("{} at {} is past the end of the chunk ({})", name, hex(offset), hex(data.len())))
+}
+
+fn padding(offset: usize, bytes: &[u8]) -> RawField {
+    RawField {
+        name: String::from("padding"),
+        offset: hex(offset),
+        field_type: FieldType::Bytes(bytes.len()).name(),
+        bytes: hex::encode(bytes),
+        value: None,
+        target: None,
+        string: None,
+        entries: None
+    }
+}
+
+/// Splits the string pool after the entries into its null terminated strings
+fn read_strings(pool: &[u8], pool_offset: usize, targets: &BTreeMap<usize, usize>) -> Vec<RawString> {
+    let mut strings = Vec::new();
+    let mut start = 0;
+
+    while start < pool.len() {
+        if pool[start] == 0 {
+            start += 1;
+            continue;
+        }
+
+        let end = pool[start..].iter().position(|&b| b == 0).map_or(pool.len(), |len| start + len);
+        let offset = pool_offset + start;
+
+        strings.push(RawString {
+            offset: hex(offset),
+            text: decode(&pool[start..end]),
+            references: targets.get(&offset).copied().unwrap_or(0)
+        });
+
+        start = end;
+    }
+
+    strings
+}
+
+fn hex(offset: usize) -> String {
+    format!("{:#x}", offset)
+}
@@ -2,9 +2,10 @@ use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
 use super::{NuccBinaryParsed, NuccBinaryType};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, Credit};
 
 
-// Format reversed by valant96
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
@@ -32,6 +33,32 @@ pub struct SkillIndexSettingParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0xc,
+    entry: &[
+        Field::new("characode_index", 0x0, U32),
+        Field::new("first_jutsu_skl_index", 0x4, I32),
+        Field::new("second_jutsu_skl_index", 0x8, I32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "skill_index_setting_param.rs",
+    credit: Some(Credit::new("valant96", None)),
+    deserialize: true,
+    fields: &[
+        fields::CHARACODE_INDEX,
+    ]
+};
+
 impl NuccBinaryParsed for SkillIndexSettingParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::SkillIndexSettingParam
@@ -42,7 +69,7 @@ impl NuccBinaryParsed for SkillIndexSettingParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -1,7 +1,9 @@
 use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
-use super::{NuccBinaryParsed, NuccBinaryType};
+use super::{NuccBinaryParsed, NuccBinaryType, FixedString};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::FormatInfo;
 
 const STR_LEN: usize = 0x20;
 
@@ -9,11 +11,10 @@ const STR_LEN: usize = 0x20;
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
-    #[br(map = |x: Vec<u8>| String::from_utf8_lossy(&x).trim_end_matches('\u{0}').to_string(), count = STR_LEN)] // Need to trim the null bytes
-    #[bw(map = |x: &String| (x.clone() + String::from('\u{0}').repeat(STR_LEN - x.len()).as_str()).into_bytes())]
-    pub sound_name: String,
+    pub sound_name: FixedString<STR_LEN>,
 
     #[brw(pad_before = 0x2)]
+    #[serde(with = "super::float")]
     pub volume: f32,
     pub unk1: i16,
     pub unk2: i16,
@@ -21,13 +22,16 @@ pub struct Entry {
     pub unk3: i16,
     pub pitch: i16,
 
+    #[serde(with = "super::float")]
     pub unk4: f32,
+    #[serde(with = "super::float")]
     pub timing: f32,
 
     pub index: i16,
     pub unk6: i16,
     pub unk7: i16,
 
+    #[serde(with = "super::float")]
     pub unk8: f32,
 
 }
@@ -42,6 +46,36 @@ pub struct Snd {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("entry_count", 0x0, U16),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x2,
+    entry_size: 0x40,
+    entry: &[
+        Field::new("sound_name", 0x0, Str(STR_LEN)),
+        Field::new("volume", 0x22, F32),
+        Field::new("unk1", 0x26, I16),
+        Field::new("unk2", 0x28, I16),
+        Field::new("unk3", 0x2a, I16),
+        Field::new("pitch", 0x2c, I16),
+        Field::new("unk4", 0x2e, F32),
+        Field::new("timing", 0x32, F32),
+        Field::new("index", 0x36, I16),
+        Field::new("unk6", 0x38, I16),
+        Field::new("unk7", 0x3a, I16),
+        Field::new("unk8", 0x3c, F32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "snd.rs",
+    credit: None,
+    deserialize: true,
+    fields: &[]
+};
 
 impl NuccBinaryParsed for Snd {
     fn binary_type(&self) -> NuccBinaryType {
@@ -53,7 +87,7 @@ impl NuccBinaryParsed for Snd {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
@@ -61,6 +95,6 @@ impl NuccBinaryParsed for Snd {
             Self: Sized,
 
         {   
-            serde_json::from_slice(data).unwrap()
+            super::from_json(data)
         }
 }
\ No newline at end of file
@@ -2,8 +2,9 @@ use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
 use super::{NuccBinaryParsed, NuccBinaryType};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, Credit};
 
-// Format reversed by Portable Productions (https://www.youtube.com/@PortableProductions)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
@@ -36,6 +37,35 @@ pub struct SupportActionParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x18,
+    entry: &[
+        Field::new("characode_index", 0x0, U32),
+        Field::new("unk1", 0x4, U32),
+        Field::new("unk2", 0x8, U32),
+        Field::new("support_type", 0xc, U32),
+        Field::new("unk3", 0x10, I32),
+        Field::new("unk4", 0x14, I32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "support_action_param.rs",
+    credit: Some(Credit::new("Portable Productions", Some("https://www.youtube.com/@PortableProductions"))),
+    deserialize: true,
+    fields: &[
+        fields::CHARACODE_INDEX,
+    ]
+};
+
 impl NuccBinaryParsed for SupportActionParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::SupportActionParam
@@ -46,7 +76,7 @@ impl NuccBinaryParsed for SupportActionParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
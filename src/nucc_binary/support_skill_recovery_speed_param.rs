@@ -2,22 +2,31 @@ use binrw::binrw;
 use serde::{Serialize, Deserialize};
 
 use super::{NuccBinaryParsed, NuccBinaryType};
+use super::layout::{Layout, Field, FieldType::*};
+use super::fields::{self, FormatInfo, FieldInfo, Credit};
 
 
-// Format reversed by Xact (https://www.youtube.com/@Xact__)
 #[binrw]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Entry {
     pub characode_index: u32,
 
+    #[serde(with = "super::float")]
     pub skll1: f32,
+    #[serde(with = "super::float")]
     pub skll2: f32,
+    #[serde(with = "super::float")]
     pub skll3: f32,
+    #[serde(with = "super::float")]
     pub skll4: f32,
+    #[serde(with = "super::float")]
     pub skll5: f32,
+    #[serde(with = "super::float")]
     pub skll6: f32,
 
-    pub awa_skll1: f32, // Awakening skill
+    #[serde(with = "super::float")]
+    pub awa_skll1: f32,
+    #[serde(with = "super::float")]
     pub awa_skll2: f32,
 
 
@@ -41,6 +50,39 @@ pub struct SupportSkillRecoverySpeedParam {
     pub entries: Vec<Entry>
 }
 
+pub const LAYOUT: Layout = Layout {
+    header: &[
+        Field::new("version", 0x0, U32),
+        Field::new("entry_count", 0x4, U32),
+        Field::new("entry_ptr", 0x8, U64),
+    ],
+    entry_count: Some("entry_count"),
+    entries_offset: 0x10,
+    entry_size: 0x24,
+    entry: &[
+        Field::new("characode_index", 0x0, U32),
+        Field::new("skll1", 0x4, F32),
+        Field::new("skll2", 0x8, F32),
+        Field::new("skll3", 0xc, F32),
+        Field::new("skll4", 0x10, F32),
+        Field::new("skll5", 0x14, F32),
+        Field::new("skll6", 0x18, F32),
+        Field::new("awa_skll1", 0x1c, F32),
+        Field::new("awa_skll2", 0x20, F32),
+    ],
+    ptr_bias: 0
+};
+
+pub const INFO: FormatInfo = FormatInfo {
+    source: "support_skill_recovery_speed_param.rs",
+    credit: Some(Credit::new("Xact", Some("https://www.youtube.com/@Xact__"))),
+    deserialize: true,
+    fields: &[
+        fields::CHARACODE_INDEX,
+        FieldInfo::new("awa_skll1", "Awakening skill"),
+    ]
+};
+
 impl NuccBinaryParsed for SupportSkillRecoverySpeedParam {
     fn binary_type(&self) -> NuccBinaryType {
         NuccBinaryType::SupportSkillRecoverySpeedParam
@@ -51,7 +93,7 @@ impl NuccBinaryParsed for SupportSkillRecoverySpeedParam {
     }
 
     fn serialize(&self) -> Vec<u8> {
-        serde_json::to_string_pretty(self).unwrap().into()
+        super::to_json(self)
     }
 
     fn deserialize(data: &[u8]) -> Self
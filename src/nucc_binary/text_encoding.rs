@@ -0,0 +1,243 @@
+use std::cell::Cell;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use encoding_rs::{DecoderResult, Encoding, SHIFT_JIS, UTF_8};
+
+
+/// Strings that couldn't be decoded cleanly are exported with this prefix,
+/// with every undecodable byte written as `\xNN` and every backslash doubled.
+pub const ESCAPE_PREFIX: &str = "!esc:";
+
+/// Encodings tried in order when detecting the encoding of a chunk
+const CANDIDATES: [&Encoding; 2] = [UTF_8, SHIFT_JIS];
+
+thread_local! {
+    static ENCODING: Cell<&'static Encoding> = Cell::new(UTF_8);
+    static MALFORMED: Cell<usize> = Cell::new(0);
+}
+
+
+/// Restores the encoding it holds when dropped, even when the code it guards panics
+struct EncodingGuard(&'static Encoding);
+
+impl Drop for EncodingGuard {
+    fn drop(&mut self) {
+        ENCODING.with(|e| e.set(self.0));
+    }
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextEncoding {
+    Auto,
+    Fixed(&'static Encoding)
+}
+
+impl FromStr for TextEncoding {
+    type Err = String;
+
+    fn from_str(label: &str) -> Result<Self, Self::Err> {
+        if label.eq_ignore_ascii_case("auto") {
+            return Ok(TextEncoding::Auto);
+        }
+
+        Encoding::for_label(label.as_bytes())
+            .map(TextEncoding::Fixed)
+            .ok_or_else(|| format!("Unknown text encoding: {}", label))
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextEncoding::Auto => f.write_str("auto"),
+            TextEncoding::Fixed(encoding) => f.write_str(encoding.name())
+        }
+    }
+}
+
+
+/// Runs `f` with every string read or written on this thread in `encoding`, then puts the previous encoding back
+pub fn with_encoding<T>(encoding: &'static Encoding, f: impl FnOnce() -> T) -> T {
+    let _guard = EncodingGuard(ENCODING.with(|e| e.replace(encoding)));
+    f()
+}
+
+pub fn encoding() -> &'static Encoding {
+    ENCODING.with(|e| e.get())
+}
+
+/// Runs `read` with each candidate encoding and keeps the first one that decodes every string cleanly.
+/// Falls back to UTF-8, which still keeps the undecodable bytes as escapes.
+pub fn detect<T>(mut read: impl FnMut() -> T) -> (T, &'static Encoding) {
+    for candidate in CANDIDATES {
+        MALFORMED.with(|m| m.set(0));
+
+        let parsed = with_encoding(candidate, &mut read);
+
+        if MALFORMED.with(|m| m.get()) == 0 {
+            return (parsed, candidate);
+        }
+    }
+
+    (with_encoding(UTF_8, read), UTF_8)
+}
+
+
+pub fn decode(bytes: &[u8]) -> String {
+    let encoding = encoding();
+
+    if let Some(text) = encoding.decode_without_bom_handling_and_without_replacement(bytes) {
+        if !text.starts_with(ESCAPE_PREFIX) {
+            return text.into_owned();
+        }
+    } else {
+        MALFORMED.with(|m| m.set(m.get() + 1));
+    }
+
+    escape(bytes, encoding)
+}
+
+fn escape(bytes: &[u8], encoding: &'static Encoding) -> String {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut escaped = String::from(ESCAPE_PREFIX);
+    let mut text = String::new();
+    let mut src = bytes;
+
+    loop {
+        text.clear();
+        text.reserve(src.len() * 3 + 16);
+
+        let (result, read) = decoder.decode_to_string_without_replacement(src, &mut text, true);
+        escaped.push_str(&text.replace('\\', "\\\\"));
+
+        match result {
+            DecoderResult::InputEmpty => break,
+            DecoderResult::OutputFull => {}
+            DecoderResult::Malformed(bad, after) => {
+                let end = read - after as usize;
+
+                for byte in &src[end - bad as usize..end] {
+                    write!(escaped, "\\x{:02X}", byte).unwrap();
+                }
+            }
+        }
+
+        src = &src[read..];
+    }
+
+    escaped
+}
+
+
+pub fn encode(text: &str) -> Result<Vec<u8>, String> {
+    let escaped = match text.strip_prefix(ESCAPE_PREFIX) {
+        Some(escaped) => escaped,
+        None => return encode_plain(text)
+    };
+
+    let mut bytes = Vec::new();
+    let mut plain = String::new();
+    let mut chars = escaped.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            plain.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('\\') => plain.push('\\'),
+            Some('x') => {
+                let digits: String = chars.by_ref().take(2).collect();
+
+                // from_str_radix alone would take a sign, or a single digit at the end of the string
+                if digits.len() != 2 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!("{:?} has an invalid \\x escape, it needs exactly two hex digits", text));
+                }
+
+                let byte = u8::from_str_radix(&digits, 16).unwrap();
+
+                bytes.extend(encode_plain(&plain)?);
+                plain.clear();
+                bytes.push(byte);
+            }
+            _ => return Err(format!("{:?} has a lone backslash, write it as \\\\", text))
+        }
+    }
+
+    bytes.extend(encode_plain(&plain)?);
+    Ok(bytes)
+}
+
+fn encode_plain(text: &str) -> Result<Vec<u8>, String> {
+    let encoding = encoding();
+    let (bytes, _, had_errors) = encoding.encode(text);
+
+    if had_errors {
+        return Err(format!("{:?} can't be represented in {}", text, encoding.name()));
+    }
+
+    Ok(bytes.into_owned())
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{SHIFT_JIS, UTF_8};
+
+    use super::*;
+
+    #[test]
+    fn escape_round_trip_test() {
+        let cases: &[(&'static Encoding, &[u8], &str)] = &[
+            (UTF_8, b"plain", "plain"),
+            (UTF_8, b"back\\slash", "back\\slash"),
+            (UTF_8, b"bad \xFF byte", "!esc:bad \\xFF byte"),
+            (UTF_8, b"\xC3 and \\", "!esc:\\xC3 and \\\\"),
+            (UTF_8, b"!esc:looks escaped", "!esc:!esc:looks escaped"),
+            (SHIFT_JIS, b"\x82\xA0", "\u{3042}"),
+            (SHIFT_JIS, b"\x82\xA0\x82", "!esc:\u{3042}\\x82"),
+            (SHIFT_JIS, b"\x5C", "\u{5C}")
+        ];
+
+        for &(encoding, bytes, text) in cases {
+            let decoded = with_encoding(encoding, || decode(bytes));
+            assert_eq!(decoded, text, "decoding {:?} as {}", bytes, encoding.name());
+
+            let encoded = with_encoding(encoding, || encode(&decoded));
+            assert_eq!(encoded.as_deref(), Ok(bytes), "encoding {:?} as {}", decoded, encoding.name());
+        }
+    }
+
+    #[test]
+    fn invalid_escape_test() {
+        let cases = ["!esc:\\x4", "!esc:\\x", "!esc:\\x+1", "!esc:\\xZZ", "!esc:\\x4 ", "!esc:lone \\ backslash", "!esc:\\"];
+
+        for text in cases {
+            assert!(encode(text).is_err(), "{:?} should be rejected", text);
+        }
+
+        assert_eq!(encode("!esc:\\x41\\x4a"), Ok(b"AJ".to_vec()));
+    }
+
+    #[test]
+    fn with_encoding_test() {
+        assert_eq!(encoding(), UTF_8);
+
+        with_encoding(SHIFT_JIS, || {
+            assert_eq!(encoding(), SHIFT_JIS);
+            with_encoding(UTF_8, || assert_eq!(encoding(), UTF_8));
+            assert_eq!(encoding(), SHIFT_JIS);
+        });
+
+        assert_eq!(encoding(), UTF_8);
+
+        // Detecting leaves the encoding as it was too
+        let (_, detected) = detect(|| decode(b"\x82\xA0"));
+        assert_eq!(detected, SHIFT_JIS);
+        assert_eq!(encoding(), UTF_8);
+    }
+}
\ No newline at end of file
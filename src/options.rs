@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use nuccbin::NuccBinaryType;
+use nuccbin::rotation::RotationView;
+use nuccbin::nucc_binary::text_encoding::TextEncoding;
+
+
+/// Flags that can be passed after the file path, e.g.
+/// `nuccbin.exe data.xfbin --encoding shift_jis --encoding MessageInfo=utf-8`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub encoding: Option<TextEncoding>,
+    pub format_encodings: HashMap<NuccBinaryType, TextEncoding>,
+
+    /// Rounds floats to this many decimal places when unpacking
+    pub float_precision: Option<usize>,
+
+    /// Also writes every chunk with its pointers and offsets to <chunk>.raw.json
+    pub raw: bool,
+
+    /// Unpacks chunks with broken string pointers, reading those strings as empty
+    pub lenient: bool,
+
+    /// Writes every entry to its own file, in a folder per chunk
+    pub split: bool,
+
+    /// Byte identical output across runs and machines, see `nucc_binary::canonical`
+    pub canonical: bool,
+
+    /// Groups CharacterSelectParam's render settings into vectors by context and side, see `render_settings::group`
+    pub group_render_settings: bool,
+
+    /// Writes BodAcc rotations in degrees, as quaternions or as a matrix, see `rotation`
+    pub bodacc_rotation: Option<RotationView>,
+
+    /// A characode xfbin, to show and read characode indices as their characode
+    pub characodes: Option<PathBuf>
+}
+
+impl Options {
+    pub fn new() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--encoding" => {
+                    let value = args.next().ok_or("--encoding needs a value, e.g. shift_jis or MessageInfo=shift_jis")?;
+
+                    match value.split_once('=') {
+                        Some((format, label)) => {
+                            let nucc_binary_type = NuccBinaryType::from_str(format)
+                                .map_err(|_| format!("Unknown format: {}", format))?;
+                            options.format_encodings.insert(nucc_binary_type, label.parse()?);
+                        }
+                        None => options.encoding = Some(value.parse()?)
+                    }
+                }
+
+                "--float-precision" => {
+                    let value = args.next().ok_or("--float-precision needs a number of decimal places")?;
+                    options.float_precision = Some(value.parse().map_err(|_| format!("Invalid number of decimal places: {}", value))?);
+                }
+
+                "--raw" => options.raw = true,
+                "--lenient" => options.lenient = true,
+                "--split" => options.split = true,
+                "--canonical" => options.canonical = true,
+                "--group-render-settings" => options.group_render_settings = true,
+
+                "--bodacc-rotation" => {
+                    let value = args.next().ok_or("--bodacc-rotation needs degrees, quaternion or matrix")?;
+                    options.bodacc_rotation = Some(value.parse()?);
+                }
+
+                "--characodes" => {
+                    let value = args.next().ok_or("--characodes needs the path to characode.bin.xfbin")?;
+                    options.characodes = Some(PathBuf::from(value));
+                }
+
+                _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+                _ => {}
+            }
+        }
+
+        // Rounding depends on what the user asked for, so it can't be canonical
+        if options.canonical && options.float_precision.is_some() {
+            return Err(String::from("--float-precision can't be used with --canonical"));
+        }
+
+        Ok(options)
+    }
+
+    /// The encoding asked for on the command line, a format specific one wins over the per-run one
+    pub fn encoding_for(&self, nucc_binary_type: NuccBinaryType) -> Option<TextEncoding> {
+        self.format_encodings.get(&nucc_binary_type).copied().or(self.encoding)
+    }
+}
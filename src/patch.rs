@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::NuccBinaryType;
+use crate::chunks::edit_chunk;
+use crate::diff::{ChunkDiff, Status, entry_key, keyed_entries};
+
+
+/// Entry level changes to chunks, applied on top of the original game data so mods touching the same file don't overwrite each other
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Patch {
+    pub chunks: Vec<ChunkPatch>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChunkPatch {
+    pub chunk: String,
+    pub format: NuccBinaryType,
+
+    /// Fields outside of the entries, by path (e.g. `unk` or `entries_ptr`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub header: BTreeMap<String, Value>,
+
+    /// Keys of the entries to remove
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edit: Vec<EntryEdit>,
+
+    /// Whole entries, appended after the existing ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub add: Vec<Value>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EntryEdit {
+    pub key: String,
+
+    /// New values by path inside the entry, e.g. `icon_id` or `spl_fin_victims[3].victim_name`
+    pub fields: BTreeMap<String, Value>
+}
+
+
+impl Patch {
+    /// Turns a diff into the patch that replays it. Chunks that were added or removed entirely, or that aren't json, can't be patched.
+    pub fn from_diffs(diffs: &[ChunkDiff]) -> Result<Self, String> {
+        let mut patch = Patch::default();
+
+        for diff in diffs {
+            match diff.status {
+                Status::Added => return Err(format!("{} was added, only changes inside a chunk can be patched", diff.chunk)),
+                Status::Removed => return Err(format!("{} was removed, only changes inside a chunk can be patched", diff.chunk)),
+                Status::Modified => {}
+            }
+
+            if diff.header.iter().any(|change| change.field == "data") {
+                return Err(format!("{} isn't json, it can't be patched", diff.chunk));
+            }
+
+            patch.chunks.push(ChunkPatch {
+                chunk: diff.chunk.clone(),
+                format: diff.format,
+                header: diff.header.iter().map(|change| (change.field.clone(), change.after.clone())).collect(),
+                remove: diff.removed.iter().map(|entry| entry.key.clone()).collect(),
+                edit: diff.modified.iter().map(|entry| EntryEdit {
+                    key: entry.key.clone(),
+                    fields: entry.changes.iter().map(|change| (change.field.clone(), change.after.clone())).collect()
+                }).collect(),
+                add: diff.added.iter().map(|entry| entry.entry.clone()).collect()
+            });
+        }
+
+        Ok(patch)
+    }
+
+    pub fn chunk(&self, name: &str) -> Option<&ChunkPatch> {
+        self.chunks.iter().find(|chunk_patch| chunk_patch.chunk == name)
+    }
+}
+
+impl ChunkPatch {
+    /// Applies the patch to a chunk's unpacked json. Removals and edits refer to the entries as they were before the patch.
+    pub fn apply(&self, value: &mut Value) -> Result<(), String> {
+        for (path, new) in &self.header {
+            set_path(value, path, new.clone())?;
+        }
+
+        let entries = value.get_mut("entries")
+            .and_then(Value::as_array_mut)
+            .ok_or("there are no entries to patch")?;
+
+        let keys = keyed_entries(self.format, entries).into_iter()
+            .enumerate()
+            .map(|(index, (key, _))| (key, index))
+            .collect::<HashMap<_, _>>();
+
+        let find = |key: &String| keys.get(key).copied()
+            .ok_or_else(|| format!("entry {} isn't there anymore", key));
+
+        let mut removed = vec![false; entries.len()];
+
+        for key in &self.remove {
+            removed[find(key)?] = true;
+        }
+
+        for edit in &self.edit {
+            let index = find(&edit.key)?;
+
+            if removed[index] {
+                return Err(format!("entry {} is both edited and removed", edit.key));
+            }
+
+            for (path, new) in &edit.fields {
+                set_path(&mut entries[index], path, new.clone())
+                    .map_err(|err| format!("entry {}: {}", edit.key, err))?;
+            }
+        }
+
+        let mut index = 0;
+        entries.retain(|_| {
+            index += 1;
+            !removed[index - 1]
+        });
+
+        let mut kept = keyed_entries(self.format, entries).into_iter().map(|(key, _)| key).collect::<HashSet<_>>();
+
+        for entry in &self.add {
+            let key = entry_key(self.format, entry, entries.len());
+
+            if !self.format.entry_key().is_empty() && !kept.insert(key.clone()) {
+                return Err(format!("can't add entry {}, there's already one with that key", key));
+            }
+
+            entries.push(entry.clone());
+        }
+
+        let entry_count = entries.len();
+
+        if let Some(count) = value.get_mut("entry_count") {
+            *count = entry_count.into();
+        }
+
+        Ok(())
+    }
+
+    /// Applies the patch to a chunk's binary data, going through the format's typed structs both ways
+    pub fn apply_to_data(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        edit_chunk(self.format, data, |value| self.apply(value))
+            .map(|(data, _)| data)
+            .map_err(|err| format!("{}: {}", self.chunk, err))
+    }
+}
+
+
+/// Sets the value at a path like `a.b[2].c`, which has to exist already
+pub fn set_path(value: &mut Value, path: &str, new: Value) -> Result<(), String> {
+    let mut current = value;
+
+    for part in path.split('.') {
+        let (name, indices) = match part.find('[') {
+            Some(start) => (&part[..start], &part[start..]),
+            None => (part, "")
+        };
+
+        if !name.is_empty() {
+            current = current.get_mut(name).ok_or_else(|| format!("there's no field {}", path))?;
+        }
+
+        for index in indices.split(']').filter(|index| !index.is_empty()) {
+            let index = index.trim_start_matches('[').parse::<usize>().map_err(|_| format!("invalid field path {}", path))?;
+            current = current.get_mut(index).ok_or_else(|| format!("there's no field {}", path))?;
+        }
+    }
+
+    *current = new;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::chunks::Chunk;
+    use crate::diff::diff_chunks;
+    use crate::nucc_binary::*;
+
+    /// A SkillIndexSettingParam chunk's json of (characode_index, first_jutsu_skl_index, second_jutsu_skl_index) entries
+    fn skills(entries: &[(u32, i32, i32)]) -> Value {
+        let entries = entries.iter()
+            .map(|(characode_index, first, second)| json!({ "characode_index": characode_index, "first_jutsu_skl_index": first, "second_jutsu_skl_index": second }))
+            .collect::<Vec<_>>();
+
+        json!({ "entry_count": entries.len(), "entries": entries })
+    }
+
+    fn chunk(value: &Value) -> Chunk {
+        Chunk { name: String::from("skills"), nucc_binary_type: NuccBinaryType::SkillIndexSettingParam, extension: String::from(".json"), data: value.to_string().into_bytes(), encoding: encoding_rs::UTF_8 }
+    }
+
+    fn binary(value: &Value) -> Vec<u8> {
+        let deserializer = NuccBinaryParsedDeserializer(NuccBinaryType::SkillIndexSettingParam, value.to_string().into_bytes());
+        NuccBinaryParsedWriter(deserializer.into()).into()
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let base = [(1, 10, 11), (2, 20, 21), (3, 30, 31)];
+
+        // (before, after), the patch of their diff has to turn one into the other
+        let cases: [(&[(u32, i32, i32)], &[(u32, i32, i32)]); 6] = [
+            (&base, &[(1, 15, 11), (2, 20, 21), (3, 30, 31)]),
+            (&base, &[(1, 10, 11), (3, 30, 31)]),
+            (&base, &[(1, 10, 11), (2, 20, 21), (3, 30, 31), (4, 40, 41)]),
+            (&base, &[(2, 20, 26), (3, 30, 31), (5, 50, 51)]),
+            (&base, &[]),
+
+            // The second entry of a key is patched by its `key#2`
+            (&[(1, 10, 11), (1, 20, 21)], &[(1, 10, 11), (1, 25, 21)]),
+        ];
+
+        for (before, after) in cases {
+            let (before, after) = (skills(before), skills(after));
+
+            let patch = Patch::from_diffs(&diff_chunks(&[chunk(&before)], &[chunk(&after)])).unwrap();
+            let chunk_patch = patch.chunk("skills").unwrap();
+
+            let mut patched = before.clone();
+            chunk_patch.apply(&mut patched).unwrap();
+            assert_eq!(patched, after, "{}", before);
+
+            // And through the format's binary data
+            let data = chunk_patch.apply_to_data(&binary(&before)).unwrap();
+            assert_eq!(data, binary(&after), "{}", before);
+        }
+    }
+
+    #[test]
+    fn missing_entry_test() {
+        let before = skills(&[(1, 10, 11), (2, 20, 21)]);
+        let after = skills(&[(1, 15, 11)]);
+
+        let patch = Patch::from_diffs(&diff_chunks(&[chunk(&before)], &[chunk(&after)])).unwrap();
+        let chunk_patch = patch.chunk("skills").unwrap();
+
+        // The entry the patch edits or removes is gone from the target
+        let targets = [
+            (skills(&[(2, 20, 21)]), "entry 1 isn't there anymore"),
+            (skills(&[(1, 10, 11)]), "entry 2 isn't there anymore"),
+        ];
+
+        for (target, expected) in targets {
+            let mut value = target.clone();
+
+            assert_eq!(chunk_patch.apply(&mut value), Err(String::from(expected)), "{}", target);
+            assert_eq!(chunk_patch.apply_to_data(&binary(&target)), Err(format!("skills: {}", expected)), "{}", target);
+        }
+
+        // The entry it adds is already there
+        let add = ChunkPatch { chunk: String::from("skills"), format: NuccBinaryType::SkillIndexSettingParam, header: BTreeMap::new(), remove: Vec::new(), edit: Vec::new(), add: vec![json!({ "characode_index": 2 })] };
+        assert!(add.apply(&mut before.clone()).is_err());
+    }
+}
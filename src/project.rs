@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+
+use crate::NuccBinaryType;
+use crate::nucc_binary::lint::Severity;
+
+
+/// Settings shared by everyone working on a mod, kept at the root of the mod's folder
+pub const PROJECT_FILE: &str = "nuccbin.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Project {
+    /// Severity overrides by rule, either the full id (`CostumeParam/zero-price-locked`) or the rule name for every format that has it
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lint: BTreeMap<String, Severity>,
+
+    /// Limits of the rules that compare with one, by full id or rule name the same as `lint`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lint_limits: BTreeMap<String, u64>,
+
+    /// Ids reserved for this mod by format, where `"auto"` placeholders are taken from
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ids: BTreeMap<String, IdRange>
+}
+
+/// Both ends included
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct IdRange {
+    pub start: i64,
+    pub end: i64
+}
+
+impl Project {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&data))
+            .map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// The range reserved for a format, checking the project only names formats that have ids
+    pub fn id_range(&self, nucc_binary_type: NuccBinaryType) -> Result<Option<IdRange>, String> {
+        for (format, range) in &self.ids {
+            match NuccBinaryType::from_str(format).ok().filter(|nucc_binary_type| nucc_binary_type.id_field().is_some()) {
+                Some(_) if range.start > range.end => return Err(format!("The {} id range starts after it ends", format)),
+                Some(_) if range.start < 0 => return Err(format!("The {} id range starts below 0, negative ids mean none", format)),
+                Some(_) => {}
+                None => return Err(format!("{} doesn't have ids to reserve", format))
+            }
+        }
+
+        Ok(self.ids.get(&nucc_binary_type.to_string()).copied())
+    }
+
+    /// The project file in `dir` or the closest folder above it
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|dir| dir.join(PROJECT_FILE))
+            .find(|path| path.is_file())
+    }
+}
\ No newline at end of file
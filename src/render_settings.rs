@@ -0,0 +1,250 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+
+/// The contexts `RenderSettings` has a block for, with the prefix of their flat field names
+pub const CONTEXTS: [(&str, &str); 3] = [
+    ("char_select", ""),
+    ("selection", "sel"),
+    ("vs", "vs")
+];
+
+pub const SIDES: [(&str, &str); 2] = [("1p", "1P"), ("2p", "2P")];
+
+const AXES: [(&str, &str); 3] = [("x", "X"), ("y", "Y"), ("z", "Z")];
+
+/// The vectors after the known blocks, `unk_x3` to `unk_z8`
+const UNKNOWN: std::ops::RangeInclusive<u32> = 3..=8;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Mirrored {
+    pub entry: usize,
+    pub searchcode: String
+}
+
+
+/// The flat field name, e.g. `selOfsX1P` for `("sel", "Ofs", "X", "1P")` or `rot2P` for `("", "Rot", "", "2P")`
+fn flat_field(prefix: &str, name: &str, axis: &str, side: &str) -> String {
+    match prefix {
+        "" => format!("{}{}{}", name.to_lowercase(), axis, side),
+        _ => format!("{}{}{}{}", prefix, name, axis, side)
+    }
+}
+
+/// The flat fields of a side of a context, with the path of each in the grouped form
+fn side_fields(prefix: &str, side: &str) -> Vec<(String, [&'static str; 2])> {
+    let mut fields = Vec::new();
+
+    for (key, name) in [("offset", "Ofs"), ("light", "Light")] {
+        for (axis_key, axis) in AXES {
+            fields.push((flat_field(prefix, name, axis, side), [key, axis_key]));
+        }
+    }
+
+    fields.push((flat_field(prefix, "Rot", "", side), ["rotation", ""]));
+    fields
+}
+
+fn render_settings(value: &mut Value) -> impl Iterator<Item = (usize, &mut Map<String, Value>)> {
+    value.get_mut("entries").and_then(Value::as_array_mut).into_iter().flatten()
+        .enumerate()
+        .filter_map(|(index, entry)| entry.get_mut("render_settings").and_then(Value::as_object_mut).map(|settings| (index, settings)))
+}
+
+
+/// Whether a CharacterSelectParam's render settings were unpacked grouped
+pub fn is_grouped(value: &Value) -> bool {
+    value.get("entries").and_then(Value::as_array).into_iter().flatten()
+        .any(|entry| entry["render_settings"].get(CONTEXTS[0].0).is_some())
+}
+
+/// Groups the 60 floats of every entry's `render_settings` by context and side:
+/// `{"char_select": {"1p": {"offset": {"x", "y", "z"}, "rotation", "light": {"x", "y", "z"}}, "2p": ...}, "selection": ..., "vs": ..., "unk3": {"x", "y", "z"}, ...}`
+pub fn group(value: &mut Value) {
+    for (_, settings) in render_settings(value) {
+        let mut grouped = Map::new();
+
+        for (context, prefix) in CONTEXTS {
+            let mut sides = Map::new();
+
+            for (side_key, side) in SIDES {
+                let mut block = Map::new();
+
+                for (field, [key, axis]) in side_fields(prefix, side) {
+                    let float = settings.remove(&field).unwrap_or_default();
+
+                    match axis {
+                        "" => { block.insert(key.to_string(), float); }
+                        _ => {
+                            block.entry(key).or_insert_with(|| Value::Object(Map::new()))
+                                .as_object_mut().unwrap()
+                                .insert(axis.to_string(), float);
+                        }
+                    }
+                }
+
+                sides.insert(side_key.to_string(), block.into());
+            }
+
+            grouped.insert(context.to_string(), sides.into());
+        }
+
+        for unknown in UNKNOWN {
+            let vector = AXES.iter()
+                .map(|(axis_key, _)| (axis_key.to_string(), settings.remove(&format!("unk_{}{}", axis_key, unknown)).unwrap_or_default()))
+                .collect::<Map<_, _>>();
+
+            grouped.insert(format!("unk{}", unknown), vector.into());
+        }
+
+        *settings = grouped;
+    }
+}
+
+/// Puts grouped render settings back into their flat fields, so the chunk repacks to the same bytes it was unpacked from
+pub fn ungroup(value: &mut Value) -> Result<(), String> {
+    for (index, settings) in render_settings(value) {
+        let mut grouped = std::mem::take(settings);
+        let mut take = |path: &[&str]| -> Result<Value, String> {
+            let (last, parents) = path.split_last().unwrap();
+            let mut map = &mut grouped;
+
+            for parent in parents {
+                map = map.get_mut(*parent).and_then(Value::as_object_mut)
+                    .ok_or_else(|| format!("entry {}, render_settings.{} is missing", index, path.join(".")))?;
+            }
+
+            map.remove(*last).ok_or_else(|| format!("entry {}, render_settings.{} is missing", index, path.join(".")))
+        };
+
+        for (context, prefix) in CONTEXTS {
+            for (side_key, side) in SIDES {
+                for (field, [key, axis]) in side_fields(prefix, side) {
+                    let float = match axis {
+                        "" => take(&[context, side_key, key])?,
+                        _ => take(&[context, side_key, key, axis])?
+                    };
+
+                    settings.insert(field, float);
+                }
+            }
+        }
+
+        for unknown in UNKNOWN {
+            for (axis_key, _) in AXES {
+                settings.insert(format!("unk_{}{}", axis_key, unknown), take(&[&format!("unk{}", unknown), axis_key])?);
+            }
+        }
+
+        // Whatever is left is a typo that would otherwise be dropped silently
+        if let Some(path) = leftover(&grouped, "") {
+            return Err(format!("entry {}, render_settings{} isn't a render setting", index, path));
+        }
+    }
+
+    Ok(())
+}
+
+fn leftover(map: &Map<String, Value>, path: &str) -> Option<String> {
+    map.iter().find_map(|(key, value)| match value {
+        Value::Object(child) => leftover(child, &format!("{}.{}", path, key)),
+        _ => Some(format!("{}.{}", path, key))
+    })
+}
+
+/// Sets the 2P settings of every context to the 1P ones seen from the other side:
+/// the x of the offset and the light and the rotation are negated, floats written as hex are copied as they are.
+/// Which components the game mirrors is a guess, it hasn't been compared with the game's own 2P settings.
+/// Only the entries with one of `searchcodes` are changed, every entry when it's empty.
+pub fn mirror_1p_to_2p(value: &mut Value, searchcodes: &[String]) -> Vec<Mirrored> {
+    let mut mirrored = Vec::new();
+
+    for (index, entry) in value.get_mut("entries").and_then(Value::as_array_mut).into_iter().flatten().enumerate() {
+        let searchcode = entry["searchcode"].as_str().unwrap_or_default().to_string();
+
+        if !searchcodes.is_empty() && !searchcodes.contains(&searchcode) {
+            continue;
+        }
+
+        let settings = match entry.get_mut("render_settings").and_then(Value::as_object_mut) {
+            Some(settings) => settings,
+            None => continue
+        };
+
+        for (_, prefix) in CONTEXTS {
+            for ((field, [key, axis]), (field_2p, _)) in side_fields(prefix, "1P").into_iter().zip(side_fields(prefix, "2P")) {
+                let float = settings.get(&field).cloned().unwrap_or_default();
+
+                let float = match (key, axis, float.as_f64()) {
+                    // Not negating 0 keeps -0 out of the file
+                    ("rotation", _, Some(float)) | (_, "x", Some(float)) if float != 0.0 => (-float).into(),
+                    _ => float
+                };
+
+                settings.insert(field_2p, float);
+            }
+        }
+
+        mirrored.push(Mirrored { entry: index, searchcode });
+    }
+
+    mirrored
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Every flat field of the render settings, with the value `float` gives its position
+    fn flat(float: impl Fn(usize) -> Value) -> Map<String, Value> {
+        let fields = CONTEXTS.iter()
+            .flat_map(|(_, prefix)| SIDES.iter().flat_map(move |(_, side)| side_fields(prefix, side)))
+            .map(|(field, _)| field)
+            .chain(UNKNOWN.flat_map(|unknown| AXES.map(|(axis_key, _)| format!("unk_{}{}", axis_key, unknown))));
+
+        fields.enumerate().map(|(index, field)| (field, float(index))).collect()
+    }
+
+    #[test]
+    fn group_round_trip_test() {
+        let cases: [fn(usize) -> Value; 4] = [
+            |index| json!(index as f64 + 0.5),
+            |index| json!(-(index as f64) / 3.0),
+            |index| if index % 2 == 0 { json!("0x7FC00000") } else { json!(-0.0) },
+            |_| json!(0.0),
+        ];
+
+        for float in cases {
+            let settings = flat(float);
+            assert_eq!(settings.len(), 60);
+
+            let mut value = json!({ "entries": [{ "searchcode": "1nrt01", "render_settings": settings }] });
+            let flat_value = value.clone();
+
+            group(&mut value);
+            assert!(is_grouped(&value));
+            assert_eq!(value["entries"][0]["render_settings"]["selection"]["2p"]["offset"]["y"], flat_value["entries"][0]["render_settings"]["selOfsY2P"]);
+            assert_eq!(value["entries"][0]["render_settings"]["char_select"]["1p"]["rotation"], flat_value["entries"][0]["render_settings"]["rot1P"]);
+            assert_eq!(value["entries"][0]["render_settings"]["unk8"]["z"], flat_value["entries"][0]["render_settings"]["unk_z8"]);
+
+            ungroup(&mut value).unwrap();
+            assert_eq!(value, flat_value);
+        }
+    }
+
+    #[test]
+    fn ungroup_error_test() {
+        let mut value = json!({ "entries": [{ "render_settings": flat(|index| json!(index)) }] });
+        group(&mut value);
+
+        let mut missing = value.clone();
+        missing["entries"][0]["render_settings"]["vs"]["1p"]["light"].as_object_mut().unwrap().remove("x");
+        assert!(ungroup(&mut missing).is_err());
+
+        let mut typo = value.clone();
+        typo["entries"][0]["render_settings"]["vs"]["1p"]["ligth"] = json!({ "x": 1.0 });
+        assert!(ungroup(&mut typo).is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,934 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+use xfbin::{read_xfbin, write_xfbin};
+use xfbin::nucc::*;
+
+use crate::NuccBinaryType;
+use crate::characodes::{edit_characodes, CharacodeEdit, Characodes};
+use crate::chunks::{edit_chunk, find_nucc_binary_type, find_xfbins, load_chunks};
+use crate::diff::{entries, entry_key};
+use crate::ids::{assign_ids, Assigned, Ids};
+use crate::nucc_binary::named::is_set;
+use crate::patch::set_path;
+use crate::project::Project;
+
+
+/// The unpacked json of a chunk that can get new entries, and the xfbin it's written back to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub file: PathBuf,
+    pub chunk: String,
+    pub format: NuccBinaryType,
+    pub value: Value,
+    pub changed: bool
+}
+
+/// A new playable character. Every value the spec leaves out is copied from the `base` character's entries.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CharacterSpec {
+    pub characode: String,
+    pub base: String,
+
+    /// Where the character goes on the character select screen
+    pub slot: Slot,
+
+    pub costumes: Vec<CostumeSpec>,
+
+    /// Gives the character its own DictionaryCharacterParam page, otherwise it links to the base character's
+    #[serde(default)]
+    pub dictionary_link: Option<String>,
+
+    /// The character's name, shown on the select screen and icons, otherwise it keeps the base character's
+    #[serde(default)]
+    pub names: Option<NameSpec>,
+
+    /// Icons of every costume, otherwise they're the base costume's
+    #[serde(default)]
+    pub icons: IconSpec,
+
+    /// MessageInfo entries, added to every MessageInfo chunk (one per language)
+    #[serde(default)]
+    pub messages: Vec<Map<String, Value>>,
+
+    /// Field values for every entry added to a format, by format and field path, e.g. `{"SkillIndexSettingParam": {"first_jutsu_skl_index": 3}}`
+    #[serde(default)]
+    pub fields: BTreeMap<String, Map<String, Value>>
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Slot {
+    pub page_index: u32,
+    pub slot_index: u32
+}
+
+/// A name message: its id is what the other formats refer to, and MessageInfo finds it by the id's crc32
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NameSpec {
+    /// Used for `CharacterSelectParam.char_name` and `PlayerIcon.name_id`
+    pub id: String,
+
+    /// Crc32 of the id, as hex
+    pub crc32: String,
+
+    /// The MessageInfo entry's `text1`
+    pub text: String
+}
+
+/// PlayerIcon ids, the ones left out are copied
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct IconSpec {
+    #[serde(default)]
+    pub icon_id: Option<String>,
+
+    #[serde(default)]
+    pub awakening_icon_id: Option<String>,
+
+    #[serde(default)]
+    pub sub_ninjutsu_icon_id: Option<String>
+}
+
+/// A costume of a new character, each one gets its own PlayerSettingParam preset
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CostumeSpec {
+    pub searchcode: String,
+
+    #[serde(default)]
+    pub costume_link: Option<String>,
+
+    #[serde(default)]
+    pub costume_name: Option<String>,
+
+    /// Icons of this costume, replacing the ones of `CharacterSpec::icons`
+    #[serde(default)]
+    pub icons: IconSpec,
+
+    /// Field values for this costume's entries only, like `CharacterSpec::fields`
+    #[serde(default)]
+    pub fields: BTreeMap<String, Map<String, Value>>
+}
+
+/// A new costume for an existing character
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NewCostumeSpec {
+    /// Searchcode of the costume every value the spec leaves out is copied from
+    pub base: String,
+    pub searchcode: String,
+
+    /// The costume's duel player param model, after the character's last one by default
+    #[serde(default)]
+    pub costume_index: Option<i64>,
+
+    /// After the last costume of the base's select screen slot by default
+    #[serde(default)]
+    pub costume_slot_index: Option<u64>,
+
+    #[serde(default)]
+    pub costume_link: Option<String>,
+
+    #[serde(default)]
+    pub costume_name: Option<String>,
+
+    #[serde(default)]
+    pub color_index: Option<u32>,
+
+    #[serde(default)]
+    pub price: Option<u32>,
+
+    #[serde(default)]
+    pub messages: Vec<Map<String, Value>>,
+
+    #[serde(default)]
+    pub fields: BTreeMap<String, Map<String, Value>>
+}
+
+/// A new accessory. Every value the spec leaves out is copied from the `base` accessory.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AccessorySpec {
+    pub accessory: String,
+    pub base: String,
+
+    /// One of `ACCESSORY_SLOTS`, the base's by default
+    #[serde(default)]
+    pub slot: Option<String>,
+
+    /// The bone every BodAcc entry attaches to, the base's by default
+    #[serde(default)]
+    pub bone: Option<String>,
+
+    #[serde(default)]
+    pub price: Option<u32>,
+
+    /// Model codes that can't wear accessories in this slot, their AccessoryExceptionParam entry gets `exception_flag`
+    #[serde(default)]
+    pub exceptions: Vec<String>,
+
+    /// One of `EXCEPTION_FLAGS`, the one named like the slot by default
+    #[serde(default)]
+    pub exception_flag: Option<String>,
+
+    #[serde(default)]
+    pub messages: Vec<Map<String, Value>>,
+
+    #[serde(default)]
+    pub fields: BTreeMap<String, Map<String, Value>>
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Changes {
+    pub added: Vec<Added>,
+
+    /// Existing entries that were changed, e.g. the AccessoryExceptionParam entry of a model that can't wear a new accessory
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub edited: Vec<Added>,
+
+    pub ids: Vec<Assigned>,
+
+    /// Formats that weren't found, or where the base has no entry to copy
+    pub skipped: Vec<String>,
+
+    /// Likely mistakes that didn't stop the change
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Added {
+    pub file: String,
+    pub chunk: String,
+    pub format: NuccBinaryType,
+    pub entry: String
+}
+
+
+/// The formats `add_character` adds entries to
+pub const CHARACTER_FORMATS: &[NuccBinaryType] = &[
+    NuccBinaryType::Characode,
+    NuccBinaryType::PlayerSettingParam,
+    NuccBinaryType::CharacterSelectParam,
+    NuccBinaryType::CostumeParam,
+    NuccBinaryType::PlayerIcon,
+    NuccBinaryType::DictionaryCharacterParam,
+    NuccBinaryType::SkillIndexSettingParam,
+    NuccBinaryType::SupportActionParam,
+    NuccBinaryType::MessageInfo,
+];
+
+/// The formats `add_costume` adds entries to
+pub const COSTUME_FORMATS: &[NuccBinaryType] = &[
+    NuccBinaryType::PlayerSettingParam,
+    NuccBinaryType::CharacterSelectParam,
+    NuccBinaryType::CostumeParam,
+    NuccBinaryType::CostumeBreakParam,
+    NuccBinaryType::PlayerIcon,
+    NuccBinaryType::MessageInfo,
+];
+
+/// The formats `add_accessory` adds entries to
+pub const ACCESSORY_FORMATS: &[NuccBinaryType] = &[
+    NuccBinaryType::AccessoriesParam,
+    NuccBinaryType::AccessoryParam,
+    NuccBinaryType::AccessoryExceptionParam,
+    NuccBinaryType::BodAcc,
+    NuccBinaryType::MessageInfo,
+];
+
+/// Accessory slots, as their AccessoryParam flag
+pub const ACCESSORY_SLOTS: &[&str] = &["head", "face", "back", "waist", "arms"];
+
+/// The AccessoryExceptionParam flags. Which one goes with a slot is only taken for granted when they have the same name,
+/// `back` could be `back_a` and `shoulders` and `head_a` have no slot, so those have to be given in the spec.
+pub const EXCEPTION_FLAGS: &[&str] = &["head", "face", "shoulders", "back_a", "arms", "waist", "head_a"];
+
+
+/// The chunks of the given formats in every xfbin of a folder
+pub fn load_tables(folder: &Path, formats: &[NuccBinaryType]) -> Result<Vec<Table>, String> {
+    let mut tables = Vec::new();
+
+    for file in find_xfbins(folder) {
+        for chunk in load_chunks(&file)? {
+            if let Some(value) = chunk.json().filter(|_| formats.contains(&chunk.nucc_binary_type)) {
+                tables.push(Table { file: file.clone(), chunk: chunk.name, format: chunk.nucc_binary_type, value, changed: false });
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
+/// Writes the changed tables back to their xfbins, returning the files written
+pub fn save_tables(tables: &[Table]) -> Result<Vec<PathBuf>, String> {
+    let mut files = tables.iter().filter(|table| table.changed).map(|table| table.file.clone()).collect::<Vec<_>>();
+    files.dedup();
+
+    // Everything is converted first, so nothing is written when one of the chunks fails
+    let mut xfbins = Vec::new();
+
+    for file in &files {
+        let mut xfbin = read_xfbin(file).map_err(|err| format!("Couldn't read {}: {}", file.display(), err))?;
+
+        for page in &mut xfbin.pages {
+            for chunk in &mut page.structs {
+                if let Some(nucc_binary) = chunk.downcast_mut::<NuccBinary>() {
+                    let table = tables.iter().find(|table| table.changed && table.file == *file && table.chunk == nucc_binary.struct_info.chunk_name);
+
+                    if let (Some(table), Some(nucc_binary_type)) = (table, find_nucc_binary_type(&nucc_binary.struct_info.filepath)) {
+                        let (data, _) = edit_chunk(nucc_binary_type, &nucc_binary.data, |value| {
+                            *value = table.value.clone();
+                            Ok(())
+                        }).map_err(|err| format!("{} / {}: {}", file.display(), table.chunk, err))?;
+
+                        nucc_binary.data = data;
+                    }
+                }
+            }
+        }
+
+        xfbins.push((file, xfbin));
+    }
+
+    for (file, xfbin) in xfbins {
+        write_xfbin(xfbin, file).map_err(|err| format!("Couldn't write {}: {}", file.display(), err))?;
+    }
+
+    Ok(files)
+}
+
+/// Adds a character to every table, linking the new entries to each other: the characode index, the player setting ids
+/// (taken like `"auto"` placeholders, from the project's reserved range) and the searchcodes.
+/// Nothing is changed when it fails.
+pub fn add_character(spec: &CharacterSpec, tables: &mut [Table], ids: &mut Ids, project: &Project) -> Result<Changes, String> {
+    let mut edit = Edit::new(tables.to_vec(), ids.clone(), project);
+
+    check_fields(&spec.fields, CHARACTER_FORMATS)?;
+
+    if spec.costumes.is_empty() {
+        return Err(String::from("The character needs at least one costume"));
+    }
+
+    for costume in &spec.costumes {
+        check_fields(&costume.fields, CHARACTER_FORMATS)?;
+
+        if edit.find(NuccBinaryType::PlayerSettingParam, |entry| entry["searchcode"] == costume.searchcode.as_str()).is_some() {
+            return Err(format!("The searchcode {} is already used", costume.searchcode));
+        }
+    }
+
+    // The characode goes at the end, so no other index changes
+    let characode_table = edit.single(NuccBinaryType::Characode)?;
+    let characodes = Characodes::from_json(&edit.tables[characode_table].value).ok_or("The Characode chunk isn't a Characode table")?;
+
+    let base_index = characodes.index(&spec.base).ok_or_else(|| format!("There's no characode {:?}", spec.base))?;
+    let characode_index = characodes.names.len() as u64 + 1;
+
+    edit_characodes(&mut edit.tables[characode_table].value, &CharacodeEdit::Insert { characode: spec.characode.clone(), position: characode_index })?;
+    edit.tables[characode_table].changed = true;
+    edit.added.push((characode_table, characode_index as usize - 1));
+
+    let is_base = |entry: &Value| entry["characode_index"].as_u64() == Some(base_index);
+
+    // The base's presets in order, the new costumes copy the one at the same position, or the last one
+    let (psp_table, base_presets) = edit.find_all(NuccBinaryType::PlayerSettingParam, is_base)
+        .ok_or_else(|| format!("{} has no PlayerSettingParam entries to copy", spec.base))?;
+
+    let base_preset = |index: usize| &base_presets[index.min(base_presets.len() - 1)];
+
+    // Player setting ids of the copied presets point to the new ones instead
+    let mut relink = HashMap::new();
+
+    for (index, costume) in spec.costumes.iter().enumerate().rev() {
+        if let Some(id) = base_preset(index)["player_setting_id"].as_i64() {
+            relink.insert(id, placeholder(&costume.searchcode));
+        }
+    }
+
+    for (index, costume) in spec.costumes.iter().enumerate() {
+        let mut preset = base_preset(index).clone();
+        preset["player_setting_id"] = placeholder(&costume.searchcode);
+        preset["characode_index"] = characode_index.into();
+        preset["main_characode_index"] = characode_index.into();
+        preset["searchcode"] = costume.searchcode.as_str().into();
+
+        if let Some(main) = preset["main_player_setting_id"].as_i64().and_then(|id| relink.get(&id)) {
+            preset["main_player_setting_id"] = main.clone();
+        }
+
+        edit.push(psp_table, preset, &[&spec.fields, &costume.fields])?;
+    }
+
+    edit.assign_ids(psp_table)?;
+
+    // Select screen slots, one per costume
+    match edit.tables_of(NuccBinaryType::CharacterSelectParam).first() {
+        Some(&csp_table) => {
+            for (index, costume) in spec.costumes.iter().enumerate() {
+                let mut slot = costume_base(&edit, NuccBinaryType::CharacterSelectParam, "searchcode", &base_presets, index)
+                    .ok_or_else(|| format!("{} has no CharacterSelectParam entry to copy", spec.base))?.1;
+
+                slot["searchcode"] = costume.searchcode.as_str().into();
+                slot["page_index"] = spec.slot.page_index.into();
+                slot["slot_index"] = spec.slot.slot_index.into();
+                slot["costume_slot_index"] = index.into();
+
+                if let Some(costume_name) = &costume.costume_name {
+                    slot["costume_name"] = costume_name.as_str().into();
+                }
+
+                if let Some(dictionary_link) = &spec.dictionary_link {
+                    slot["dictionary_link"] = dictionary_link.as_str().into();
+                }
+
+                if let Some(names) = &spec.names {
+                    slot["char_name"] = names.id.as_str().into();
+                }
+
+                let taken = |entry: &Value| ["page_index", "slot_index", "costume_slot_index"].iter().all(|field| entry[*field] == slot[*field]);
+
+                if let Some((_, other)) = edit.find(NuccBinaryType::CharacterSelectParam, taken) {
+                    return Err(format!("Page {}, slot {}, costume slot {} is already taken by {}", slot["page_index"], slot["slot_index"], slot["costume_slot_index"], other["searchcode"]));
+                }
+
+                edit.push(csp_table, slot, &[&spec.fields, &costume.fields])?;
+            }
+        }
+        None => edit.skip(NuccBinaryType::CharacterSelectParam, "isn't in the folder")
+    }
+
+    // Costumes point to their preset
+    match edit.find_all(NuccBinaryType::CostumeParam, |entry| base_presets.iter().any(|preset| preset["player_setting_id"] == entry["player_setting_id"])) {
+        Some((costume_table, _)) => {
+            for (index, costume) in spec.costumes.iter().enumerate() {
+                let mut costume_entry = costume_base(&edit, NuccBinaryType::CostumeParam, "player_setting_id", &base_presets, index)
+                    .ok_or_else(|| format!("{} has no CostumeParam entry to copy", spec.base))?.1;
+
+                costume_entry["index"] = placeholder(&costume.searchcode);
+                costume_entry["player_setting_id"] = placeholder(&costume.searchcode);
+
+                if let Some(costume_link) = &costume.costume_link {
+                    costume_entry["costume_link"] = costume_link.as_str().into();
+                }
+
+                if let Some(costume_name) = &costume.costume_name {
+                    costume_entry["costume_name"] = costume_name.as_str().into();
+                }
+
+                edit.push(costume_table, costume_entry, &[&spec.fields, &costume.fields])?;
+            }
+
+            edit.assign_ids(costume_table)?;
+        }
+        None => edit.skip(NuccBinaryType::CostumeParam, "has no entry for the base's presets")
+    }
+
+    // Icons are per costume too
+    match edit.find_all(NuccBinaryType::PlayerIcon, is_base) {
+        Some((icon_table, base_icons)) => {
+            for (index, costume) in spec.costumes.iter().enumerate() {
+                let mut icon = base_icons[index.min(base_icons.len() - 1)].clone();
+                icon["characode_index"] = characode_index.into();
+
+                let ids = [
+                    ("icon_id", &costume.icons.icon_id, &spec.icons.icon_id),
+                    ("awakening_icon_id", &costume.icons.awakening_icon_id, &spec.icons.awakening_icon_id),
+                    ("sub_ninjutsu_icon_id", &costume.icons.sub_ninjutsu_icon_id, &spec.icons.sub_ninjutsu_icon_id)
+                ];
+
+                for (field, costume_id, character_id) in ids {
+                    if let Some(id) = costume_id.as_ref().or(character_id.as_ref()) {
+                        icon[field] = id.as_str().into();
+                    }
+                }
+
+                if let Some(names) = &spec.names {
+                    icon["name_id"] = names.id.as_str().into();
+                }
+
+                edit.push(icon_table, icon, &[&spec.fields, &costume.fields])?;
+            }
+        }
+        None => edit.skip(NuccBinaryType::PlayerIcon, "has no entry for the base")
+    }
+
+    if let Some(dictionary_link) = &spec.dictionary_link {
+        let base_link = edit.find(NuccBinaryType::CharacterSelectParam, |entry| entry["searchcode"] == base_presets[0]["searchcode"])
+            .map(|(_, entry)| entry["dictionary_link"].clone());
+
+        match base_link.and_then(|base_link| edit.find(NuccBinaryType::DictionaryCharacterParam, |entry| entry["dictionary_link"] == base_link)) {
+            Some((dictionary_table, mut page)) => {
+                page["dictionary_link"] = dictionary_link.as_str().into();
+                edit.push(dictionary_table, page, &[&spec.fields])?;
+            }
+            None => edit.skip(NuccBinaryType::DictionaryCharacterParam, "has no page for the base")
+        }
+    }
+
+    for format in [NuccBinaryType::SkillIndexSettingParam, NuccBinaryType::SupportActionParam] {
+        match edit.find(format, is_base) {
+            Some((table, mut entry)) => {
+                entry["characode_index"] = characode_index.into();
+                edit.push(table, entry, &[&spec.fields])?;
+            }
+            None => edit.skip(format, "has no entry for the base")
+        }
+    }
+
+    let mut messages = spec.messages.clone();
+
+    if let Some(names) = &spec.names {
+        let mut message = Map::new();
+        message.insert(String::from("crc32"), names.crc32.as_str().into());
+        message.insert(String::from("text1"), names.text.as_str().into());
+        messages.insert(0, message);
+    }
+
+    add_messages(&mut edit, &messages)?;
+
+    Ok(edit.finish(tables, ids))
+}
+
+/// Adds a costume to an existing character, copying every value the spec leaves out from the `base` costume.
+/// The new preset gets a player setting id like an `"auto:<searchcode>"` placeholder and points to the character's main preset.
+pub fn add_costume(spec: &NewCostumeSpec, tables: &mut [Table], ids: &mut Ids, project: &Project) -> Result<Changes, String> {
+    let mut edit = Edit::new(tables.to_vec(), ids.clone(), project);
+
+    check_fields(&spec.fields, COSTUME_FORMATS)?;
+
+    if edit.find(NuccBinaryType::PlayerSettingParam, |entry| entry["searchcode"] == spec.searchcode.as_str()).is_some() {
+        return Err(format!("The searchcode {} is already used", spec.searchcode));
+    }
+
+    let (psp_table, base) = edit.find(NuccBinaryType::PlayerSettingParam, |entry| entry["searchcode"] == spec.base.as_str())
+        .ok_or_else(|| format!("There's no preset with the searchcode {}", spec.base))?;
+
+    let characode_index = base["characode_index"].clone();
+    let base_costume_index = base["duel_player_param_model_index"].clone();
+    let is_character = |entry: &Value| entry["characode_index"] == characode_index;
+
+    // A new duel player param costume by default, after the character's last one
+    let costume_index = match spec.costume_index {
+        Some(costume_index) => Value::from(costume_index),
+        None => {
+            let (_, presets) = edit.find_all(NuccBinaryType::PlayerSettingParam, is_character).unwrap();
+            let last = presets.iter().filter_map(|preset| preset["duel_player_param_model_index"].as_i64()).max().unwrap_or(-1);
+            Value::from(last + 1)
+        }
+    };
+
+    let mut preset = base.clone();
+    preset["player_setting_id"] = placeholder(&spec.searchcode);
+    preset["searchcode"] = spec.searchcode.as_str().into();
+    preset["duel_player_param_model_index"] = costume_index.clone();
+
+    // The base is either the main preset or points to it
+    if preset["main_player_setting_id"].as_i64() == Some(-1) {
+        preset["main_player_setting_id"] = base["player_setting_id"].clone();
+    }
+
+    edit.push(psp_table, preset, &[&spec.fields])?;
+    edit.assign_ids(psp_table)?;
+
+    match edit.find(NuccBinaryType::CharacterSelectParam, |entry| entry["searchcode"] == spec.base.as_str()) {
+        Some((csp_table, mut slot)) => {
+            let same_slot = |entry: &Value| entry["page_index"] == slot["page_index"] && entry["slot_index"] == slot["slot_index"];
+
+            // After the slot's last costume by default
+            let costume_slot_index = match spec.costume_slot_index {
+                Some(costume_slot_index) => costume_slot_index,
+                None => edit.find_all(NuccBinaryType::CharacterSelectParam, same_slot).unwrap().1.iter()
+                    .filter_map(|entry| entry["costume_slot_index"].as_u64())
+                    .max()
+                    .map_or(0, |last| last + 1)
+            };
+
+            if let Some((_, other)) = edit.find(NuccBinaryType::CharacterSelectParam, |entry| same_slot(entry) && entry["costume_slot_index"] == costume_slot_index) {
+                return Err(format!("Costume slot {} of page {}, slot {} is already taken by {}", costume_slot_index, slot["page_index"], slot["slot_index"], other["searchcode"]));
+            }
+
+            slot["searchcode"] = spec.searchcode.as_str().into();
+            slot["costume_slot_index"] = costume_slot_index.into();
+            slot["duel_player_param_model_index"] = costume_index.clone();
+
+            if let Some(costume_name) = &spec.costume_name {
+                slot["costume_name"] = costume_name.as_str().into();
+            }
+
+            edit.push(csp_table, slot, &[&spec.fields])?;
+        }
+        None => edit.skip(NuccBinaryType::CharacterSelectParam, "has no slot for the base")
+    }
+
+    match edit.find(NuccBinaryType::CostumeParam, |entry| entry["player_setting_id"] == base["player_setting_id"]) {
+        Some((costume_table, mut costume)) => {
+            costume["index"] = placeholder(&spec.searchcode);
+            costume["player_setting_id"] = placeholder(&spec.searchcode);
+
+            let named = [
+                ("costume_link", spec.costume_link.clone().map(Value::from)),
+                ("costume_name", spec.costume_name.clone().map(Value::from)),
+                ("color_index", spec.color_index.map(Value::from)),
+                ("price", spec.price.map(Value::from))
+            ];
+
+            for (field, value) in named {
+                if let Some(value) = value {
+                    costume[field] = value;
+                }
+            }
+
+            edit.push(costume_table, costume, &[&spec.fields])?;
+            edit.assign_ids(costume_table)?;
+        }
+        None => edit.skip(NuccBinaryType::CostumeParam, "has no entry for the base")
+    }
+
+    // Both are keyed by the character and its duel player param costume
+    for (format, field) in [(NuccBinaryType::CostumeBreakParam, "costume_index"), (NuccBinaryType::PlayerIcon, "duel_player_param_costume_index")] {
+        match edit.find(format, |entry| is_character(entry) && entry[field] == base_costume_index) {
+            Some((table, mut entry)) => {
+                entry[field] = costume_index.clone();
+                edit.push(table, entry, &[&spec.fields])?;
+            }
+            None => edit.skip(format, "has no entry for the base")
+        }
+    }
+
+    add_messages(&mut edit, &spec.messages)?;
+
+    Ok(edit.finish(tables, ids))
+}
+
+/// Adds an accessory to the shop, its slot, the models that can't wear it and where it attaches to the body.
+/// The slot has to be a single AccessoryParam flag, and a bone no other accessory of the slot attaches to is reported as a warning.
+pub fn add_accessory(spec: &AccessorySpec, tables: &mut [Table], ids: &mut Ids, project: &Project) -> Result<Changes, String> {
+    let mut edit = Edit::new(tables.to_vec(), ids.clone(), project);
+
+    check_fields(&spec.fields, ACCESSORY_FORMATS)?;
+
+    let name = spec.accessory.as_str();
+    let is_base = |entry: &Value| entry["accessory"] == spec.base.as_str();
+
+    for format in [NuccBinaryType::AccessoriesParam, NuccBinaryType::AccessoryParam, NuccBinaryType::BodAcc] {
+        if edit.find(format, |entry| entry["accessory"] == name).is_some() {
+            return Err(format!("{} already has an accessory {}", format, name));
+        }
+    }
+
+    let requested_slot = spec.slot.as_deref()
+        .map(|slot| ACCESSORY_SLOTS.iter().find(|name| **name == slot).copied()
+            .ok_or_else(|| format!("Unknown slot {}, expected one of {}", slot, ACCESSORY_SLOTS.join(", "))))
+        .transpose()?;
+
+    // The slot, which decides the exception flag and the bones to compare with
+    let slot = match edit.find(NuccBinaryType::AccessoryParam, is_base) {
+        Some((table, mut entry)) => {
+            entry["accessory"] = name.into();
+
+            if let Some(flag) = requested_slot {
+                for other in ACCESSORY_SLOTS {
+                    entry[*other] = (*other == flag).into();
+                }
+            }
+
+            edit.push(table, entry, &[&spec.fields])?;
+
+            let entry = entries(&edit.tables[table].value).unwrap().last().unwrap();
+            let flags = ACCESSORY_SLOTS.iter().copied().filter(|flag| is_set(&entry[*flag])).collect::<Vec<_>>();
+
+            match flags.as_slice() {
+                [slot] => Some(*slot),
+                [] => return Err(format!("{} isn't in any slot", name)),
+                _ => return Err(format!("{} is in more than one slot: {}", name, flags.join(", ")))
+            }
+        }
+        None => {
+            edit.skip(NuccBinaryType::AccessoryParam, "has no entry for the base");
+            requested_slot
+        }
+    };
+
+    match edit.find(NuccBinaryType::AccessoriesParam, is_base) {
+        Some((table, mut entry)) => {
+            entry["accessory"] = name.into();
+            entry["index"] = placeholder(name);
+
+            if let Some(price) = spec.price {
+                entry["price"] = price.into();
+            }
+
+            edit.push(table, entry, &[&spec.fields])?;
+            edit.assign_ids(table)?;
+        }
+        None => edit.skip(NuccBinaryType::AccessoriesParam, "has no entry for the base")
+    }
+
+    match edit.find_all(NuccBinaryType::BodAcc, is_base) {
+        Some((table, attachments)) => {
+            for mut attachment in attachments {
+                attachment["accessory"] = name.into();
+
+                if let Some(bone) = &spec.bone {
+                    attachment["bone_name"] = bone.as_str().into();
+                }
+
+                edit.push(table, attachment, &[&spec.fields])?;
+            }
+
+            if let Some(flag) = slot {
+                check_bones(&mut edit, table, name, flag);
+            }
+        }
+        None => edit.skip(NuccBinaryType::BodAcc, "has no entry for the base")
+    }
+
+    if !spec.exceptions.is_empty() {
+        let exception_flag = match &spec.exception_flag {
+            Some(flag) => EXCEPTION_FLAGS.iter().find(|name| *name == flag).copied()
+                .ok_or_else(|| format!("Unknown exception flag {}, expected one of {}", flag, EXCEPTION_FLAGS.join(", ")))?,
+            None => {
+                let slot = slot.ok_or_else(|| format!("The slot of {} isn't known, give it or the exception_flag in the spec to add exceptions", name))?;
+
+                EXCEPTION_FLAGS.iter().find(|name| **name == slot).copied()
+                    .ok_or_else(|| format!("No exception flag is known for the {} slot, give one of {} as the exception_flag", slot, EXCEPTION_FLAGS.join(", ")))?
+            }
+        };
+        let table = edit.single(NuccBinaryType::AccessoryExceptionParam)?;
+
+        for modelcode in &spec.exceptions {
+            let existing = entries(&edit.tables[table].value).unwrap().iter().position(|entry| entry["modelcode"] == modelcode.as_str());
+
+            match existing {
+                Some(index) => {
+                    edit.tables[table].value["entries"][index][exception_flag] = true.into();
+                    edit.tables[table].changed = true;
+                    edit.edited.push((table, index));
+                }
+                None => {
+                    let mut entry = entries(&edit.tables[table].value).unwrap().first().cloned().ok_or("AccessoryExceptionParam has no entry to copy")?;
+
+                    // Named flags are written as booleans, the unknown ones as numbers
+                    for (_, flag) in entry.as_object_mut().unwrap().iter_mut() {
+                        match flag {
+                            Value::Bool(_) => *flag = false.into(),
+                            Value::Number(_) => *flag = 0.into(),
+                            _ => {}
+                        }
+                    }
+
+                    entry["modelcode"] = modelcode.as_str().into();
+                    entry[exception_flag] = true.into();
+
+                    edit.push(table, entry, &[&spec.fields])?;
+                }
+            }
+        }
+    }
+
+    add_messages(&mut edit, &spec.messages)?;
+
+    Ok(edit.finish(tables, ids))
+}
+
+/// Warns when the new accessory's bones aren't used by any other accessory of its slot
+fn check_bones(edit: &mut Edit, table: usize, name: &str, flag: &str) {
+    let in_slot = edit.tables_of(NuccBinaryType::AccessoryParam).into_iter()
+        .flat_map(|table| entries(&edit.tables[table].value).unwrap().clone())
+        .filter(|entry| entry["accessory"] != name && is_set(&entry[flag]))
+        .map(|entry| entry["accessory"].clone())
+        .collect::<Vec<_>>();
+
+    let attachments = entries(&edit.tables[table].value).unwrap();
+
+    let bones = attachments.iter()
+        .filter(|entry| in_slot.contains(&entry["accessory"]))
+        .filter_map(|entry| entry["bone_name"].as_str())
+        .collect::<BTreeSet<_>>();
+
+    let warnings = attachments.iter()
+        .filter(|entry| entry["accessory"] == name)
+        .filter_map(|entry| entry["bone_name"].as_str())
+        .filter(|bone| !bones.is_empty() && !bones.contains(bone))
+        .map(|bone| format!("No other {} accessory attaches to {}, they use {}", flag, bone, bones.iter().copied().collect::<Vec<_>>().join(", ")))
+        .collect::<BTreeSet<_>>();
+
+    edit.warnings.extend(warnings);
+}
+
+/// Adds the messages to every MessageInfo chunk
+fn add_messages(edit: &mut Edit, messages: &[Map<String, Value>]) -> Result<(), String> {
+    let message_tables = edit.tables_of(NuccBinaryType::MessageInfo);
+
+    if !messages.is_empty() && message_tables.is_empty() {
+        edit.skip(NuccBinaryType::MessageInfo, "isn't in the folder");
+    }
+
+    for table in message_tables {
+        for message in messages {
+            add_message(edit, table, message)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A new MessageInfo entry, the fields the spec leaves out are copied from the chunk's first message with empty texts
+fn add_message(edit: &mut Edit, table: usize, message: &Map<String, Value>) -> Result<(), String> {
+    let crc32 = message.get("crc32").ok_or("Every message needs a crc32")?;
+
+    if entries(&edit.tables[table].value).unwrap().iter().any(|entry| entry["crc32"] == *crc32) {
+        return Err(format!("The message {} already exists in {}", crc32, edit.tables[table].chunk));
+    }
+
+    let mut entry = entries(&edit.tables[table].value).unwrap().first().cloned().ok_or("MessageInfo has no message to copy")?;
+
+    for text in ["text1", "text2", "text3"] {
+        entry[text] = "".into();
+    }
+
+    let mut fields = BTreeMap::new();
+    fields.insert(NuccBinaryType::MessageInfo.to_string(), message.clone());
+
+    edit.push(table, entry, &[&fields])
+}
+
+/// The entry copied for the costume at `index`: the one of its base preset, or of the closest preset before it that has one.
+/// Entries are matched to the presets by `field`, which both have.
+fn costume_base(edit: &Edit, format: NuccBinaryType, field: &str, base_presets: &[Value], index: usize) -> Option<(usize, Value)> {
+    base_presets[..=index.min(base_presets.len() - 1)].iter()
+        .rev()
+        .find_map(|preset| edit.find(format, |entry| entry[field] == preset[field]))
+}
+
+fn placeholder(name: &str) -> Value {
+    format!("auto:{}", name).into()
+}
+
+/// Only formats the new entries are added to can be given fields
+fn check_fields(fields: &BTreeMap<String, Map<String, Value>>, formats: &[NuccBinaryType]) -> Result<(), String> {
+    for format in fields.keys() {
+        match NuccBinaryType::from_str(format) {
+            Ok(nucc_binary_type) if formats.contains(&nucc_binary_type) => {}
+            _ => return Err(format!("Can't set fields of {}, entries are only added to {}", format,
+                formats.iter().map(|format| format.to_string()).collect::<Vec<_>>().join(", ")))
+        }
+    }
+
+    Ok(())
+}
+
+
+/// Copies of the tables being edited, so nothing changes when a step fails
+struct Edit<'a> {
+    tables: Vec<Table>,
+    ids: Ids,
+    project: &'a Project,
+
+    /// Table and entry index of every new entry
+    added: Vec<(usize, usize)>,
+    edited: Vec<(usize, usize)>,
+    assigned: Vec<Assigned>,
+    skipped: Vec<String>,
+    warnings: Vec<String>
+}
+
+impl<'a> Edit<'a> {
+    fn new(tables: Vec<Table>, ids: Ids, project: &'a Project) -> Self {
+        Self { tables, ids, project, added: Vec::new(), edited: Vec::new(), assigned: Vec::new(), skipped: Vec::new(), warnings: Vec::new() }
+    }
+
+    fn tables_of(&self, format: NuccBinaryType) -> Vec<usize> {
+        (0..self.tables.len()).filter(|index| self.tables[*index].format == format).collect()
+    }
+
+    fn single(&self, format: NuccBinaryType) -> Result<usize, String> {
+        match self.tables_of(format).as_slice() {
+            [table] => Ok(*table),
+            [] => Err(format!("There's no {} chunk in the folder", format)),
+            _ => Err(format!("There's more than one {} chunk in the folder", format))
+        }
+    }
+
+    /// The first entry of a format matching `filter`, and the table it's in
+    fn find(&self, format: NuccBinaryType, filter: impl Fn(&Value) -> bool) -> Option<(usize, Value)> {
+        self.find_all(format, filter).map(|(table, entries)| (table, entries[0].clone()))
+    }
+
+    /// Every entry matching `filter` in the first table of the format that has any
+    fn find_all(&self, format: NuccBinaryType, filter: impl Fn(&Value) -> bool) -> Option<(usize, Vec<Value>)> {
+        self.tables_of(format).into_iter().find_map(|table| {
+            let found = entries(&self.tables[table].value)?.iter().filter(|entry| filter(entry)).cloned().collect::<Vec<_>>();
+            (!found.is_empty()).then_some((table, found))
+        })
+    }
+
+    /// Adds an entry after setting the spec's fields for its format
+    fn push(&mut self, table: usize, mut entry: Value, fields: &[&BTreeMap<String, Map<String, Value>>]) -> Result<(), String> {
+        let format = self.tables[table].format;
+
+        for fields in fields.iter().filter_map(|fields| fields.get(&format.to_string())) {
+            for (path, value) in fields {
+                set_path(&mut entry, path, value.clone()).map_err(|err| format!("{}: {}", format, err))?;
+            }
+        }
+
+        let value = &mut self.tables[table].value;
+        let entries = value["entries"].as_array_mut().unwrap();
+        entries.push(entry);
+
+        let entry_count = entries.len();
+
+        if let Some(count) = value.get_mut("entry_count") {
+            *count = entry_count.into();
+        }
+
+        self.tables[table].changed = true;
+        self.added.push((table, entry_count - 1));
+
+        Ok(())
+    }
+
+    fn assign_ids(&mut self, table: usize) -> Result<(), String> {
+        let Table { format, chunk, value, .. } = &mut self.tables[table];
+        let range = self.project.id_range(*format)?;
+
+        let assigned = assign_ids(*format, chunk, value, &mut self.ids, range).map_err(|err| format!("{}: {}", chunk, err))?;
+        self.assigned.extend(assigned);
+
+        Ok(())
+    }
+
+    fn skip(&mut self, format: NuccBinaryType, reason: &str) {
+        self.skipped.push(format!("{} {}", format, reason));
+    }
+
+    /// Hands the edited tables back, keying the new entries now that their ids are known
+    fn finish(self, tables: &mut [Table], ids: &mut Ids) -> Changes {
+        let describe = |&(table, entry): &(usize, usize)| {
+            let table = &self.tables[table];
+
+            Added {
+                file: table.file.display().to_string(),
+                chunk: table.chunk.clone(),
+                format: table.format,
+                entry: entry_key(table.format, &entries(&table.value).unwrap()[entry], entry)
+            }
+        };
+
+        let added = self.added.iter().map(describe).collect();
+        let edited = self.edited.iter().map(describe).collect();
+
+        tables.clone_from_slice(&self.tables);
+        *ids = self.ids;
+
+        Changes { added, edited, ids: self.assigned, skipped: self.skipped, warnings: self.warnings }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,375 @@
+//! Other views of `BodAcc` rotations, which are stored as three f32 Euler angles.
+//!
+//! The game doesn't document them, so they're taken as radians applied in X, Y, Z order (`R = Rz * Ry * Rx`).
+//! Every view is written with the shortest decimals that convert back to the exact same f32s,
+//! and entries that can't be (e.g. angles past 180 degrees in a quaternion, or a negative scale in a matrix) keep their raw fields.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+
+pub const DEGREES_FIELD: &str = "rotation_degrees";
+pub const QUATERNION_FIELD: &str = "rotation_quaternion";
+
+/// Location, rotation and scale as a row major 4x4 matrix, `T * R * S`
+pub const MATRIX_FIELD: &str = "matrix";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationView {
+    /// `rotation_degrees: [x, y, z]`
+    Degrees,
+
+    /// `rotation_quaternion: [x, y, z, w]`
+    Quaternion,
+
+    /// `matrix` in place of `location`, `rotation` and `scale`
+    Matrix
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Transform {
+    location: [f32; 3],
+    rotation: [f32; 3],
+    scale: [f32; 3]
+}
+
+
+impl FromStr for RotationView {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "degrees" => Ok(RotationView::Degrees),
+            "quaternion" => Ok(RotationView::Quaternion),
+            "matrix" => Ok(RotationView::Matrix),
+            _ => Err(format!("Unknown rotation view: {}, expected degrees, quaternion or matrix", s))
+        }
+    }
+}
+
+impl fmt::Display for RotationView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RotationView::Degrees => "degrees",
+            RotationView::Quaternion => "quaternion",
+            RotationView::Matrix => "matrix"
+        };
+
+        f.pad(name)
+    }
+}
+
+impl RotationView {
+    fn field(&self) -> &'static str {
+        match self {
+            RotationView::Degrees => DEGREES_FIELD,
+            RotationView::Quaternion => QUATERNION_FIELD,
+            RotationView::Matrix => MATRIX_FIELD
+        }
+    }
+
+    /// The stored fields the view stands in for
+    fn replaces(&self) -> &'static [&'static str] {
+        match self {
+            RotationView::Degrees | RotationView::Quaternion => &["rotation"],
+            RotationView::Matrix => &["location", "rotation", "scale"]
+        }
+    }
+
+    fn encode(&self, transform: &Transform) -> Vec<f64> {
+        let rotation = transform.rotation.map(f64::from);
+
+        match self {
+            RotationView::Degrees => rotation.iter().map(|angle| angle.to_degrees()).collect(),
+            RotationView::Quaternion => euler_to_quaternion(rotation).to_vec(),
+            RotationView::Matrix => transform_to_matrix(transform).concat()
+        }
+    }
+
+    /// The transform with the view's values in place of the fields it replaces
+    fn decode(&self, values: &[f64], transform: &Transform) -> Transform {
+        match self {
+            RotationView::Degrees => Transform { rotation: [0, 1, 2].map(|axis| values[axis].to_radians() as f32), ..*transform },
+            RotationView::Quaternion => Transform { rotation: quaternion_to_euler([values[0], values[1], values[2], values[3]]).map(|angle| angle as f32), ..*transform },
+            RotationView::Matrix => matrix_to_transform(values)
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            RotationView::Degrees => 3,
+            RotationView::Quaternion => 4,
+            RotationView::Matrix => 16
+        }
+    }
+}
+
+
+/// Replaces every BodAcc entry's rotation with the view, returning the entries kept raw because they wouldn't convert back exactly
+pub fn to_view(value: &mut Value, view: RotationView) -> Vec<usize> {
+    let mut raw = Vec::new();
+
+    for (index, entry) in value.get_mut("entries").and_then(Value::as_array_mut).into_iter().flatten().enumerate() {
+        let map = match entry.as_object_mut() {
+            Some(map) => map,
+            None => continue
+        };
+
+        let transform = match read_transform(map) {
+            Some(transform) => transform,
+            None => {
+                raw.push(index);
+                continue;
+            }
+        };
+
+        let mut values = view.encode(&transform);
+
+        if values.iter().any(|value| !value.is_finite()) || !same(&view.decode(&values, &transform), &transform) {
+            raw.push(index);
+            continue;
+        }
+
+        shorten(&mut values, |values| same(&view.decode(values, &transform), &transform));
+
+        // The view goes where the first field it replaces was, so the entry keeps its field order
+        let position = map.keys().position(|key| view.replaces().contains(&key.as_str())).unwrap_or(map.len());
+
+        for field in view.replaces() {
+            map.shift_remove(*field);
+        }
+
+        let values = match view {
+            RotationView::Matrix => Value::Array(values.chunks(4).map(|row| row.to_vec().into()).collect()),
+            _ => values.into()
+        };
+
+        map.shift_insert(position, view.field().to_string(), values);
+    }
+
+    raw
+}
+
+/// Whether a BodAcc was unpacked with a rotation view
+pub fn uses_views(value: &Value) -> bool {
+    value.get("entries").and_then(Value::as_array).into_iter().flatten()
+        .any(|entry| [DEGREES_FIELD, QUATERNION_FIELD, MATRIX_FIELD].iter().any(|field| entry.get(*field).is_some()))
+}
+
+/// Converts every view back to the stored fields. Entries can mix views and raw fields, but not have both for the same field.
+pub fn from_views(value: &mut Value) -> Result<(), String> {
+    for (index, entry) in value.get_mut("entries").and_then(Value::as_array_mut).into_iter().flatten().enumerate() {
+        let map = match entry.as_object_mut() {
+            Some(map) => map,
+            None => continue
+        };
+
+        for view in [RotationView::Degrees, RotationView::Quaternion, RotationView::Matrix] {
+            // The fields go back where the view was, so the entry keeps its field order
+            let position = match map.keys().position(|key| key == view.field()) {
+                Some(position) => position,
+                None => continue
+            };
+
+            let values = map.shift_remove(view.field()).unwrap();
+
+            let values = match &values {
+                Value::Array(rows) if view == RotationView::Matrix => rows.iter().flat_map(|row| row.as_array().cloned().unwrap_or_default()).collect(),
+                Value::Array(values) => values.clone(),
+                _ => Vec::new()
+            };
+
+            let values = values.iter().map(Value::as_f64).collect::<Option<Vec<_>>>()
+                .filter(|values| values.len() == view.len())
+                .ok_or_else(|| format!("entry {}, {} needs {} numbers", index, view.field(), view.len()))?;
+
+            if let Some(field) = view.replaces().iter().find(|field| map.contains_key(**field)) {
+                return Err(format!("entry {} has both {} and {}, remove one", index, view.field(), field));
+            }
+
+            // The fields a view doesn't replace are still in the entry
+            let current = Transform {
+                location: read_floats(map, "location").unwrap_or_default(),
+                rotation: [0.0; 3],
+                scale: read_floats(map, "scale").unwrap_or_default()
+            };
+
+            let transform = view.decode(&values, &current);
+
+            let fields = [("location", transform.location), ("rotation", transform.rotation), ("scale", transform.scale)];
+
+            for (offset, (field, floats)) in fields.into_iter().filter(|(field, _)| view.replaces().contains(field)).enumerate() {
+                if floats.iter().any(|float| !float.is_finite()) {
+                    return Err(format!("entry {}, {} doesn't convert back to a {}", index, view.field(), field));
+                }
+
+                map.shift_insert(position + offset, field.to_string(), write_floats(floats));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+
+/// Compares the bits, so -0 and 0 aren't the same
+fn same(a: &Transform, b: &Transform) -> bool {
+    let bits = |transform: &Transform| [transform.location, transform.rotation, transform.scale].concat().iter().map(|float| float.to_bits()).collect::<Vec<_>>();
+    bits(a) == bits(b)
+}
+
+fn read_transform(map: &Map<String, Value>) -> Option<Transform> {
+    Some(Transform {
+        location: read_floats(map, "location")?,
+        rotation: read_floats(map, "rotation")?,
+        scale: read_floats(map, "scale")?
+    })
+}
+
+/// `None` for floats written as hex, which only the raw fields can hold
+fn read_floats(map: &Map<String, Value>, field: &str) -> Option<[f32; 3]> {
+    let floats = map.get(field)?.as_array()?;
+
+    match floats.as_slice() {
+        [x, y, z] => Some([x.as_f64()? as f32, y.as_f64()? as f32, z.as_f64()? as f32]),
+        _ => None
+    }
+}
+
+fn write_floats(floats: [f32; 3]) -> Value {
+    // Through the f32's shortest decimal, so the json holds e.g. 0.1 and not 0.10000000149011612
+    floats.iter().map(|float| Value::from(float.to_string().parse::<f64>().unwrap())).collect()
+}
+
+/// Rounds every value to the fewest decimals that still convert back
+fn shorten(values: &mut [f64], converts_back: impl Fn(&[f64]) -> bool) {
+    for index in 0..values.len() {
+        let exact = values[index];
+
+        for decimals in 0..17 {
+            values[index] = format!("{:.*}", decimals, exact).parse().unwrap();
+
+            if converts_back(values) {
+                break;
+            }
+
+            values[index] = exact;
+        }
+    }
+}
+
+
+fn euler_to_quaternion([x, y, z]: [f64; 3]) -> [f64; 4] {
+    let (sx, cx) = (x / 2.0).sin_cos();
+    let (sy, cy) = (y / 2.0).sin_cos();
+    let (sz, cz) = (z / 2.0).sin_cos();
+
+    [
+        sx * cy * cz - cx * sy * sz,
+        cx * sy * cz + sx * cy * sz,
+        cx * cy * sz - sx * sy * cz,
+        cx * cy * cz + sx * sy * sz
+    ]
+}
+
+fn quaternion_to_euler([x, y, z, w]: [f64; 4]) -> [f64; 3] {
+    [
+        (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y)),
+        (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin(),
+        (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z))
+    ]
+}
+
+fn transform_to_matrix(transform: &Transform) -> [[f64; 4]; 4] {
+    let [x, y, z] = transform.rotation.map(f64::from);
+    let (sx, cx) = x.sin_cos();
+    let (sy, cy) = y.sin_cos();
+    let (sz, cz) = z.sin_cos();
+
+    let rotation = [
+        [cy * cz, sx * sy * cz - cx * sz, cx * sy * cz + sx * sz],
+        [cy * sz, sx * sy * sz + cx * cz, cx * sy * sz - sx * cz],
+        [-sy, sx * cy, cx * cy]
+    ];
+
+    let mut matrix = [[0.0, 0.0, 0.0, 1.0]; 4];
+
+    for row in 0..3 {
+        for column in 0..3 {
+            matrix[row][column] = rotation[row][column] * f64::from(transform.scale[column]);
+        }
+
+        matrix[row][3] = f64::from(transform.location[row]);
+    }
+
+    matrix
+}
+
+fn matrix_to_transform(values: &[f64]) -> Transform {
+    let at = |row: usize, column: usize| values[row * 4 + column];
+
+    let scale = [0, 1, 2].map(|column| (0..3).map(|row| at(row, column).powi(2)).sum::<f64>().sqrt());
+    let rotation = |row: usize, column: usize| at(row, column) / scale[column];
+
+    Transform {
+        location: [0, 1, 2].map(|row| at(row, 3) as f32),
+        rotation: [
+            rotation(2, 1).atan2(rotation(2, 2)) as f32,
+            (-rotation(2, 0)).clamp(-1.0, 1.0).asin() as f32,
+            rotation(1, 0).atan2(rotation(0, 0)) as f32
+        ],
+        scale: scale.map(|scale| scale as f32)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn view_round_trip_test() {
+        // (location, rotation, scale)
+        let cases = [
+            ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [1.0, 1.0, 1.0]),
+            ([1.5, -2.25, 0.1], [0.1, -0.2, 0.3], [1.0, 1.0, 1.0]),
+            ([-0.0, 3.0, 7.125], [1.5707964, 0.0, -1.5707964], [0.5, 2.0, 1.25]),
+            ([0.0, 0.0, 0.0], [-0.0, 0.0, 0.0], [1.0, 1.0, 1.0]),
+            ([12.0, -0.5, 0.333], [3.0, 2.9, -3.1], [1.0, 1.0, 1.0]),
+            ([0.0, 1.0, 0.0], [0.7, 0.2, 0.4], [-1.0, 1.0, 1.0]),
+        ];
+
+        for view in [RotationView::Degrees, RotationView::Quaternion, RotationView::Matrix] {
+            for (location, rotation, scale) in cases {
+                let entry = json!({ "bone_name": "head", "location": location, "rotation": rotation, "scale": scale });
+                let mut value = json!({ "entries": [entry] });
+
+                let raw = to_view(&mut value, view);
+
+                // Through the json text, the same as an unpacked file
+                let mut value = serde_json::from_str(&value.to_string()).unwrap();
+                from_views(&mut value).unwrap();
+
+                let map = value["entries"][0].as_object().unwrap();
+                let bits = |floats: [f32; 3]| floats.map(f32::to_bits);
+
+                assert_eq!(bits(read_floats(map, "location").unwrap()), bits(location), "{} {:?}", view, location);
+                assert_eq!(bits(read_floats(map, "rotation").unwrap()), bits(rotation), "{} {:?} raw {:?}", view, rotation, raw);
+                assert_eq!(bits(read_floats(map, "scale").unwrap()), bits(scale), "{} {:?}", view, scale);
+                assert_eq!(map.keys().collect::<Vec<_>>(), ["bone_name", "location", "rotation", "scale"]);
+            }
+        }
+    }
+
+    #[test]
+    fn conflicting_view_test() {
+        let mut value = json!({ "entries": [{ "rotation_degrees": [0.0, 90.0, 0.0], "rotation": [0.0, 0.0, 0.0] }] });
+        assert!(from_views(&mut value).is_err());
+
+        let mut value = json!({ "entries": [{ "rotation_quaternion": [0.0, 0.0, 1.0] }] });
+        assert!(from_views(&mut value).is_err());
+    }
+}
\ No newline at end of file
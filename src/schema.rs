@@ -0,0 +1,175 @@
+//! JSON Schemas of the unpacked json, with the known meaning of each field as its description.
+//! They describe the default export, options that change the json's shape (e.g. `--group-render-settings`) aren't covered.
+
+use serde_json::{json, Map, Value};
+
+use crate::NuccBinaryType;
+use crate::ids::id_references;
+use crate::nucc_binary::fields::{describe, FieldMeta, FLAG};
+use crate::nucc_binary::layout::FieldType;
+
+
+pub const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The schema of a format's unpacked json, `None` for formats that aren't unpacked to json
+pub fn schema(nucc_binary_type: NuccBinaryType) -> Option<Value> {
+    let layout = nucc_binary_type.layout()?;
+    let info = nucc_binary_type.info();
+    let fields = describe(nucc_binary_type);
+
+    // Id fields and the fields referring to them can hold "auto" placeholders
+    let mut auto = id_references(nucc_binary_type).into_iter().map(|(field, _)| field).collect::<Vec<_>>();
+    auto.extend(nucc_binary_type.id_field());
+
+    let mut properties = Map::new();
+    let mut entry = Map::new();
+
+    for field in &fields {
+        let target = if field.header { &mut properties } else { &mut entry };
+        insert(target, field, &auto);
+    }
+
+    let entry = object(entry);
+
+    match layout.entry_count {
+        Some(_) => properties.insert("entries".to_string(), json!({ "type": "array", "items": entry })),
+        None => properties.insert("entry".to_string(), entry)
+    };
+
+    let mut description = format!("An unpacked {} chunk, see src/nucc_binary/{}", nucc_binary_type, info.source);
+
+    if let Some(credit) = info.credit {
+        description.push_str(&format!(". Format reversed by {}", credit.name));
+    }
+
+    let mut schema = object(properties);
+    schema["$schema"] = DRAFT.into();
+    schema["title"] = nucc_binary_type.to_string().into();
+    schema["description"] = description.into();
+
+    Some(schema)
+}
+
+
+/// Adds a field at its json path, e.g. `location[0]` as an array and `render_settings.rot1P` in an object
+fn insert(properties: &mut Map<String, Value>, field: &FieldMeta, auto: &[&str]) {
+    let path = match &field.json {
+        Some(path) => path.as_str(),
+        None => return
+    };
+
+    let mut schema = field_schema(field, auto.contains(&path));
+
+    let (parent, name) = match path.split_once('.') {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, path)
+    };
+
+    let properties = match parent {
+        Some(parent) => properties.entry(parent)
+            .or_insert_with(|| object(Map::new()))["properties"]
+            .as_object_mut()
+            .unwrap(),
+        None => properties
+    };
+
+    match name.split_once('[') {
+        Some((base, _)) => {
+            let description = schema.as_object_mut().unwrap().shift_remove("description");
+            let array = properties.entry(base).or_insert_with(|| json!({ "type": "array", "items": schema, "minItems": 0, "maxItems": 0 }));
+
+            if let Some(description) = description {
+                array["description"] = description;
+            }
+
+            let count = array["maxItems"].as_u64().unwrap() + 1;
+            array["minItems"] = count.into();
+            array["maxItems"] = count.into();
+        }
+
+        None => {
+            properties.insert(name.to_string(), schema);
+        }
+    }
+}
+
+fn field_schema(field: &FieldMeta, auto: bool) -> Value {
+    let mut schema = match field.field_type {
+        FieldType::U8 => integer(u8::MIN.into(), u8::MAX.into()),
+        FieldType::I8 => integer(i8::MIN.into(), i8::MAX.into()),
+        FieldType::U16 => integer(u16::MIN.into(), u16::MAX.into()),
+        FieldType::I16 => integer(i16::MIN.into(), i16::MAX.into()),
+        FieldType::U32 | FieldType::U32Be => integer(u32::MIN.into(), u32::MAX.into()),
+        FieldType::I32 => integer(i32::MIN.into(), i32::MAX.into()),
+        FieldType::U64 => json!({ "type": "integer", "minimum": 0 }),
+        FieldType::I64 => json!({ "type": "integer" }),
+
+        // Floats JSON can't hold are written as their bits in hex
+        FieldType::F32 => json!({ "anyOf": [{ "type": "number" }, { "type": "string", "pattern": "^0x[0-9A-Fa-f]{8}$" }] }),
+
+        FieldType::Bytes(size) => json!({ "anyOf": [
+            { "type": "string", "pattern": format!("^([0-9A-Fa-f]{{2}}){{{}}}$", size) },
+            { "type": "array", "items": integer(0, 255), "minItems": size, "maxItems": size }
+        ] }),
+
+        // Strings with leftover bytes after their terminator are written as an object
+        FieldType::Str(_) => json!({ "anyOf": [
+            { "type": "string" },
+            { "type": "object", "properties": { "text": { "type": "string" }, "trailing": { "type": "string" } }, "required": ["text"], "additionalProperties": false }
+        ] }),
+
+        FieldType::Ptr | FieldType::Ptr32 => json!({ "type": "string" }),
+
+        FieldType::Array { count, .. } => {
+            let mut sub_entry = Map::new();
+
+            for sub_field in &field.fields {
+                insert(&mut sub_entry, sub_field, &[]);
+            }
+
+            json!({ "type": "array", "items": object(sub_entry), "minItems": count, "maxItems": count })
+        }
+    };
+
+    if field.values == FLAG {
+        schema = json!({ "anyOf": [{ "type": "boolean" }, schema] });
+    } else if !field.values.is_empty() {
+        let names = field.values.iter().map(|(_, name)| *name).collect::<Vec<_>>();
+        schema = json!({ "anyOf": [{ "enum": names }, schema] });
+    }
+
+    if auto {
+        let placeholder = json!({ "type": "string", "pattern": "^auto(:.+)?$" });
+
+        match schema.get_mut("anyOf").and_then(Value::as_array_mut) {
+            Some(any_of) => any_of.push(placeholder),
+            None => schema = json!({ "anyOf": [schema, placeholder] })
+        }
+    }
+
+    if let Some(description) = description(field) {
+        schema["description"] = description.into();
+    }
+
+    schema
+}
+
+/// The field's meaning followed by its known values, e.g. `Type of move. 1: neutral, 2: up, 3: down, 4: throw`
+pub fn description(field: &FieldMeta) -> Option<String> {
+    let values = field.values.iter().map(|(value, name)| format!("{}: {}", value, name)).collect::<Vec<_>>().join(", ");
+
+    match (field.meaning, values.is_empty()) {
+        (Some(meaning), true) => Some(meaning.to_string()),
+        (Some(meaning), false) => Some(format!("{}. {}", meaning, values)),
+        (None, false) => Some(values),
+        (None, true) => None
+    }
+}
+
+fn integer(minimum: i64, maximum: i64) -> Value {
+    json!({ "type": "integer", "minimum": minimum, "maximum": maximum })
+}
+
+fn object(properties: Map<String, Value>) -> Value {
+    json!({ "type": "object", "properties": properties })
+}
\ No newline at end of file
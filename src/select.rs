@@ -0,0 +1,368 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::diff::entries;
+
+
+/// A page of the character select screen
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Page {
+    pub page_index: u64,
+
+    /// Every slot from 0 to the last used one, including the empty ones
+    pub slots: Vec<Slot>
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Slot {
+    pub slot_index: u64,
+    pub costumes: Vec<Costume>,
+
+    /// More than one entry has the same costume slot
+    pub duplicate: bool
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Costume {
+    pub costume_slot_index: u64,
+    pub searchcode: String,
+    pub char_name: String,
+    pub entry: usize
+}
+
+/// A slot on a page, written `<page>:<slot>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotRef {
+    pub page_index: u64,
+    pub slot_index: u64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotEdit {
+    /// Moves every costume of a slot, closing the gap it leaves and making room where it goes
+    Move { from: SlotRef, to: SlotRef },
+    Swap { a: SlotRef, b: SlotRef },
+
+    /// Makes room for an empty slot, moving the slots from there on one to the right
+    Insert { at: SlotRef }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Moved {
+    pub searchcode: String,
+    pub before: String,
+    pub after: String
+}
+
+
+impl Slot {
+    pub fn is_empty(&self) -> bool {
+        self.costumes.is_empty()
+    }
+}
+
+impl FromStr for SlotRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |index: &str| index.parse::<u64>().map_err(|_| format!("Invalid slot {}, expected <page>:<slot>", s));
+
+        match s.split_once(':') {
+            Some((page_index, slot_index)) => Ok(SlotRef { page_index: parse(page_index)?, slot_index: parse(slot_index)? }),
+            None => Err(format!("Invalid slot {}, expected <page>:<slot>", s))
+        }
+    }
+}
+
+impl fmt::Display for SlotRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.page_index, self.slot_index)
+    }
+}
+
+
+/// The pages of an unpacked CharacterSelectParam
+pub fn layout(value: &Value) -> Vec<Page> {
+    let mut pages: BTreeMap<u64, BTreeMap<u64, Vec<Costume>>> = BTreeMap::new();
+
+    for (entry, slot) in entries(value).into_iter().flatten().enumerate() {
+        let index = |field: &str| slot[field].as_u64().unwrap_or_default();
+
+        pages.entry(index("page_index")).or_default()
+            .entry(index("slot_index")).or_default()
+            .push(Costume {
+                costume_slot_index: index("costume_slot_index"),
+                searchcode: slot["searchcode"].as_str().unwrap_or_default().to_string(),
+                char_name: slot["char_name"].as_str().unwrap_or_default().to_string(),
+                entry
+            });
+    }
+
+    pages.into_iter()
+        .map(|(page_index, mut slots)| {
+            let last = slots.keys().last().copied().unwrap_or_default();
+
+            let slots = (0..=last)
+                .map(|slot_index| {
+                    let mut costumes = slots.remove(&slot_index).unwrap_or_default();
+                    costumes.sort_by_key(|costume| costume.costume_slot_index);
+
+                    let duplicate = costumes.windows(2).any(|pair| pair[0].costume_slot_index == pair[1].costume_slot_index);
+                    Slot { slot_index, costumes, duplicate }
+                })
+                .collect();
+
+            Page { page_index, slots }
+        })
+        .collect()
+}
+
+/// Moves, swaps or inserts slots, shifting the other slots of the pages involved
+pub fn edit_slots(value: &mut Value, edit: &SlotEdit) -> Result<Vec<Moved>, String> {
+    let entries = value.get_mut("entries").and_then(Value::as_array_mut).ok_or("This isn't a CharacterSelectParam")?;
+    let before = entries.clone();
+
+    let position = |entry: &Value| SlotRef {
+        page_index: entry["page_index"].as_u64().unwrap_or_default(),
+        slot_index: entry["slot_index"].as_u64().unwrap_or_default()
+    };
+
+    let members = |entries: &[Value], slot: SlotRef| entries.iter()
+        .enumerate()
+        .filter(|(_, entry)| position(entry) == slot)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    // Moves the slots of a page from `from` on by `offset`
+    let shift = |entries: &mut Vec<Value>, page_index: u64, from: u64, offset: i64| {
+        for entry in entries.iter_mut() {
+            let slot = position(entry);
+
+            if slot.page_index == page_index && slot.slot_index >= from {
+                entry["slot_index"] = (slot.slot_index as i64 + offset).into();
+            }
+        }
+    };
+
+    let place = |entries: &mut Vec<Value>, indices: &[usize], slot: SlotRef| {
+        for &index in indices {
+            entries[index]["page_index"] = slot.page_index.into();
+            entries[index]["slot_index"] = slot.slot_index.into();
+        }
+    };
+
+    match *edit {
+        SlotEdit::Move { from, to } => {
+            let moving = members(entries, from);
+
+            if moving.is_empty() {
+                return Err(format!("Slot {} is empty", from));
+            }
+
+            // Parked on a page that doesn't exist while the others shift
+            place(entries, &moving, SlotRef { page_index: u64::MAX, slot_index: 0 });
+            shift(entries, from.page_index, from.slot_index + 1, -1);
+            shift(entries, to.page_index, to.slot_index, 1);
+            place(entries, &moving, to);
+        }
+
+        SlotEdit::Swap { a, b } => {
+            let (in_a, in_b) = (members(entries, a), members(entries, b));
+
+            if in_a.is_empty() && in_b.is_empty() {
+                return Err(format!("Slots {} and {} are both empty", a, b));
+            }
+
+            place(entries, &in_a, b);
+            place(entries, &in_b, a);
+        }
+
+        SlotEdit::Insert { at } => shift(entries, at.page_index, at.slot_index, 1)
+    }
+
+    let describe = |entry: &Value| format!("{}:{}", position(entry), entry["costume_slot_index"]);
+
+    Ok(before.iter()
+        .zip(entries.iter())
+        .filter(|(old, new)| position(old) != position(new))
+        .map(|(old, new)| Moved {
+            searchcode: new["searchcode"].as_str().unwrap_or_default().to_string(),
+            before: describe(old),
+            after: describe(new)
+        })
+        .collect())
+}
+
+
+/// Each page as a grid of `columns` slots, with the char_name and searchcode of every costume
+pub fn render_text(pages: &[Page], columns: usize) -> String {
+    const WIDTH: usize = 20;
+
+    let mut text = String::new();
+
+    for page in pages {
+        text.push_str(&format!("Page {}\n", page.page_index));
+
+        for row in page.slots.chunks(columns.max(1)) {
+            let cells = row.iter().map(cell_lines).collect::<Vec<_>>();
+            let height = cells.iter().map(Vec::len).max().unwrap_or_default();
+
+            text.push_str(&format!("+{}\n", format!("{}+", "-".repeat(WIDTH)).repeat(row.len())));
+
+            for line in 0..height {
+                text.push('|');
+
+                for cell in &cells {
+                    let content = cell.get(line).map(String::as_str).unwrap_or_default();
+                    text.push_str(&format!("{:<width$}|", truncate(content, WIDTH), width = WIDTH));
+                }
+
+                text.push('\n');
+            }
+
+            text.push_str(&format!("+{}\n", format!("{}+", "-".repeat(WIDTH)).repeat(row.len())));
+        }
+
+        text.push('\n');
+    }
+
+    text
+}
+
+fn cell_lines(slot: &Slot) -> Vec<String> {
+    let marker = match (slot.is_empty(), slot.duplicate) {
+        (true, _) => " (empty)",
+        (_, true) => " DUPLICATE",
+        _ => ""
+    };
+
+    let mut lines = vec![format!("{}{}", slot.slot_index, marker)];
+
+    if let Some(first) = slot.costumes.first() {
+        lines.push(first.char_name.clone());
+    }
+
+    for costume in &slot.costumes {
+        lines.push(format!("{} {}", costume.costume_slot_index, costume.searchcode));
+    }
+
+    lines
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    match text.chars().count() > width {
+        true => format!("{}~", text.chars().take(width - 1).collect::<String>()),
+        false => text.to_string()
+    }
+}
+
+/// The same grids as `render_text` as an SVG, with empty slots dashed and duplicated costume slots in red
+pub fn render_svg(pages: &[Page], columns: usize) -> String {
+    const CELL_WIDTH: usize = 160;
+    const LINE_HEIGHT: usize = 16;
+    const PADDING: usize = 8;
+
+    let columns = columns.max(1);
+    let mut body = String::new();
+    let mut y = PADDING;
+    let mut width = 0;
+
+    for page in pages {
+        y += LINE_HEIGHT;
+        body.push_str(&format!("  <text x=\"{}\" y=\"{}\" font-weight=\"bold\">Page {}</text>\n", PADDING, y, page.page_index));
+        y += PADDING;
+
+        for row in page.slots.chunks(columns) {
+            let cells = row.iter().map(cell_lines).collect::<Vec<_>>();
+            let height = cells.iter().map(Vec::len).max().unwrap_or(1) * LINE_HEIGHT + PADDING;
+
+            for (column, (slot, lines)) in row.iter().zip(&cells).enumerate() {
+                let x = PADDING + column * CELL_WIDTH;
+
+                let style = match (slot.is_empty(), slot.duplicate) {
+                    (true, _) => "fill=\"#ffffff\" stroke=\"#999999\" stroke-dasharray=\"4\"",
+                    (_, true) => "fill=\"#ffd0d0\" stroke=\"#cc0000\"",
+                    _ => "fill=\"#e8eefc\" stroke=\"#334466\""
+                };
+
+                body.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>\n", x, y, CELL_WIDTH - PADDING, height - PADDING, style));
+
+                for (line, text) in lines.iter().enumerate() {
+                    body.push_str(&format!("  <text x=\"{}\" y=\"{}\">{}</text>\n", x + PADDING / 2, y + (line + 1) * LINE_HEIGHT, escape(text)));
+                }
+
+                width = width.max(x + CELL_WIDTH);
+            }
+
+            y += height;
+        }
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"12\">\n{}</svg>\n",
+        width + PADDING, y + PADDING, body
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn edit_slots_test() {
+        let slot = |page_index, slot_index| SlotRef { page_index, slot_index };
+
+        // (edit, page and slot of a, b, b2, c, d and e after it)
+        let cases = [
+            // Same page, to the right and to the left
+            (SlotEdit::Move { from: slot(0, 0), to: slot(0, 2) }, [(0, 2), (0, 0), (0, 0), (0, 1), (1, 0), (1, 1)]),
+            (SlotEdit::Move { from: slot(0, 2), to: slot(0, 0) }, [(0, 1), (0, 2), (0, 2), (0, 0), (1, 0), (1, 1)]),
+
+            // Across pages, closing the gap on one and making room on the other
+            (SlotEdit::Move { from: slot(0, 1), to: slot(1, 1) }, [(0, 0), (1, 1), (1, 1), (0, 1), (1, 0), (1, 2)]),
+            (SlotEdit::Move { from: slot(1, 0), to: slot(0, 0) }, [(0, 1), (0, 2), (0, 2), (0, 3), (0, 0), (1, 0)]),
+
+            (SlotEdit::Swap { a: slot(0, 0), b: slot(1, 1) }, [(1, 1), (0, 1), (0, 1), (0, 2), (1, 0), (0, 0)]),
+            (SlotEdit::Swap { a: slot(0, 2), b: slot(0, 5) }, [(0, 0), (0, 1), (0, 1), (0, 5), (1, 0), (1, 1)]),
+            (SlotEdit::Insert { at: slot(0, 1) }, [(0, 0), (0, 2), (0, 2), (0, 3), (1, 0), (1, 1)]),
+        ];
+
+        for (edit, expected) in cases {
+            let mut value = json!({ "entries": [
+                { "searchcode": "a", "page_index": 0, "slot_index": 0, "costume_slot_index": 0 },
+                { "searchcode": "b", "page_index": 0, "slot_index": 1, "costume_slot_index": 0 },
+                { "searchcode": "b2", "page_index": 0, "slot_index": 1, "costume_slot_index": 1 },
+                { "searchcode": "c", "page_index": 0, "slot_index": 2, "costume_slot_index": 0 },
+                { "searchcode": "d", "page_index": 1, "slot_index": 0, "costume_slot_index": 0 },
+                { "searchcode": "e", "page_index": 1, "slot_index": 1, "costume_slot_index": 0 }
+            ]});
+
+            edit_slots(&mut value, &edit).unwrap();
+
+            let positions = entries(&value).unwrap().iter()
+                .map(|entry| (entry["page_index"].as_u64().unwrap(), entry["slot_index"].as_u64().unwrap()))
+                .collect::<Vec<_>>();
+
+            assert_eq!(positions, expected, "{:?}", edit);
+        }
+    }
+
+    #[test]
+    fn empty_slot_test() {
+        let mut value = json!({ "entries": [{ "searchcode": "a", "page_index": 0, "slot_index": 0, "costume_slot_index": 0 }] });
+        let empty = SlotRef { page_index: 0, slot_index: 1 };
+
+        assert!(edit_slots(&mut value, &SlotEdit::Move { from: empty, to: SlotRef { page_index: 0, slot_index: 0 } }).is_err());
+        assert!(edit_slots(&mut value, &SlotEdit::Swap { a: empty, b: SlotRef { page_index: 1, slot_index: 0 } }).is_err());
+    }
+}
\ No newline at end of file
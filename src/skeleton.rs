@@ -0,0 +1,69 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::NuccBinaryType;
+use crate::chunks::Chunk;
+use crate::diff::{entries, keyed_entries};
+use crate::validate::{Issue, IssueKind};
+
+
+/// The bones of a model, to check what `BodAcc` entries attach to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Skeleton {
+    /// The listing's file name without its extension, e.g. `1nrt`
+    pub name: String,
+    pub bones: BTreeSet<String>
+}
+
+impl Skeleton {
+    /// Reads a listing with one bone name per line, skipping empty lines and `#` comments, or a json array of bone names
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+
+        let bones = match text.trim_start().starts_with('[') {
+            true => serde_json::from_str::<Vec<String>>(&text).map_err(|err| format!("{}: {}", path.display(), err))?.into_iter().collect(),
+            false => text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect()
+        };
+
+        Ok(Skeleton { name, bones })
+    }
+}
+
+
+/// Reports every `BodAcc.bone_name` that isn't a bone of each skeleton, as accessories have to attach to every model wearing them
+pub fn check_bones(chunks: &[(String, Chunk)], skeletons: &[Skeleton]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for (file, chunk) in chunks.iter().filter(|(_, chunk)| chunk.nucc_binary_type == NuccBinaryType::BodAcc) {
+        let value = match chunk.json() {
+            Some(value) => value,
+            None => continue
+        };
+
+        for (key, entry) in keyed_entries(NuccBinaryType::BodAcc, entries(&value).map(Vec::as_slice).unwrap_or_default()) {
+            let bone = entry["bone_name"].as_str().unwrap_or_default();
+
+            for skeleton in skeletons.iter().filter(|skeleton| !skeleton.bones.contains(bone)) {
+                issues.push(Issue {
+                    kind: IssueKind::Dangling,
+                    file: file.clone(),
+                    chunk: chunk.name.clone(),
+                    entry: key.clone(),
+                    field: "bone_name".to_string(),
+                    value: Value::from(bone),
+                    message: format!("{} has no bone with this name", skeleton.name)
+                });
+            }
+        }
+    }
+
+    issues
+}
\ No newline at end of file
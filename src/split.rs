@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::NuccBinaryType;
+use crate::diff::{entries, keyed_entries};
+use crate::nucc_binary::to_json;
+
+
+/// Inside a split chunk's folder, the header fields and the order of the entry files
+pub const INDEX_FILE: &str = "_index.json";
+
+/// Device names that Windows doesn't allow as file names
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9"
+];
+
+/// Splits a chunk's json into an index file and one file per entry, named by the entry's key.
+/// `None` for formats without entries.
+pub fn split(nucc_binary_type: NuccBinaryType, data: &[u8]) -> Option<Vec<(String, Vec<u8>)>> {
+    let value: Value = serde_json::from_slice(data).ok()?;
+    let entries = entries(&value)?;
+
+    let mut files = Vec::new();
+    let mut order: Vec<String> = Vec::new();
+
+    // Lowercased, as two names that only differ by case are the same file on Windows and macOS
+    let mut used = HashSet::from([INDEX_FILE.trim_end_matches(".json").to_lowercase()]);
+
+    for (key, entry) in keyed_entries(nucc_binary_type, entries) {
+        let mut name = file_name(&key);
+
+        // Keys that only differ by case or by characters that can't be in file names, or that would overwrite the index
+        if used.contains(&name.to_lowercase()) {
+            name = (order.len()..)
+                .map(|n| format!("{}_{}", name, n))
+                .find(|name| !used.contains(&name.to_lowercase()))
+                .unwrap();
+        }
+
+        used.insert(name.to_lowercase());
+        files.push((format!("{}.json", name), to_json(entry)));
+        order.push(name);
+    }
+
+    let mut index = value.clone();
+    index.as_object_mut()?.shift_remove("entries");
+    index["order"] = order.into();
+
+    files.insert(0, (INDEX_FILE.to_string(), to_json(&index)));
+
+    Some(files)
+}
+
+/// Rebuilds a chunk's json from a folder written by `split`
+pub fn join(dir: &Path) -> Result<Vec<u8>, String> {
+    let read = |path: &Path| -> Result<Value, String> {
+        let data = fs::read(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+        serde_json::from_slice(&data).map_err(|err| format!("{}: {}", path.display(), err))
+    };
+
+    let mut value = read(&dir.join(INDEX_FILE))?;
+
+    let order = value.as_object_mut()
+        .and_then(|index| index.remove("order"))
+        .and_then(|order| serde_json::from_value::<Vec<String>>(order).ok())
+        .ok_or_else(|| format!("{} has no order list", dir.join(INDEX_FILE).display()))?;
+
+    let entries = order.iter()
+        .map(|name| read(&dir.join(format!("{}.json", name))))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if value.get("entry_count").is_some() {
+        value["entry_count"] = entries.len().into();
+    }
+
+    value["entries"] = Value::Array(entries);
+
+    Ok(to_json(&value))
+}
+
+pub fn is_split(dir: &Path) -> bool {
+    dir.join(INDEX_FILE).is_file()
+}
+
+fn file_name(key: &str) -> String {
+    if key.is_empty() {
+        return String::from("_");
+    }
+
+    let mut name = key.chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c
+        })
+        .collect::<String>();
+
+    // Windows drops dots and spaces at the end of a name, and won't create device names like `con` even with an extension
+    if name.ends_with('.') || name.ends_with(' ') {
+        name.push('_');
+    }
+
+    let stem = name.split('.').next().unwrap_or_default().trim_end().to_uppercase();
+
+    if RESERVED_NAMES.contains(&stem.as_str()) {
+        name.insert(0, '_');
+    }
+
+    name
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_test() {
+        let cases = [
+            ("1nrt", "1nrt"),
+            ("", "_"),
+            ("a/b:c", "a_b_c"),
+            ("con", "_con"),
+            ("Nul.txt", "_Nul.txt"),
+            ("com1 ", "com1 _"),
+            ("LPT9", "_LPT9"),
+            ("console", "console"),
+            ("dots...", "dots..._")
+        ];
+
+        for (key, expected) in cases {
+            assert_eq!(file_name(key), expected, "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn unique_names_test() {
+        // Keys that only differ by case or by replaced characters, and one that would be the index
+        let json = br#"{"entry_count": 6, "entries": [
+            {"substring": "a"}, {"substring": "A"}, {"substring": "a_2"}, {"substring": "a?2"}, {"substring": "_index"}, {"substring": "_con"}
+        ]}"#;
+
+        let files = split(NuccBinaryType::ProhibitedSubstringParam, json).unwrap();
+        let names = files.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(names, [INDEX_FILE, "a.json", "A_1.json", "a_2.json", "a_2_3.json", "_index_4.json", "_con.json"]);
+    }
+}
\ No newline at end of file
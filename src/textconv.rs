@@ -0,0 +1,75 @@
+use serde_json::Value;
+
+use crate::chunks::Chunk;
+use crate::diff::{entries, keyed_entries};
+
+
+/// Renders chunks as text that diffs well line by line, e.g. as a git textconv.
+/// Chunks are sorted by name and entries by key, with one `field = value` line per field.
+pub fn render(chunks: &[Chunk]) -> String {
+    let mut chunks = chunks.iter().collect::<Vec<_>>();
+    chunks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut text = String::new();
+
+    for chunk in chunks {
+        text.push_str(&format!("=== {} ({}) ===\n", chunk.name, chunk.nucc_binary_type));
+
+        match chunk.json() {
+            Some(value) => render_json(chunk, &value, &mut text),
+            None if chunk.extension == ".lua" || chunk.extension == ".xml" => text.push_str(&String::from_utf8_lossy(&chunk.data)),
+            None => text.push_str(&format!("{} bytes\n", chunk.data.len()))
+        }
+
+        text.push('\n');
+    }
+
+    text
+}
+
+fn render_json(chunk: &Chunk, value: &Value, text: &mut String) {
+    let entries = match entries(value) {
+        Some(entries) => entries,
+        None => return render_fields("", value, text)
+    };
+
+    if let Value::Object(map) = value {
+        for (field, value) in map.iter().filter(|(field, _)| *field != "entries") {
+            render_fields(field, value, text);
+        }
+    }
+
+    let mut keyed = keyed_entries(chunk.nucc_binary_type, entries);
+
+    // Formats without key fields keep their order
+    if !chunk.nucc_binary_type.entry_key().is_empty() {
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    for (key, entry) in keyed {
+        text.push_str(&format!("\n[{}]\n", key));
+        render_fields("", entry, text);
+    }
+}
+
+fn render_fields(path: &str, value: &Value, text: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut fields = map.iter().collect::<Vec<_>>();
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            for (field, value) in fields {
+                let path = if path.is_empty() { field.clone() } else { format!("{}.{}", path, field) };
+                render_fields(&path, value, text);
+            }
+        }
+
+        Value::Array(items) if !items.is_empty() && items.iter().any(|item| item.is_object() || item.is_array()) => {
+            for (index, item) in items.iter().enumerate() {
+                render_fields(&format!("{}[{}]", path, index), item, text);
+            }
+        }
+
+        value => text.push_str(&format!("{} = {}\n", path, value))
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::NuccBinaryType;
+use crate::chunks::Chunk;
+use crate::diff::{entries, keyed_entries};
+
+
+/// A field that refers to an entry of another format (or the same one)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reference {
+    pub from: NuccBinaryType,
+    pub field: &'static str,
+    pub to: Target,
+
+    /// Report the target's entries that nothing refers to through this field
+    pub orphans: bool
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Field(NuccBinaryType, &'static str),
+
+    /// An index into the Characode table, starting at 1
+    CharacodeIndex
+}
+
+pub const REFERENCES: &[Reference] = &[
+    Reference { from: NuccBinaryType::PlayerSettingParam, field: "main_player_setting_id", to: Target::Field(NuccBinaryType::PlayerSettingParam, "player_setting_id"), orphans: false },
+    Reference { from: NuccBinaryType::CostumeParam, field: "player_setting_id", to: Target::Field(NuccBinaryType::PlayerSettingParam, "player_setting_id"), orphans: false },
+    Reference { from: NuccBinaryType::CharaPoseParam, field: "player_setting_id", to: Target::Field(NuccBinaryType::PlayerSettingParam, "player_setting_id"), orphans: false },
+    Reference { from: NuccBinaryType::FinalSpSkillCutIn, field: "player_setting_id", to: Target::Field(NuccBinaryType::PlayerSettingParam, "player_setting_id"), orphans: false },
+    Reference { from: NuccBinaryType::FinalSpSkillCutIn, field: "victim_player_setting_id", to: Target::Field(NuccBinaryType::PlayerSettingParam, "player_setting_id"), orphans: false },
+    Reference { from: NuccBinaryType::CharacterSelectParam, field: "searchcode", to: Target::Field(NuccBinaryType::PlayerSettingParam, "searchcode"), orphans: true },
+    Reference { from: NuccBinaryType::CharacterSelectParam, field: "dictionary_link", to: Target::Field(NuccBinaryType::DictionaryCharacterParam, "dictionary_link"), orphans: false },
+    Reference { from: NuccBinaryType::OugiFinishParam, field: "search_code", to: Target::Field(NuccBinaryType::PlayerSettingParam, "searchcode"), orphans: false },
+    Reference { from: NuccBinaryType::CommandListParam, field: "characode", to: Target::Field(NuccBinaryType::Characode, "characode"), orphans: false },
+    Reference { from: NuccBinaryType::PlayerSettingParam, field: "characode_index", to: Target::CharacodeIndex, orphans: true },
+    Reference { from: NuccBinaryType::PlayerSettingParam, field: "main_characode_index", to: Target::CharacodeIndex, orphans: false },
+    Reference { from: NuccBinaryType::CostumeBreakParam, field: "characode_index", to: Target::CharacodeIndex, orphans: false },
+    Reference { from: NuccBinaryType::PlayerDoubleEffectParam, field: "characode_index", to: Target::CharacodeIndex, orphans: false },
+    Reference { from: NuccBinaryType::PlayerIcon, field: "characode_index", to: Target::CharacodeIndex, orphans: false },
+    Reference { from: NuccBinaryType::SkillIndexSettingParam, field: "characode_index", to: Target::CharacodeIndex, orphans: false },
+    Reference { from: NuccBinaryType::SupportActionParam, field: "characode_index", to: Target::CharacodeIndex, orphans: false },
+    Reference { from: NuccBinaryType::SupportSkillRecoverySpeedParam, field: "characode_index", to: Target::CharacodeIndex, orphans: false },
+];
+
+/// A field that has to be unique across every chunk of its format, or only within each chunk
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unique {
+    pub format: NuccBinaryType,
+    pub field: &'static str,
+
+    /// Every chunk repeats the same values, e.g. each language's MessageInfo has the same crc32s
+    pub per_chunk: bool
+}
+
+pub const UNIQUE: &[Unique] = &[
+    Unique { format: NuccBinaryType::Characode, field: "characode", per_chunk: false },
+    Unique { format: NuccBinaryType::MessageInfo, field: "crc32", per_chunk: true },
+    Unique { format: NuccBinaryType::PlayerSettingParam, field: "player_setting_id", per_chunk: false },
+    Unique { format: NuccBinaryType::PlayerSettingParam, field: "searchcode", per_chunk: false },
+];
+
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueKind {
+    /// Refers to an entry that doesn't exist
+    Dangling,
+    Duplicate,
+
+    /// Nothing refers to it, only a warning
+    Orphan
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub file: String,
+    pub chunk: String,
+    pub entry: String,
+    pub field: String,
+    pub value: Value,
+    pub message: String
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            IssueKind::Dangling => "dangling",
+            IssueKind::Duplicate => "duplicate",
+            IssueKind::Orphan => "orphan"
+        };
+
+        write!(f, "{}: {} / {}: entry {}, {} = {}: {}", kind, self.file, self.chunk, self.entry, self.field, self.value, self.message)
+    }
+}
+
+struct Row<'a> {
+    file: &'a str,
+    chunk: &'a str,
+    key: String,
+    entry: &'a Value
+}
+
+
+/// Checks the references between the chunks of every file given, `(file, chunk)`, reporting the ones to missing entries,
+/// duplicated ids and entries nothing refers to. References to formats that weren't loaded aren't checked.
+pub fn validate(chunks: &[(String, Chunk)]) -> Vec<Issue> {
+    let values = chunks.iter().map(|(_, chunk)| chunk.json()).collect::<Vec<_>>();
+    let mut rows: HashMap<NuccBinaryType, Vec<Row>> = HashMap::new();
+
+    for ((file, chunk), value) in chunks.iter().zip(&values) {
+        if let Some(entries) = value.as_ref().and_then(entries) {
+            let format_rows = rows.entry(chunk.nucc_binary_type).or_default();
+
+            for (key, entry) in keyed_entries(chunk.nucc_binary_type, entries) {
+                format_rows.push(Row { file, chunk: &chunk.name, key, entry });
+            }
+        }
+    }
+
+    let mut issues = Vec::new();
+
+    for reference in REFERENCES {
+        let (sources, targets) = match (rows.get(&reference.from), target_values(&rows, reference.to)) {
+            (Some(sources), Some(targets)) => (sources, targets),
+            _ => continue
+        };
+
+        let mut used = HashSet::new();
+
+        for row in sources {
+            let value = match reference_value(&row.entry[reference.field]) {
+                // Characode indices start at 1, 0 is used for no characode
+                Some(value) if reference.to == Target::CharacodeIndex && value == "0" => continue,
+                Some(value) => value,
+                None => continue
+            };
+
+            if targets.contains(&value) {
+                used.insert(value);
+                continue;
+            }
+
+            let message = match reference.to {
+                Target::Field(format, field) => format!("there's no {} with this {}", format, field),
+                Target::CharacodeIndex => format!("past the end of Characode ({} characodes)", targets.len())
+            };
+
+            issues.push(issue(IssueKind::Dangling, row, reference.field, message));
+        }
+
+        if !reference.orphans {
+            continue;
+        }
+
+        let (format, field) = match reference.to {
+            Target::Field(format, field) => (format, field),
+            Target::CharacodeIndex => (NuccBinaryType::Characode, "characode")
+        };
+
+        for (index, row) in rows[&format].iter().enumerate() {
+            let value = match reference.to {
+                Target::Field(..) => reference_value(&row.entry[field]),
+                Target::CharacodeIndex => Some((index + 1).to_string())
+            };
+
+            if value.map_or(false, |value| !used.contains(&value)) {
+                issues.push(issue(IssueKind::Orphan, row, field, format!("no {} refers to it through {}", reference.from, reference.field)));
+            }
+        }
+    }
+
+    for unique in UNIQUE {
+        let mut seen = HashMap::new();
+
+        for row in rows.get(&unique.format).into_iter().flatten() {
+            if let Some(value) = reference_value(&row.entry[unique.field]) {
+                let scope = if unique.per_chunk { Some((row.file, row.chunk)) } else { None };
+
+                if let Some(first) = seen.insert((scope, value), row) {
+                    issues.push(issue(IssueKind::Duplicate, row, unique.field, format!("also used by entry {} in {} / {}", first.key, first.file, first.chunk)));
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+
+/// Every value the target accepts, `None` when its format wasn't loaded
+fn target_values(rows: &HashMap<NuccBinaryType, Vec<Row>>, target: Target) -> Option<HashSet<String>> {
+    match target {
+        Target::Field(format, field) => Some(rows.get(&format)?.iter().filter_map(|row| reference_value(&row.entry[field])).collect()),
+        Target::CharacodeIndex => Some((1..=rows.get(&NuccBinaryType::Characode)?.len()).map(|index| index.to_string()).collect())
+    }
+}
+
+/// The value a field refers to by, `None` for the ones meaning "nothing" (negative numbers and empty strings).
+/// Byte arrays are keyed by their hex, the same as the fields serialized as hex strings.
+fn reference_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Number(number) if number.as_i64().map_or(false, |number| number < 0) => None,
+        Value::Number(number) => Some(number.to_string()),
+        Value::String(text) if text.is_empty() => None,
+        Value::String(text) => Some(text.clone()),
+        Value::Object(map) => map.get("text").and_then(|text| reference_value(text)),
+        Value::Array(bytes) if bytes.is_empty() => None,
+        Value::Array(bytes) => bytes.iter().map(|byte| byte.as_u64().filter(|byte| *byte <= 0xFF).map(|byte| format!("{:02x}", byte))).collect(),
+        _ => None
+    }
+}
+
+fn issue(kind: IssueKind, row: &Row, field: &str, message: String) -> Issue {
+    Issue {
+        kind,
+        file: row.file.to_string(),
+        chunk: row.chunk.to_string(),
+        entry: row.key.clone(),
+        field: field.to_string(),
+        value: row.entry[field].clone(),
+        message
+    }
+}